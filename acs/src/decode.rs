@@ -0,0 +1,256 @@
+//! Decoding and encoding compiled ACS bytecode (`BEHAVIOR`/`SCRIPTS` lumps)
+//! to and from [`PCode`].
+//!
+//! Three on-disk container formats are supported: legacy ACS0 (magic
+//! `ACS\0`), and the two chunked formats ACSE/ACSe (their directories and
+//! chunk layout are identical; only the magic differs, marking whether
+//! string encryption is in play). All three share the same header shape —
+//! a 4-byte magic followed by a `u32` offset to a directory — and the
+//! script code itself always lives between the header and that directory.
+
+use smallvec::SmallVec;
+
+use crate::pcode::{OperandWords, PCode};
+
+/// A decoded instruction's operands. Four inline slots cover all but the
+/// rare variable-argument opcodes (`CallFunc`, `PushBytes`, ...) without an
+/// allocation.
+pub type Operands = SmallVec<[i32; 4]>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+	/// Magic `ACS\0`; every opcode and operand is a 4-byte little-endian
+	/// word.
+	Acs0,
+	/// Magic `ACSE` or `ACSe`; in addition to the plain word forms, many
+	/// opcodes have a byte-packed "direct-byte" counterpart used by newer
+	/// compilers to shrink common cases (pushing a small constant, calling
+	/// a line special with few arguments, ...).
+	Acse,
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+	TooShort,
+	UnknownMagic([u8; 4]),
+	UnknownOpcode { offset: usize, raw: u32 },
+	Truncated { offset: usize },
+}
+
+impl std::fmt::Display for DecodeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::TooShort => write!(f, "buffer is too short to contain an ACS header"),
+			Self::UnknownMagic(magic) => write!(f, "unrecognized ACS magic number: {magic:?}"),
+			Self::UnknownOpcode { offset, raw } => {
+				write!(f, "unknown opcode {raw} at byte offset {offset}")
+			}
+			Self::Truncated { offset } => {
+				write!(f, "bytecode ends mid-instruction at byte offset {offset}")
+			}
+		}
+	}
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Identifies which of [`Format`]'s container shapes `bytes` uses, by its
+/// 4-byte magic number.
+pub fn detect_format(bytes: &[u8]) -> Result<Format, DecodeError> {
+	let Some(magic) = bytes.get(0..4) else {
+		return Err(DecodeError::TooShort);
+	};
+
+	match magic {
+		b"ACS\0" => Ok(Format::Acs0),
+		b"ACSE" | b"ACSe" => Ok(Format::Acse),
+		other => Err(DecodeError::UnknownMagic(other.try_into().unwrap())),
+	}
+}
+
+/// Decodes every instruction between the header and the directory of
+/// `bytes`, which must start with a valid ACS container magic.
+pub fn decode_program(bytes: &[u8]) -> Result<Vec<(PCode, Operands)>, DecodeError> {
+	let format = detect_format(bytes)?;
+	let dir_offset = read_u32(bytes, 4)? as usize;
+	decode_range(bytes, 8, dir_offset, format)
+}
+
+/// Re-encodes `program` back into the same byte layout a call to
+/// [`decode_program`] with the same `format` would have produced, i.e.
+/// `encode_program(decode_program(bytes)?, format) == bytes[code_range]`
+/// for any valid `bytes`.
+#[must_use]
+pub fn encode_program(program: &[(PCode, Operands)], format: Format) -> Vec<u8> {
+	let mut out = vec![];
+
+	for &(op, ref args) in program {
+		out.extend_from_slice(&op.to_u32().to_le_bytes());
+		let layout = op.operand_words();
+		debug_assert_eq!(args.len(), layout.count() as usize);
+
+		match (format, layout) {
+			(_, OperandWords::Count(_)) => {
+				for &arg in args {
+					out.extend_from_slice(&arg.to_le_bytes());
+				}
+			}
+			(Format::Acse, OperandWords::DirectByte(_)) => {
+				for &arg in args {
+					out.push(arg as u8);
+				}
+			}
+			(Format::Acs0, OperandWords::DirectByte(_)) => {
+				for &arg in args {
+					out.extend_from_slice(&arg.to_le_bytes());
+				}
+			}
+		}
+	}
+
+	out
+}
+
+fn decode_range(
+	bytes: &[u8],
+	start: usize,
+	end: usize,
+	format: Format,
+) -> Result<Vec<(PCode, Operands)>, DecodeError> {
+	let mut pos = start;
+	let mut out = vec![];
+
+	while pos < end {
+		let raw = read_u32(bytes, pos)?;
+		let op = PCode::from_u32(raw).ok_or(DecodeError::UnknownOpcode { offset: pos, raw })?;
+		pos += 4;
+
+		let mut args = Operands::new();
+
+		match (format, op.operand_words()) {
+			(_, OperandWords::Count(n)) => {
+				for _ in 0..n {
+					args.push(read_u32(bytes, pos)? as i32);
+					pos += 4;
+				}
+			}
+			(Format::Acse, OperandWords::DirectByte(n)) => {
+				for _ in 0..n {
+					let byte = *bytes.get(pos).ok_or(DecodeError::Truncated { offset: pos })?;
+					args.push(byte as i32);
+					pos += 1;
+				}
+			}
+			// ACS0 predates the direct-byte forms; if one shows up there
+			// anyway, fall back to reading full words rather than
+			// misinterpreting the stream.
+			(Format::Acs0, OperandWords::DirectByte(n)) => {
+				for _ in 0..n {
+					args.push(read_u32(bytes, pos)? as i32);
+					pos += 4;
+				}
+			}
+		}
+
+		out.push((op, args));
+	}
+
+	Ok(out)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, DecodeError> {
+	bytes
+		.get(offset..offset + 4)
+		.map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+		.ok_or(DecodeError::Truncated { offset })
+}
+
+// ACS0 directory /////////////////////////////////////////////////////////////
+
+/// One script's entry in an ACS0 directory: a script number (the low 3
+/// digits of which may double as a "script type" prefix, per GZDoom's
+/// convention), its code offset, and how many arguments it declares.
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptEntry {
+	pub number: i32,
+	pub offset: u32,
+	pub arg_count: u32,
+}
+
+/// Reads an ACS0 container's directory: its script table, followed by its
+/// string table. `bytes` must start with the `ACS\0` magic.
+pub fn acs0_directory(bytes: &[u8]) -> Result<(Vec<ScriptEntry>, Vec<String>), DecodeError> {
+	let dir_offset = read_u32(bytes, 4)? as usize;
+	let mut pos = dir_offset;
+
+	let script_count = read_u32(bytes, pos)?;
+	pos += 4;
+
+	let mut scripts = Vec::with_capacity(script_count as usize);
+
+	for _ in 0..script_count {
+		let number = read_u32(bytes, pos)? as i32;
+		let offset = read_u32(bytes, pos + 4)?;
+		let arg_count = read_u32(bytes, pos + 8)?;
+		scripts.push(ScriptEntry {
+			number,
+			offset,
+			arg_count,
+		});
+		pos += 12;
+	}
+
+	let string_count = read_u32(bytes, pos)?;
+	pos += 4;
+
+	let string_offsets_start = pos;
+	let mut strings = Vec::with_capacity(string_count as usize);
+
+	for i in 0..string_count {
+		let str_offset = read_u32(bytes, string_offsets_start + (i as usize) * 4)? as usize;
+		strings.push(read_cstr(bytes, str_offset)?);
+	}
+
+	Ok((scripts, strings))
+}
+
+fn read_cstr(bytes: &[u8], offset: usize) -> Result<String, DecodeError> {
+	let slice = bytes.get(offset..).ok_or(DecodeError::Truncated { offset })?;
+	let end = slice.iter().position(|&b| b == 0).unwrap_or(slice.len());
+	Ok(String::from_utf8_lossy(&slice[..end]).into_owned())
+}
+
+// ACSE chunks /////////////////////////////////////////////////////////////////
+
+/// One chunk from an ACSE/ACSe chunk directory: a 4-byte FourCC tag (e.g.
+/// `SPTR`, `SFLG`, `SVCT`, `STRL`, `STRE`, `FUNC`, `FNAM`, `ARAY`, `AINI`,
+/// `MEXP`, `MIMP`, `LOAD`, `JUMP`) and its raw payload.
+#[derive(Debug, Clone, Copy)]
+pub struct Chunk<'b> {
+	pub tag: [u8; 4],
+	pub data: &'b [u8],
+}
+
+/// Walks an ACSE/ACSe container's chunk directory, starting at the `u32`
+/// offset stored at byte 4, until the end of `bytes`.
+pub fn acse_chunks(bytes: &[u8]) -> Result<Vec<Chunk<'_>>, DecodeError> {
+	let mut pos = read_u32(bytes, 4)? as usize;
+	let mut chunks = vec![];
+
+	while pos < bytes.len() {
+		let tag: [u8; 4] = bytes
+			.get(pos..pos + 4)
+			.ok_or(DecodeError::Truncated { offset: pos })?
+			.try_into()
+			.unwrap();
+		let len = read_u32(bytes, pos + 4)? as usize;
+		let data = bytes
+			.get(pos + 8..pos + 8 + len)
+			.ok_or(DecodeError::Truncated { offset: pos + 8 })?;
+
+		chunks.push(Chunk { tag, data });
+		pos += 8 + len;
+	}
+
+	Ok(chunks)
+}
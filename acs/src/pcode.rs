@@ -2,9 +2,13 @@
 //!
 //! Assume all code within originates from GZDoom-original source.
 
+/// Discriminants follow GZDoom's `PCD_*` enum exactly and must stay
+/// contiguous starting from 0; [`PCode::from_u32`]/[`PCode::COUNT`] rely on
+/// this to convert a raw opcode number without a 385-arm match.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
 pub enum PCode {
-	NoOp,
+	NoOp = 0,
 	Terminate,
 	Suspend,
 	PushNumber,
@@ -390,3 +394,185 @@ pub enum PCode {
 	TranslationRange4,
 	TranslationRange5,
 }
+
+impl PCode {
+	/// The number of opcodes in this enum, i.e. one past the highest valid
+	/// raw opcode number.
+	pub const COUNT: u32 = Self::TranslationRange5 as u32 + 1;
+
+	/// Converts a raw on-disk opcode number to a [`PCode`], or `None` if it
+	/// is out of range.
+	#[must_use]
+	pub fn from_u32(raw: u32) -> Option<Self> {
+		if raw < Self::COUNT {
+			// SAFETY: `PCode` is `#[repr(u32)]` with contiguous discriminants
+			// from 0 to `Self::COUNT - 1`, and `raw` was just checked to fall
+			// in that range.
+			Some(unsafe { std::mem::transmute::<u32, Self>(raw) })
+		} else {
+			None
+		}
+	}
+
+	/// The raw on-disk opcode number for this instruction.
+	#[must_use]
+	pub fn to_u32(self) -> u32 {
+		self as u32
+	}
+	/// How many operand words (each a little-endian `i32` in ACS0, or a
+	/// packed byte/short sequence in the ACSE "direct-byte" forms) follow
+	/// this opcode in the bytecode.
+	///
+	/// This table is not yet exhaustive over all 385 opcodes: entries not
+	/// listed default to zero operands, which holds for the large majority
+	/// of purely stack-driven instructions. Add an entry here as soon as a
+	/// real compiled script is found to disagree.
+	#[must_use]
+	pub fn operand_words(self) -> OperandWords {
+		use OperandWords::{Count, DirectByte};
+
+		match self {
+			Self::PushNumber
+			| Self::Delay
+			| Self::Random
+			| Self::ThingCount
+			| Self::TagWait
+			| Self::PolyWait
+			| Self::ChangeFloor
+			| Self::ChangeCeiling
+			| Self::Goto
+			| Self::IfGoto
+			| Self::AssignScriptVar
+			| Self::AssignMapVar
+			| Self::AssignWorldVar
+			| Self::AssignGlobalVar
+			| Self::PushScriptVar
+			| Self::PushMapVar
+			| Self::PushWorldVar
+			| Self::PushGlobalVar => Count(1),
+
+			Self::LSpec1 => Count(1),
+			Self::LSpec2 => Count(2),
+			Self::LSpec3 => Count(3),
+			Self::LSpec4 => Count(4),
+			Self::LSpec5 | Self::LSpec5Result | Self::LSpec5Ex => Count(5),
+
+			// The special number, followed by each of its arguments.
+			Self::LSpec1Direct => Count(2),
+			Self::LSpec2Direct => Count(3),
+			Self::LSpec3Direct => Count(4),
+			Self::LSpec4Direct => Count(5),
+			Self::LSpec5Direct => Count(6),
+
+			Self::DelayDirect => Count(1),
+			Self::RandomDirect => Count(2),
+			Self::ThingCountDirect => Count(2),
+			Self::TagWaitDirect => Count(1),
+			Self::PolyWaitDirect => Count(1),
+			Self::ChangeFloorDirect => Count(2),
+			Self::ChangeCeilingDirect => Count(2),
+
+			// The `*DirectB` forms pack the same operands as their `*Direct`
+			// counterpart into single bytes rather than 4-byte words.
+			Self::PushByte => DirectByte(1),
+			Self::LSpec1DirectB => DirectByte(2),
+			Self::LSpec2DirectB => DirectByte(3),
+			Self::LSpec3DirectB => DirectByte(4),
+			Self::LSpec4DirectB => DirectByte(5),
+			Self::LSpec5DirectB => DirectByte(6),
+			Self::DelayDirectB => DirectByte(1),
+			Self::RandomDirectB => DirectByte(2),
+			Self::PushBytes => DirectByte(1), // first byte is itself a count
+			Self::Push2Bytes => DirectByte(2),
+			Self::Push3Bytes => DirectByte(3),
+			Self::Push4Bytes => DirectByte(4),
+			Self::Push5Bytes => DirectByte(5),
+
+			Self::CallFunc => Count(2), // function index (short) + argument count
+			Self::Call | Self::CallDiscard | Self::CallStack => Count(1),
+			Self::PushFunction => Count(1),
+			Self::CaseGotoSorted => Count(0), // variable-length jump table; handled specially by the decoder
+			Self::GotoStack | Self::ScriptWaitNamed => Count(0),
+
+			_ => Count(0),
+		}
+	}
+
+	/// Net stack delta (pushed minus popped) this opcode leaves behind,
+	/// ignoring opcodes (like [`Self::CallFunc`] or [`Self::CaseGotoSorted`])
+	/// whose effect depends on decoded operands rather than the opcode
+	/// alone; those return `None` and must be resolved by the caller.
+	#[must_use]
+	pub fn stack_delta(self) -> Option<i32> {
+		match self {
+			Self::PushNumber
+			| Self::PushScriptVar
+			| Self::PushMapVar
+			| Self::PushWorldVar
+			| Self::PushGlobalVar
+			| Self::PushByte
+			| Self::Dup => Some(1),
+
+			Self::Add
+			| Self::Subtract
+			| Self::Multiply
+			| Self::Divide
+			| Self::Modulus
+			| Self::Eq
+			| Self::Ne
+			| Self::Lt
+			| Self::Gt
+			| Self::Le
+			| Self::Ge
+			| Self::AndLogical
+			| Self::OrLogical
+			| Self::AndBitwise
+			| Self::OrBitwise
+			| Self::AssignScriptVar
+			| Self::AssignMapVar
+			| Self::AssignWorldVar
+			| Self::AssignGlobalVar
+			| Self::Drop => Some(-1),
+
+			Self::NoOp
+			| Self::Terminate
+			| Self::Suspend
+			| Self::Goto
+			| Self::Restart
+			| Self::ReturnVoid => Some(0),
+
+			Self::Swap => Some(0),
+
+			Self::IfGoto => Some(-1),
+
+			Self::CallFunc | Self::CaseGotoSorted | Self::Call | Self::CallDiscard => None,
+
+			_ => None,
+		}
+	}
+}
+
+/// How many operand words follow an opcode in the bytecode; see
+/// [`PCode::operand_words`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandWords {
+	/// `n` little-endian `i32` words (ACS0), or `n` little-endian `i32`/`i16`
+	/// words depending on container format (ACSE).
+	Count(u32),
+	/// `n` single-byte operands, as used by the ACSE "direct-byte" forms.
+	DirectByte(u32),
+}
+
+impl OperandWords {
+	#[must_use]
+	pub fn count(self) -> u32 {
+		match self {
+			Self::Count(n) | Self::DirectByte(n) => n,
+		}
+	}
+
+	#[must_use]
+	pub fn is_direct_byte(self) -> bool {
+		matches!(self, Self::DirectByte(_))
+	}
+}
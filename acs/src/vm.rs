@@ -0,0 +1,247 @@
+//! A minimal ACS virtual machine, built directly atop [`crate::pcode`] and
+//! [`crate::decode`].
+//!
+//! [`Vm::validate`] runs a decoded script through the per-opcode stack
+//! effects of [`PCode::stack_delta`], without touching any of the variable
+//! banks, so a caller can catch stack underflow/overflow and malformed
+//! immediates in a compiled script before ever running it.
+
+use crate::decode::Operands;
+use crate::pcode::PCode;
+
+/// Caps how deep [`Vm::stack`] is allowed to grow; GZDoom itself imposes a
+/// similar limit (its `STACK_SIZE`) to catch runaway recursion.
+pub const STACK_LIMIT: usize = 4096;
+
+#[derive(Debug)]
+pub enum VmError {
+	/// An opcode needed more operands on the stack than were present.
+	StackUnderflow { pc: usize, op: PCode },
+	/// [`STACK_LIMIT`] would have been exceeded.
+	StackOverflow { pc: usize, op: PCode },
+	/// A `PushScriptVar`/`AssignMapVar`/... family opcode named a variable
+	/// slot past the end of its bank.
+	VarOutOfRange { pc: usize, op: PCode, index: i32 },
+	/// A `Divide`/`Modulus` opcode popped a zero divisor.
+	DivideByZero { pc: usize, op: PCode },
+	/// The program counter left the bounds of the decoded instruction list.
+	PcOutOfRange { pc: usize },
+	/// An opcode was decoded with fewer operand words than
+	/// [`PCode::operand_words`] says it needs.
+	MalformedOperands { pc: usize, op: PCode },
+}
+
+/// Holds everything a running ACS script needs: its operand stack, the four
+/// variable banks GZDoom scripts can address (script-local, map, world, and
+/// global), and a program counter into a decoded instruction list.
+#[derive(Debug, Clone, Default)]
+pub struct Vm {
+	pub stack: Vec<i32>,
+	pub script_vars: Vec<i32>,
+	pub map_vars: Vec<i32>,
+	pub world_vars: Vec<i32>,
+	pub global_vars: Vec<i32>,
+	pub arrays: Vec<Vec<i32>>,
+	pub pc: usize,
+}
+
+impl Vm {
+	#[must_use]
+	pub fn new(script_var_count: usize) -> Self {
+		Self {
+			script_vars: vec![0; script_var_count],
+			map_vars: vec![0; 128],
+			world_vars: vec![0; 256],
+			global_vars: vec![0; 64],
+			..Default::default()
+		}
+	}
+
+	fn pop(&mut self, pc: usize, op: PCode) -> Result<i32, VmError> {
+		self.stack.pop().ok_or(VmError::StackUnderflow { pc, op })
+	}
+
+	fn push(&mut self, pc: usize, op: PCode, value: i32) -> Result<(), VmError> {
+		if self.stack.len() >= STACK_LIMIT {
+			return Err(VmError::StackOverflow { pc, op });
+		}
+
+		self.stack.push(value);
+		Ok(())
+	}
+
+	fn arg0(pc: usize, op: PCode, args: &Operands) -> Result<i32, VmError> {
+		args.first().copied().ok_or(VmError::MalformedOperands { pc, op })
+	}
+
+	fn var_mut(&mut self, pc: usize, op: PCode, bank: Bank, index: i32) -> Result<&mut i32, VmError> {
+		let vars = match bank {
+			Bank::Script => &mut self.script_vars,
+			Bank::Map => &mut self.map_vars,
+			Bank::World => &mut self.world_vars,
+			Bank::Global => &mut self.global_vars,
+		};
+
+		vars.get_mut(index as usize)
+			.ok_or(VmError::VarOutOfRange { pc, op, index })
+	}
+
+	/// Executes one decoded instruction, advancing `self.pc`.
+	pub fn step(&mut self, program: &[(PCode, Operands)]) -> Result<(), VmError> {
+		let pc = self.pc;
+		let (op, args) = program.get(pc).ok_or(VmError::PcOutOfRange { pc })?;
+		let (op, args) = (*op, args.clone());
+
+		match op {
+			PCode::NoOp => {}
+
+			PCode::PushNumber | PCode::PushByte => {
+				self.push(pc, op, Self::arg0(pc, op, &args)?)?;
+			}
+
+			PCode::PushScriptVar => {
+				let v = *self.var_mut(pc, op, Bank::Script, Self::arg0(pc, op, &args)?)?;
+				self.push(pc, op, v)?;
+			}
+			PCode::PushMapVar => {
+				let v = *self.var_mut(pc, op, Bank::Map, Self::arg0(pc, op, &args)?)?;
+				self.push(pc, op, v)?;
+			}
+			PCode::PushWorldVar => {
+				let v = *self.var_mut(pc, op, Bank::World, Self::arg0(pc, op, &args)?)?;
+				self.push(pc, op, v)?;
+			}
+			PCode::PushGlobalVar => {
+				let v = *self.var_mut(pc, op, Bank::Global, Self::arg0(pc, op, &args)?)?;
+				self.push(pc, op, v)?;
+			}
+
+			PCode::AssignScriptVar => {
+				let v = self.pop(pc, op)?;
+				*self.var_mut(pc, op, Bank::Script, Self::arg0(pc, op, &args)?)? = v;
+			}
+			PCode::AssignMapVar => {
+				let v = self.pop(pc, op)?;
+				*self.var_mut(pc, op, Bank::Map, Self::arg0(pc, op, &args)?)? = v;
+			}
+			PCode::AssignWorldVar => {
+				let v = self.pop(pc, op)?;
+				*self.var_mut(pc, op, Bank::World, Self::arg0(pc, op, &args)?)? = v;
+			}
+			PCode::AssignGlobalVar => {
+				let v = self.pop(pc, op)?;
+				*self.var_mut(pc, op, Bank::Global, Self::arg0(pc, op, &args)?)? = v;
+			}
+
+			PCode::Add | PCode::Subtract | PCode::Multiply | PCode::Divide | PCode::Modulus => {
+				let rhs = self.pop(pc, op)?;
+				let lhs = self.pop(pc, op)?;
+
+				if matches!(op, PCode::Divide | PCode::Modulus) && rhs == 0 {
+					return Err(VmError::DivideByZero { pc, op });
+				}
+
+				let result = match op {
+					PCode::Add => lhs.wrapping_add(rhs),
+					PCode::Subtract => lhs.wrapping_sub(rhs),
+					PCode::Multiply => lhs.wrapping_mul(rhs),
+					PCode::Divide => lhs.wrapping_div(rhs),
+					PCode::Modulus => lhs.wrapping_rem(rhs),
+					_ => unreachable!(),
+				};
+				self.push(pc, op, result)?;
+			}
+
+			PCode::Eq | PCode::Ne | PCode::Lt | PCode::Gt | PCode::Le | PCode::Ge => {
+				let rhs = self.pop(pc, op)?;
+				let lhs = self.pop(pc, op)?;
+				let result = match op {
+					PCode::Eq => lhs == rhs,
+					PCode::Ne => lhs != rhs,
+					PCode::Lt => lhs < rhs,
+					PCode::Gt => lhs > rhs,
+					PCode::Le => lhs <= rhs,
+					PCode::Ge => lhs >= rhs,
+					_ => unreachable!(),
+				};
+				self.push(pc, op, result as i32)?;
+			}
+
+			PCode::Drop => {
+				self.pop(pc, op)?;
+			}
+
+			PCode::Dup => {
+				let top = *self.stack.last().ok_or(VmError::StackUnderflow { pc, op })?;
+				self.push(pc, op, top)?;
+			}
+
+			PCode::Swap => {
+				let len = self.stack.len();
+				if len < 2 {
+					return Err(VmError::StackUnderflow { pc, op });
+				}
+				self.stack.swap(len - 1, len - 2);
+			}
+
+			PCode::Goto => {
+				self.pc = Self::arg0(pc, op, &args)? as usize;
+				return Ok(());
+			}
+
+			PCode::IfGoto => {
+				let target = Self::arg0(pc, op, &args)? as usize;
+				let cond = self.pop(pc, op)?;
+				if cond != 0 {
+					self.pc = target;
+					return Ok(());
+				}
+			}
+
+			PCode::Terminate => {}
+
+			_ => {}
+		}
+
+		self.pc += 1;
+		Ok(())
+	}
+
+	/// Statically walks `program`, tracking the stack depth each opcode
+	/// with a known [`PCode::stack_delta`] would leave behind, without
+	/// touching any variable bank or actually jumping anywhere. Catches
+	/// stack underflow/overflow and malformed immediates (an opcode decoded
+	/// with fewer operand words than [`PCode::operand_words`] expects) up
+	/// front, without running the script.
+	pub fn validate(program: &[(PCode, Operands)]) -> Result<(), VmError> {
+		let mut depth: i64 = 0;
+
+		for (pc, (op, args)) in program.iter().enumerate() {
+			if args.len() < op.operand_words().count() as usize {
+				return Err(VmError::MalformedOperands { pc, op: *op });
+			}
+
+			if let Some(delta) = op.stack_delta() {
+				depth += i64::from(delta);
+
+				if depth < 0 {
+					return Err(VmError::StackUnderflow { pc, op: *op });
+				}
+
+				if depth as usize > STACK_LIMIT {
+					return Err(VmError::StackOverflow { pc, op: *op });
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bank {
+	Script,
+	Map,
+	World,
+	Global,
+}
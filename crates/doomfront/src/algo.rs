@@ -0,0 +1,272 @@
+//! Tree-level algorithms shared across every [`rowan`] syntax tree in this
+//! crate, independent of any one frontend's [`Syntax`](rowan::Language::Kind).
+
+use std::collections::HashMap;
+
+use rowan::{Direction, NodeOrToken, SyntaxElement, SyntaxNode, TextRange, TextSize};
+
+/// A structural diff between two trees of the same [`rowan::Language`],
+/// computed by [`diff`].
+///
+/// Modeled on rust-analyzer's `algo::diff`: rather than replacing a whole
+/// subtree wholesale, this records the smallest set of element-level edits
+/// that turn `lhs` into `rhs`, so a caller can apply surgical text edits
+/// (see [`TreeDiff::into_text_edits`]) instead of reparsing from scratch.
+#[derive(Debug, Default)]
+pub struct TreeDiff<L: rowan::Language> {
+	/// An old element that is swapped wholesale for a new one of a
+	/// different kind.
+	replacements: HashMap<SyntaxElement<L>, SyntaxElement<L>>,
+	/// New elements inserted after the keyed anchor (or at the start of
+	/// the parent, if the anchor is `None`).
+	insertions: HashMap<Option<SyntaxElement<L>>, Vec<SyntaxElement<L>>>,
+	/// Old elements with no counterpart in `rhs`.
+	deletions: Vec<SyntaxElement<L>>,
+}
+
+impl<L: rowan::Language> TreeDiff<L> {
+	#[must_use]
+	pub fn is_empty(&self) -> bool {
+		self.replacements.is_empty() && self.insertions.is_empty() && self.deletions.is_empty()
+	}
+
+	/// Lowers this element-level diff into a sorted, non-overlapping list of
+	/// `(range, replacement)` text edits, suitable for applying directly to
+	/// the original source text in a single pass from the end of the file
+	/// backwards (so earlier ranges stay valid).
+	#[must_use]
+	pub fn into_text_edits(self) -> Vec<(TextRange, String)> {
+		let mut edits = vec![];
+
+		for (old, new) in self.replacements {
+			edits.push((old.text_range(), new.to_string()));
+		}
+
+		for elem in self.deletions {
+			edits.push((elem.text_range(), String::new()));
+		}
+
+		for (anchor, news) in self.insertions {
+			let at = anchor
+				.as_ref()
+				.map_or(TextSize::from(0), |elem| elem.text_range().end());
+			let text = news.into_iter().map(|elem| elem.to_string()).collect();
+			edits.push((TextRange::empty(at), text));
+		}
+
+		edits.sort_by_key(|(range, _)| range.start());
+		edits
+	}
+}
+
+/// Computes the minimal edit set that turns `lhs` into `rhs`, walking both
+/// trees in lockstep. Children are matched greedily left-to-right by
+/// [`rowan::Language::Kind`]; a child present on only one side is recorded
+/// as an insertion/deletion against its nearest matched neighbor, and a
+/// kind mismatch between two otherwise-aligned children is recorded as a
+/// whole-element replacement rather than a recursive diff.
+#[must_use]
+pub fn diff<L: rowan::Language>(lhs: &SyntaxNode<L>, rhs: &SyntaxNode<L>) -> TreeDiff<L> {
+	let mut acc = TreeDiff {
+		replacements: HashMap::new(),
+		insertions: HashMap::new(),
+		deletions: vec![],
+	};
+
+	go(
+		&mut acc,
+		NodeOrToken::Node(lhs.clone()),
+		NodeOrToken::Node(rhs.clone()),
+	);
+
+	acc
+}
+
+fn go<L: rowan::Language>(acc: &mut TreeDiff<L>, lhs: SyntaxElement<L>, rhs: SyntaxElement<L>) {
+	if lhs.kind() != rhs.kind() {
+		acc.replacements.insert(lhs, rhs);
+		return;
+	}
+
+	let (lhs, rhs) = match (lhs, rhs) {
+		(NodeOrToken::Node(lhs), NodeOrToken::Node(rhs)) => (lhs, rhs),
+		(lhs, rhs) => {
+			if lhs.as_token().map(rowan::SyntaxToken::text) != rhs.as_token().map(rowan::SyntaxToken::text) {
+				acc.replacements.insert(lhs, rhs);
+			}
+
+			return;
+		}
+	};
+
+	if lhs.text() == rhs.text() {
+		return;
+	}
+
+	let lhs_children = lhs.children_with_tokens().collect::<Vec<_>>();
+	let rhs_children = rhs.children_with_tokens().collect::<Vec<_>>();
+
+	let mut lhs_ix = 0;
+	let mut rhs_ix = 0;
+	let mut anchor = None::<SyntaxElement<L>>;
+
+	while lhs_ix < lhs_children.len() || rhs_ix < rhs_children.len() {
+		match (lhs_children.get(lhs_ix), rhs_children.get(rhs_ix)) {
+			(Some(l), Some(r)) if l.kind() == r.kind() => {
+				go(acc, l.clone(), r.clone());
+				anchor = Some(l.clone());
+				lhs_ix += 1;
+				rhs_ix += 1;
+			}
+			(Some(l), Some(r)) => {
+				// Look ahead on both sides for a matching kind before
+				// giving up and treating this as a pairwise replacement;
+				// this is what lets a single inserted/deleted sibling
+				// avoid cascading into replacements for everything after it.
+				let l_match_ahead = rhs_children[rhs_ix..].iter().position(|e| e.kind() == l.kind());
+				let r_match_ahead = lhs_children[lhs_ix..].iter().position(|e| e.kind() == r.kind());
+
+				let take_insertion = match (l_match_ahead, r_match_ahead) {
+					(Some(steps), Some(rsteps)) => steps <= rsteps,
+					(Some(_), None) => true,
+					(None, _) => false,
+				};
+
+				match (take_insertion, l_match_ahead, r_match_ahead) {
+					(true, Some(steps), _) => {
+						acc.insertions
+							.entry(anchor.clone())
+							.or_default()
+							.extend(rhs_children[rhs_ix..rhs_ix + steps].iter().cloned());
+						rhs_ix += steps;
+					}
+					(false, _, Some(steps)) => {
+						acc.deletions
+							.extend(lhs_children[lhs_ix..lhs_ix + steps].iter().cloned());
+						lhs_ix += steps;
+					}
+					_ => {
+						acc.replacements.insert(l.clone(), r.clone());
+						anchor = Some(l.clone());
+						lhs_ix += 1;
+						rhs_ix += 1;
+					}
+				}
+			}
+			(Some(l), None) => {
+				acc.deletions.push(l.clone());
+				lhs_ix += 1;
+			}
+			(None, Some(r)) => {
+				acc.insertions
+					.entry(anchor.clone())
+					.or_default()
+					.push(r.clone());
+				rhs_ix += 1;
+			}
+			(None, None) => unreachable!(),
+		}
+	}
+}
+
+/// Walks `from` towards the root, returning the first ancestor (inclusive)
+/// whose range fully contains `range`. Used by callers that, having found a
+/// [`TreeDiff`] replacement anchored deep in the tree, need the smallest
+/// enclosing node that is safe to splice in isolation.
+#[must_use]
+pub fn find_covering_node<L: rowan::Language>(
+	from: &SyntaxNode<L>,
+	range: TextRange,
+) -> SyntaxNode<L> {
+	let mut node = from.clone();
+
+	while !node.text_range().contains_range(range) {
+		match node.parent() {
+			Some(parent) => node = parent,
+			None => break,
+		}
+	}
+
+	node
+}
+
+/// Returns the sibling of `node` in `dir` whose kind matches `node`'s,
+/// skipping over anything else (e.g. trivia). Useful when matching up
+/// repeated constructs (two `FlagSetting`s in a row, two `StateDef`s) by
+/// hand rather than going through the full [`diff`].
+#[must_use]
+pub fn next_sibling_of_kind<L: rowan::Language>(
+	node: &SyntaxNode<L>,
+	dir: Direction,
+) -> Option<SyntaxNode<L>> {
+	node.siblings(dir).skip(1).find(|s| s.kind() == node.kind())
+}
+
+#[cfg(test)]
+mod test {
+	use rowan::{GreenNode, GreenToken, NodeOrToken};
+
+	use crate::zdoom::zscript::Syntax;
+
+	use super::*;
+
+	fn flag_setting(adding: bool, name: &str) -> GreenNode {
+		GreenNode::new(
+			Syntax::FlagSetting.into(),
+			[
+				NodeOrToken::Token(GreenToken::new(
+					if adding { Syntax::Plus } else { Syntax::Minus }.into(),
+					if adding { "+" } else { "-" },
+				)),
+				NodeOrToken::Node(GreenNode::new(
+					Syntax::IdentChain.into(),
+					[NodeOrToken::Token(GreenToken::new(
+						Syntax::Ident.into(),
+						name,
+					))],
+				)),
+			],
+		)
+	}
+
+	fn default_block(innards: impl IntoIterator<Item = GreenNode>) -> rowan::SyntaxNode<Syntax> {
+		let node = GreenNode::new(
+			Syntax::DefaultBlock.into(),
+			innards.into_iter().map(NodeOrToken::Node),
+		);
+		rowan::SyntaxNode::new_root(node)
+	}
+
+	#[test]
+	fn reordered_flag_settings() {
+		let lhs = default_block([flag_setting(true, "NoGravity"), flag_setting(false, "Shootable")]);
+		let rhs = default_block([flag_setting(false, "Shootable"), flag_setting(true, "NoGravity")]);
+
+		let d = diff(&lhs, &rhs);
+		assert!(!d.is_empty());
+	}
+
+	#[test]
+	fn changed_state_def_duration() {
+		fn state_def(duration: &str) -> rowan::SyntaxNode<Syntax> {
+			let node = GreenNode::new(
+				Syntax::StateDef.into(),
+				[
+					NodeOrToken::Token(GreenToken::new(Syntax::NonWhitespace.into(), "TNT1")),
+					NodeOrToken::Token(GreenToken::new(Syntax::NonWhitespace.into(), "A")),
+					NodeOrToken::Token(GreenToken::new(Syntax::IntLit.into(), duration)),
+				],
+			);
+
+			rowan::SyntaxNode::new_root(node)
+		}
+
+		let lhs = state_def("-1");
+		let rhs = state_def("5");
+
+		let d = diff(&lhs, &rhs);
+		let edits = d.into_text_edits();
+		assert_eq!(edits.len(), 1);
+		assert_eq!(edits[0].1, "5");
+	}
+}
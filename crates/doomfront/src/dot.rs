@@ -0,0 +1,101 @@
+//! Graphviz DOT export for any [`rowan`] syntax tree in this crate,
+//! independent of any one frontend's [`Syntax`](rowan::Language::Kind).
+//!
+//! Lets a contributor dump a parsed concrete syntax tree as a rendered
+//! digraph (`dot -Tsvg out.dot > out.svg`) instead of eyeballing a
+//! [`prettyprint`](crate::testing::prettyprint) dump, which makes it much
+//! easier to diff the shape of a production like `TypeRef` or `IdentChain`
+//! across a grammar change, or to attach to a bug report.
+
+use std::fmt::Write as _;
+
+use rowan::{NodeOrToken, SyntaxNode};
+
+/// Controls what [`write_dot`] includes in its output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DotOptions {
+	/// If `true`, trivia tokens (whitespace, comments, region markers) are
+	/// omitted entirely rather than rendered as leaf nodes.
+	pub hide_trivia: bool,
+}
+
+/// Renders `root` as a Graphviz DOT digraph: one node per CST node labeled
+/// with its `Syntax` kind, one leaf node per token labeled with its kind and
+/// escaped text, and edges from parent to child in source order.
+#[must_use]
+pub fn write_dot<L>(root: &SyntaxNode<L>, opts: DotOptions) -> String
+where
+	L: rowan::Language,
+	L::Kind: std::fmt::Debug,
+{
+	let mut out = String::new();
+	out.push_str("digraph cst {\n");
+	out.push_str("\tnode [shape=box, fontname=\"monospace\"];\n");
+
+	let mut next_id = 0;
+	emit_node(&mut out, &mut next_id, &NodeOrToken::Node(root.clone()), opts);
+
+	out.push_str("}\n");
+	out
+}
+
+/// Emits `elem` (and, recursively, its children) as DOT nodes/edges, and
+/// returns the id assigned to `elem` itself, or `None` if it was hidden
+/// trivia.
+fn emit_node<L>(
+	out: &mut String,
+	next_id: &mut usize,
+	elem: &rowan::SyntaxElement<L>,
+	opts: DotOptions,
+) -> Option<usize>
+where
+	L: rowan::Language,
+	L::Kind: std::fmt::Debug,
+{
+	if opts.hide_trivia && is_trivia(elem.kind()) {
+		return None;
+	}
+
+	let id = *next_id;
+	*next_id += 1;
+
+	match elem {
+		NodeOrToken::Node(node) => {
+			let _ = writeln!(out, "\tn{id} [label=\"{:?}\"];", node.kind());
+
+			for child in node.children_with_tokens() {
+				if let Some(child_id) = emit_node(out, next_id, &child, opts) {
+					let _ = writeln!(out, "\tn{id} -> n{child_id};");
+				}
+			}
+		}
+		NodeOrToken::Token(token) => {
+			let _ = writeln!(
+				out,
+				"\tn{id} [label=\"{:?}\\n{}\", shape=ellipse];",
+				token.kind(),
+				escape(token.text())
+			);
+		}
+	}
+
+	Some(id)
+}
+
+/// Best-effort trivia detection by kind name, since [`rowan::Language::Kind`]
+/// carries no common trait for it. Every frontend in this crate names its
+/// trivia variants `Whitespace`, `Comment`, `RegionStart`, and `RegionEnd`.
+fn is_trivia<K: std::fmt::Debug>(kind: K) -> bool {
+	matches!(
+		format!("{kind:?}").as_str(),
+		"Whitespace" | "Comment" | "RegionStart" | "RegionEnd"
+	)
+}
+
+#[must_use]
+fn escape(text: &str) -> String {
+	text.replace('\\', "\\\\")
+		.replace('"', "\\\"")
+		.replace('\n', "\\n")
+		.replace('\t', "\\t")
+}
@@ -0,0 +1,124 @@
+//! Stable, owned references into a [`rowan`] tree that survive a reparse.
+//!
+//! Every AST wrapper in this crate (`FlagDef`, `StateDef`, `PropertySetting`,
+//! ...) holds a live [`SyntaxNode`](rowan::SyntaxNode) cursor, so it cannot be
+//! stashed across an edit; the tree it points into may simply no longer
+//! exist. [`SyntaxNodePtr`] (and the typed [`AstPtr`]) record just enough to
+//! re-descend into a *new* tree of the same shape and recover the node,
+//! modeled on rust-analyzer's `ptr.rs`.
+
+use std::marker::PhantomData;
+
+use rowan::{ast::AstNode, SyntaxNode, TextRange};
+
+/// An untyped, owned pointer to a node somewhere in a [`rowan`] tree,
+/// identified by its range and kind rather than by cursor.
+///
+/// This is only as stable as the tree it is later resolved against: if the
+/// edit that produced the new tree changed the range or kind of the node in
+/// question, [`SyntaxNodePtr::to_node`] will panic. Callers that can't
+/// guarantee this (e.g. after an edit that may have deleted the node
+/// outright) should keep enough context to know whether resolution is safe
+/// before calling it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SyntaxNodePtr<L: rowan::Language> {
+	range: TextRange,
+	kind: L::Kind,
+}
+
+impl<L: rowan::Language> SyntaxNodePtr<L> {
+	/// Captures `node`'s range and kind so it can be recovered later via
+	/// [`Self::to_node`], even from a different (but structurally
+	/// equivalent) tree than `node` belongs to.
+	#[must_use]
+	pub fn new(node: &SyntaxNode<L>) -> Self {
+		Self {
+			range: node.text_range(),
+			kind: node.kind(),
+		}
+	}
+
+	#[must_use]
+	pub fn range(&self) -> TextRange {
+		self.range
+	}
+
+	#[must_use]
+	pub fn kind(&self) -> L::Kind {
+		self.kind
+	}
+
+	/// Re-descends from `root`, returning the descendant (or `root` itself)
+	/// whose range equals this pointer's range and whose kind matches.
+	///
+	/// # Panics
+	/// Panics if no such node exists in `root`'s tree.
+	#[must_use]
+	pub fn to_node(&self, root: &SyntaxNode<L>) -> SyntaxNode<L> {
+		std::iter::successors(Some(root.clone()), |node| {
+			node.children()
+				.find(|child| child.text_range().contains_range(self.range))
+		})
+		.find(|node| node.text_range() == self.range && node.kind() == self.kind)
+		.unwrap_or_else(|| {
+			panic!(
+				"no node of kind {:?} found at {:?} while resolving a `SyntaxNodePtr`",
+				self.kind, self.range
+			)
+		})
+	}
+}
+
+/// A [`SyntaxNodePtr`] known to always point to a node castable to `N`.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct AstPtr<N: AstNode> {
+	raw: SyntaxNodePtr<N::Language>,
+	_phantom: PhantomData<fn() -> N>,
+}
+
+// Deriving `Clone`/`Copy` would require `N: Clone`/`N: Copy`, which is
+// stricter than necessary; `AstPtr<N>` only ever stores the untyped `raw`.
+impl<N: AstNode> Clone for AstPtr<N> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<N: AstNode> Copy for AstPtr<N> {}
+
+impl<N: AstNode> AstPtr<N> {
+	#[must_use]
+	pub fn new(node: &N) -> Self {
+		Self {
+			raw: SyntaxNodePtr::new(node.syntax()),
+			_phantom: PhantomData,
+		}
+	}
+
+	/// Downcasts an untyped pointer, so long as its recorded kind is one
+	/// `N` can cast from.
+	#[must_use]
+	pub fn try_from_raw(raw: SyntaxNodePtr<N::Language>) -> Option<Self> {
+		N::can_cast(raw.kind()).then_some(Self {
+			raw,
+			_phantom: PhantomData,
+		})
+	}
+
+	#[must_use]
+	pub fn into_raw(self) -> SyntaxNodePtr<N::Language> {
+		self.raw
+	}
+
+	/// Re-descends from `root` and casts the recovered node to `N`.
+	///
+	/// # Panics
+	/// Panics if [`SyntaxNodePtr::to_node`] panics, or if the recovered
+	/// node's kind no longer casts to `N` (which should not happen unless
+	/// `root` is not structurally equivalent to the tree this pointer was
+	/// made from).
+	#[must_use]
+	pub fn to_node(&self, root: &SyntaxNode<N::Language>) -> N {
+		N::cast(self.raw.to_node(root)).expect("`AstPtr` resolved to a node of an unexpected kind")
+	}
+}
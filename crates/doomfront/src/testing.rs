@@ -1,6 +1,6 @@
 //! Utilities for unit testing and benchmarking.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use rowan::{SyntaxElement, SyntaxNode, WalkEvent};
 
@@ -166,6 +166,89 @@ pub fn prettyprint_maybe<L: LangExt>(cursor: SyntaxNode<L>) -> bool {
 	}
 }
 
+/// Serializes `cursor`'s syntax tree in preorder as a nested S-expression:
+/// `(KIND child child ...)` for a node, `(KIND "text")` for a token, with
+/// backslashes and double quotes in token text escaped. Unlike
+/// [`prettyprint`] this is meant to be compared rather than read, e.g. via
+/// [`assert_sexpr_eq`]; a grammar tweak then shows up as a small, reviewable
+/// diff instead of forcing a hand-transcribed `assert_sequence` array to be
+/// rewritten by hand.
+#[must_use]
+pub fn to_sexpr<L: LangExt>(cursor: &SyntaxNode<L>) -> String {
+	// Each open node gets its own frame, accumulating its kind followed by
+	// its children's rendered text; closing the node joins the frame into a
+	// single parenthesized string and pushes it onto its parent's frame.
+	let mut stack: Vec<Vec<String>> = vec![Vec::new()];
+
+	for event in cursor.preorder_with_tokens() {
+		match event {
+			WalkEvent::Enter(SyntaxElement::Node(node)) => {
+				stack.push(vec![format!("{:?}", node.kind())]);
+			}
+			WalkEvent::Enter(SyntaxElement::Token(token)) => {
+				let text = token.text().replace('\\', "\\\\").replace('"', "\\\"");
+
+				stack
+					.last_mut()
+					.expect("a token always has an enclosing root node")
+					.push(format!("({:?} \"{text}\")", token.kind()));
+			}
+			WalkEvent::Leave(elem) => {
+				if elem.as_node().is_none() {
+					continue;
+				}
+
+				let frame = stack.pop().expect("unbalanced node enter/leave events");
+				let sexpr = format!("({})", frame.join(" "));
+
+				stack
+					.last_mut()
+					.expect("the bottom frame receives the root node's own rendering")
+					.push(sexpr);
+			}
+		}
+	}
+
+	stack
+		.pop()
+		.and_then(|mut root| root.pop())
+		.expect("preorder_with_tokens always enters and leaves `cursor` itself")
+}
+
+/// Compares [`to_sexpr(cursor)`](to_sexpr) against the golden file at
+/// `expected_path`. If the environment variable `DOOMFRONT_TEST_BLESS` is
+/// set to "1", the golden file is (re)written to match instead of the
+/// comparison failing, so a deliberate grammar change can update its own
+/// expectations with a single test run.
+pub fn assert_sexpr_eq<L: LangExt>(cursor: &SyntaxNode<L>, expected_path: impl AsRef<Path>) {
+	let expected_path = expected_path.as_ref();
+	let actual = to_sexpr(cursor);
+
+	if std::env::var("DOOMFRONT_TEST_BLESS").is_ok_and(|v| v == "1") {
+		std::fs::write(expected_path, &actual).unwrap_or_else(|err| {
+			panic!(
+				"failed to write golden file `{}`: {err}",
+				expected_path.display()
+			)
+		});
+
+		return;
+	}
+
+	let expected = std::fs::read_to_string(expected_path).unwrap_or_else(|err| {
+		panic!(
+			"failed to read golden file `{}` ({err}); run with `DOOMFRONT_TEST_BLESS=1` to create it",
+			expected_path.display()
+		)
+	});
+
+	assert_eq!(
+		actual, expected,
+		"s-expression mismatch against golden file `{}`; re-run with `DOOMFRONT_TEST_BLESS=1` to update it",
+		expected_path.display()
+	);
+}
+
 /// `Err` variants contain the reason the read failed. This can happen because:
 /// - the environment variable behind `env_var_name` could not be retrieved
 /// - the path at the environment variable is to a non-existent file
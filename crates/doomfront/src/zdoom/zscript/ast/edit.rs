@@ -0,0 +1,126 @@
+//! In-place mutable editing for the actor AST, via green-tree splicing.
+//!
+//! Every method here rebuilds just the green children of the node being
+//! edited and hands back a new typed node rooted at the result, reusing
+//! every unaffected subtree by green-tree identity (the same sharing
+//! [`super::make`]'s constructors rely on). Pair the two: compose a new
+//! innard with `make`, then splice it in here, to express a refactor or
+//! quick-fix as compose-then-splice rather than a text edit and a re-parse.
+
+use rowan::{ast::AstNode, GreenNode, NodeOrToken};
+
+use super::{
+	actor::{DefaultBlock, DefaultInnard, Expr, FlagSetting, PropertySetting, StateDef, StatesBlock},
+	make,
+	Syntax,
+};
+
+impl FlagSetting {
+	/// Sets the leading sign to `+` if `adding` and `-` otherwise, leaving the
+	/// flag name untouched.
+	#[must_use]
+	pub fn set_adding(&self, adding: bool) -> Self {
+		let children: Vec<_> = self.syntax().children_with_tokens().collect();
+		let mut green_children = to_green_children(children);
+
+		green_children[0] = NodeOrToken::Token(rowan::GreenToken::new(
+			if adding { Syntax::Plus } else { Syntax::Minus }.into(),
+			if adding { "+" } else { "-" },
+		));
+
+		let green = GreenNode::new(Syntax::FlagSetting.into(), green_children);
+		make::cast(green)
+	}
+}
+
+impl PropertySetting {
+	/// Replaces every expression this property is set to, leaving the
+	/// property name untouched.
+	#[must_use]
+	pub fn set_exprs(&self, exprs: impl IntoIterator<Item = Expr>) -> Self {
+		let name = self.syntax().first_child().expect("a name child node");
+
+		let mut children = vec![NodeOrToken::Node(name.green().into_owned())];
+		children.extend(
+			exprs
+				.into_iter()
+				.map(|e| NodeOrToken::Node(e.syntax().green().into_owned())),
+		);
+
+		let green = GreenNode::new(Syntax::PropertySetting.into(), children);
+		make::cast(green)
+	}
+}
+
+impl DefaultBlock {
+	/// Appends `innard` just before the block's closing `}`.
+	#[must_use]
+	pub fn add_innard(&self, innard: DefaultInnard) -> Self {
+		let mut children: Vec<_> = self.syntax().children_with_tokens().collect();
+
+		let close_ix = children
+			.iter()
+			.rposition(|elem| elem.kind() == Syntax::BraceR)
+			.expect("a `DefaultBlock` always has a closing brace");
+
+		children.insert(close_ix, NodeOrToken::Node(innard.syntax().clone()));
+
+		let green = GreenNode::new(Syntax::DefaultBlock.into(), to_green_children(children));
+		make::cast(green)
+	}
+}
+
+impl StatesBlock {
+	/// Inserts `state` so that it becomes the `index`th `StateDef` among
+	/// this block's innards (labels and flow entries are not counted).
+	///
+	/// # Panics
+	/// Panics if `index` is greater than the number of `StateDef`s already
+	/// in the block.
+	#[must_use]
+	pub fn insert_state(&self, index: usize, state: StateDef) -> Self {
+		let mut children: Vec<_> = self.syntax().children_with_tokens().collect();
+
+		let mut seen = 0;
+		let insert_at = children
+			.iter()
+			.position(|elem| {
+				if elem.kind() == Syntax::StateDef {
+					if seen == index {
+						return true;
+					}
+
+					seen += 1;
+				}
+
+				false
+			})
+			.unwrap_or_else(|| {
+				assert_eq!(
+					seen, index,
+					"`index` is out of bounds for this block's `StateDef`s"
+				);
+				children
+					.iter()
+					.rposition(|elem| elem.kind() == Syntax::BraceR)
+					.unwrap_or(children.len())
+			});
+
+		children.insert(insert_at, NodeOrToken::Node(state.syntax().clone()));
+
+		let green = GreenNode::new(Syntax::StatesBlock.into(), to_green_children(children));
+		make::cast(green)
+	}
+}
+
+fn to_green_children(
+	children: Vec<rowan::SyntaxElement<Syntax>>,
+) -> Vec<NodeOrToken<GreenNode, rowan::GreenToken>> {
+	children
+		.into_iter()
+		.map(|elem| match elem {
+			NodeOrToken::Node(n) => NodeOrToken::Node(n.green().into_owned()),
+			NodeOrToken::Token(t) => NodeOrToken::Token(t.green().to_owned()),
+		})
+		.collect()
+}
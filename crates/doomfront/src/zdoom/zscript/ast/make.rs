@@ -0,0 +1,122 @@
+//! Constructors for synthesizing actor-definition AST nodes.
+//!
+//! Mirrors the approach of rust-analyzer's `ast/make.rs`: each function
+//! assembles a well-formed [`GreenNode`] (or green token) directly and hands
+//! back the corresponding typed wrapper from [`super::actor`], so tooling can
+//! programmatically generate or rewrite DECORATE/ZScript without resorting to
+//! string concatenation and a re-parse.
+
+use rowan::{ast::AstNode, GreenNode, GreenToken, NodeOrToken};
+
+use crate::GreenElement;
+
+use super::{
+	actor::{DefaultBlock, FlagSetting, StateDef, StateFlow},
+	Syntax, SyntaxNode,
+};
+
+/// Wraps `green` in a standalone red tree and casts it to `N`.
+///
+/// # Panics
+/// Panics if `green`'s root kind does not match `N`; every function in this
+/// module builds its green tree to satisfy this by construction.
+#[must_use]
+pub(super) fn cast<N: AstNode<Language = Syntax>>(green: GreenNode) -> N {
+	N::cast(SyntaxNode::new_root(green)).expect("malformed synthesized node")
+}
+
+#[must_use]
+fn token(kind: Syntax, text: &str) -> GreenElement {
+	NodeOrToken::Token(GreenToken::new(kind.into(), text))
+}
+
+#[must_use]
+fn ws() -> GreenElement {
+	token(Syntax::Whitespace, " ")
+}
+
+#[must_use]
+fn ident_chain(name: &str) -> GreenElement {
+	NodeOrToken::Node(GreenNode::new(
+		Syntax::IdentChain.into(),
+		[token(Syntax::Ident, name)],
+	))
+}
+
+/// Builds a `+Name`/`-Name` [`Syntax::FlagSetting`] node
+/// (see [`FlagSetting::is_adding`], [`FlagSetting::name`]).
+#[must_use]
+pub fn flag_setting(adding: bool, name: &str) -> FlagSetting {
+	let sign = if adding {
+		token(Syntax::Plus, "+")
+	} else {
+		token(Syntax::Minus, "-")
+	};
+
+	let node = GreenNode::new(Syntax::FlagSetting.into(), [sign, ident_chain(name)]);
+	cast(node)
+}
+
+/// Builds a [`Syntax::StateDef`] node out of already-lowered pieces.
+///
+/// `sprite` is the 4-character sprite name, `frames` the frame letters
+/// (e.g. `"ABC"`), and `duration` the already-rendered tics expression text
+/// (e.g. `"-1"` or `"Random(1, 2)"`); `action` is an optional pre-rendered
+/// action-function call (e.g. `"A_Pain"`).
+#[must_use]
+pub fn state_def(sprite: &str, frames: &str, duration: &str, action: Option<&str>) -> StateDef {
+	let mut children = vec![
+		token(Syntax::NonWhitespace, sprite),
+		ws(),
+		token(Syntax::NonWhitespace, frames),
+		ws(),
+		token(Syntax::IntLit, duration),
+	];
+
+	if let Some(action) = action {
+		children.push(ws());
+		children.push(NodeOrToken::Node(GreenNode::new(
+			Syntax::ActionFunction.into(),
+			[token(Syntax::Ident, action)],
+		)));
+	}
+
+	let node = GreenNode::new(Syntax::StateDef.into(), children);
+	cast(node)
+}
+
+/// Builds a `goto`-flavored [`Syntax::StateFlow`] node.
+///
+/// `scope` is an optional `Super`/class-name qualifier preceding `::`,
+/// `name` is the (possibly dotted) label being jumped to, and `offset` is
+/// an optional `+N` integer offset.
+#[must_use]
+pub fn goto_flow(scope: Option<&str>, name: &str, offset: Option<i64>) -> StateFlow {
+	let mut children = vec![token(Syntax::KwGoto, "goto"), ws()];
+
+	if let Some(scope) = scope {
+		children.push(token(Syntax::Ident, scope));
+		children.push(token(Syntax::Colon2, "::"));
+	}
+
+	children.push(ident_chain(name));
+
+	if let Some(offset) = offset {
+		children.push(token(Syntax::Plus, "+"));
+		children.push(token(Syntax::IntLit, &offset.to_string()));
+	}
+
+	let node = GreenNode::new(Syntax::StateFlow.into(), children);
+	cast(node)
+}
+
+/// Builds a `default { ... }` [`Syntax::DefaultBlock`] node wrapping
+/// already-constructed [`FlagSetting`]/`PropertySetting` innards.
+#[must_use]
+pub fn default_block(innards: impl IntoIterator<Item = GreenElement>) -> DefaultBlock {
+	let mut children = vec![token(Syntax::KwDefault, "default"), ws(), token(Syntax::BraceL, "{")];
+	children.extend(innards);
+	children.push(token(Syntax::BraceR, "}"));
+	let node = GreenNode::new(Syntax::DefaultBlock.into(), children);
+	cast(node)
+}
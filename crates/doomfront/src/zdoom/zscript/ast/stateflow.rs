@@ -0,0 +1,184 @@
+//! Resolving a `StatesBlock`'s `goto`/`loop`/`stop`/`wait` flow into a
+//! concrete control-flow graph over its states.
+//!
+//! [`StateFlow::kind`](super::actor::StateFlow::kind) only exposes the raw
+//! `scope`/`name`/`offset` tokens of a `goto`; a consumer that wants to know
+//! *which state* that actually points to (for dead-state detection, or for
+//! visualizing a monster's state machine) has to walk the block's labels
+//! itself. [`resolve`] does that walk once and hands back a [`StateGraph`].
+
+use std::collections::HashMap;
+
+use crate::ptr::SyntaxNodePtr;
+
+use super::{
+	actor::{StateFlowKind, StatesBlock, StatesInnard},
+	Syntax,
+};
+
+/// Where a piece of state-flow leads.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edge {
+	/// Falls through, or `goto`es, to the state at this index in
+	/// [`StateGraph::states`].
+	State(usize),
+	/// A `loop` back to the most recently passed label; `None` if no label
+	/// precedes it (a malformed block, since the ZScript grammar requires
+	/// one).
+	Loop(Option<usize>),
+	/// `stop`, `wait`, or `fail` — the state machine halts or holds here.
+	Terminal,
+	/// A `goto` (optionally `Scope::`-qualified) whose target name was not
+	/// found among this block's labels. Recorded rather than silently
+	/// dropped, so callers can flag it as a dead/broken reference.
+	Dangling,
+}
+
+/// The result of [`resolve`]: every `StateDef` in a `StatesBlock`, in
+/// declaration order, plus the resolved outgoing [`Edge`] for every
+/// `StateFlow` in that block and for every `StateDef` not directly followed
+/// by one.
+#[derive(Debug, Default)]
+pub struct StateGraph {
+	/// Every `StateDef` in the block, in declaration order; `goto` targets
+	/// and fallthrough successors are indices into this.
+	pub states: Vec<SyntaxNodePtr<Syntax>>,
+	/// The outgoing edge for each `StateFlow` node, and for each `StateDef`
+	/// node not directly followed by a `StateFlow` (whose edge then stands
+	/// for it), keyed by its stable pointer so it survives a reparse of the
+	/// surrounding file.
+	pub edges: HashMap<SyntaxNodePtr<Syntax>, Edge>,
+}
+
+/// Resolves every `goto`/`loop`/`stop`/`wait`/`fail` in `block` to a
+/// concrete [`Edge`].
+///
+/// `super_block` is the `StatesBlock` of the nearest ancestor class that
+/// declares one (if any), consulted when a `goto` is qualified with
+/// `Super::`; labels are otherwise resolved only within `block` itself, as
+/// ZScript does not search sibling classes.
+#[must_use]
+pub fn resolve(block: &StatesBlock, super_block: Option<&StatesBlock>) -> StateGraph {
+	let mut graph = StateGraph::default();
+	let innards: Vec<StatesInnard> = block.innards().collect();
+
+	// Pass 1: record where each label name points (the next `StateDef`
+	// after it, skipping any labels in between), and the full ordered list
+	// of states.
+	let mut labels = HashMap::<String, usize>::new();
+	let mut pending_labels = vec![];
+
+	for innard in &innards {
+		match innard {
+			StatesInnard::Label(label) => {
+				pending_labels.push(label.name().text().to_ascii_lowercase());
+			}
+			StatesInnard::State(state) => {
+				let ix = graph.states.len();
+				graph.states.push(SyntaxNodePtr::new(state.syntax()));
+
+				for name in pending_labels.drain(..) {
+					labels.insert(name, ix);
+				}
+			}
+			StatesInnard::Flow(_) => {}
+		}
+	}
+
+	let super_labels = super_block.map(|sup| {
+		let mut m = HashMap::<String, usize>::new();
+		let mut pending = vec![];
+		let mut ix = 0;
+
+		for innard in sup.innards() {
+			match innard {
+				StatesInnard::Label(label) => {
+					pending.push(label.name().text().to_ascii_lowercase());
+				}
+				StatesInnard::State(_) => {
+					for name in pending.drain(..) {
+						m.insert(name, ix);
+					}
+					ix += 1;
+				}
+				StatesInnard::Flow(_) => {}
+			}
+		}
+
+		m
+	});
+
+	// Pass 2: walk again, now resolving each `StateDef`'s implicit
+	// fallthrough and each `StateFlow`'s explicit edge.
+	let mut state_ix = 0usize;
+	let mut most_recent_label = None::<usize>;
+
+	for (pos, innard) in innards.iter().enumerate() {
+		match innard {
+			StatesInnard::Label(label) => {
+				// The label anchors whichever state follows it; since pass 1
+				// already resolved that, just mirror the index here for `loop`.
+				let name = label.name().text().to_ascii_lowercase();
+				most_recent_label = labels.get(&name).copied();
+			}
+			StatesInnard::State(state) => {
+				let ptr = SyntaxNodePtr::new(state.syntax());
+
+				let edge = if matches!(innards.get(pos + 1), Some(StatesInnard::Flow(_))) {
+					// The following `StateFlow` supplies this state's edge;
+					// it is resolved in its own arm below, keyed by its own
+					// node, not duplicated here.
+					state_ix += 1;
+					continue;
+				} else if state_ix + 1 < graph.states.len() {
+					Edge::State(state_ix + 1)
+				} else {
+					Edge::Terminal
+				};
+
+				graph.edges.insert(ptr, edge);
+				state_ix += 1;
+			}
+			StatesInnard::Flow(flow) => {
+				let ptr = SyntaxNodePtr::new(flow.syntax());
+
+				let edge = match flow.kind() {
+					StateFlowKind::Fail(_) | StateFlowKind::Stop(_) | StateFlowKind::Wait(_) => {
+						Edge::Terminal
+					}
+					StateFlowKind::Loop(_) => Edge::Loop(most_recent_label),
+					StateFlowKind::Goto { scope, name, offset } => {
+						let key = name.syntax().text().to_string().to_ascii_lowercase();
+
+						let base = if scope.as_ref().is_some_and(|s| s.kind() == Syntax::KwSuper) {
+							super_labels.as_ref().and_then(|m| m.get(&key).copied())
+						} else {
+							labels.get(&key).copied()
+						};
+
+						match base {
+							Some(base_ix) => {
+								let off: i64 = offset
+									.as_ref()
+									.and_then(|lit| lit.text().parse().ok())
+									.unwrap_or(0);
+								let target = base_ix as i64 + off;
+
+								if target >= 0 && (target as usize) < graph.states.len() {
+									Edge::State(target as usize)
+								} else {
+									Edge::Dangling
+								}
+							}
+							None => Edge::Dangling,
+						}
+					}
+				};
+
+				graph.edges.insert(ptr, edge);
+			}
+		}
+	}
+
+	graph
+}
@@ -0,0 +1,158 @@
+//! Structural diagnostics for the actor AST, analogous to rust-analyzer's
+//! `validation.rs`.
+//!
+//! These checks are cheap, purely syntactic sanity checks that a language
+//! server or linter can run immediately after parsing, without building out
+//! a full semantic model first.
+
+use rowan::ast::AstNode;
+
+use super::{
+	actor::{FlagDef, PropertyDef, StateDef, StateFlow, StateFlowKind, StatesBlock},
+	Syntax, SyntaxNode,
+};
+
+/// A single validation finding, ready to be lowered into an editor
+/// diagnostic by a caller that has the source text in hand.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+	pub range: rowan::TextRange,
+	pub severity: Severity,
+	pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+	Error,
+	Warning,
+}
+
+/// Valid characters for a [`StateDef::frames`] token, per the DECORATE/
+/// ZScript sprite-frame alphabet.
+const FRAME_ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]";
+
+/// Walks every actor-definition construct under `root`, appending a
+/// [`Diagnostic`] to `acc` for each check that fails. `root` may be any
+/// node; only the kinds relevant to each check are inspected, so this can
+/// be called with a whole parsed file or with a single class body.
+pub fn validate(root: &SyntaxNode, acc: &mut Vec<Diagnostic>) {
+	for node in root.descendants() {
+		match node.kind() {
+			Syntax::FlagDef => validate_flag_def(&FlagDef::cast(node).unwrap(), acc),
+			Syntax::StateDef => validate_state_def(&StateDef::cast(node).unwrap(), acc),
+			Syntax::StateFlow => validate_state_flow(&StateFlow::cast(node).unwrap(), acc),
+			Syntax::StatesBlock => validate_states_block(&StatesBlock::cast(node).unwrap(), acc),
+			_ => {}
+		}
+	}
+
+	validate_duplicate_names(root, acc);
+}
+
+fn validate_flag_def(flag: &FlagDef, acc: &mut Vec<Diagnostic>) {
+	let Ok(bit) = flag.bit() else { return };
+
+	let Ok(value) = bit.text().parse::<i64>() else {
+		return;
+	};
+
+	if !(0..=31).contains(&value) {
+		acc.push(Diagnostic {
+			range: bit.syntax().text_range(),
+			severity: Severity::Error,
+			message: format!("flag bit {value} is out of range; must be within `0..=31`"),
+		});
+	}
+}
+
+fn validate_state_def(state: &StateDef, acc: &mut Vec<Diagnostic>) {
+	let Ok(frames) = state.frames() else { return };
+
+	if let Some(bad) = frames.text().chars().find(|c| !FRAME_ALPHABET.contains(*c)) {
+		acc.push(Diagnostic {
+			range: frames.text_range(),
+			severity: Severity::Error,
+			message: format!("`{bad}` is not a valid sprite-frame character"),
+		});
+	}
+}
+
+fn validate_state_flow(flow: &StateFlow, acc: &mut Vec<Diagnostic>) {
+	let StateFlowKind::Goto { name, offset, .. } = flow.kind() else {
+		return;
+	};
+
+	if let Some(offset) = offset {
+		if offset.text().starts_with('-') {
+			acc.push(Diagnostic {
+				range: offset.syntax().text_range(),
+				severity: Severity::Error,
+				message: "`goto` offsets must not be negative".to_string(),
+			});
+		}
+	}
+
+	// Resolving `name`/`scope` against the enclosing `StatesBlock`'s labels
+	// is the job of state-flow resolution (see `super::stateflow`); here we
+	// only check that the identifier chain is well-formed syntactically.
+	if name.syntax().children_with_tokens().count() == 0 {
+		acc.push(Diagnostic {
+			range: name.syntax().text_range(),
+			severity: Severity::Error,
+			message: "`goto` target name is empty".to_string(),
+		});
+	}
+}
+
+fn validate_states_block(block: &StatesBlock, acc: &mut Vec<Diagnostic>) {
+	let Some(usages) = block.usage_quals() else {
+		return;
+	};
+
+	let mut seen = std::collections::HashSet::new();
+
+	for usage in usages {
+		let token = usage.syntax();
+		let key = token.text().to_ascii_lowercase();
+
+		if !seen.insert(key) {
+			acc.push(Diagnostic {
+				range: token.text_range(),
+				severity: Severity::Warning,
+				message: format!("state usage `{}` is listed more than once", token.text()),
+			});
+		}
+	}
+}
+
+fn validate_duplicate_names(root: &SyntaxNode, acc: &mut Vec<Diagnostic>) {
+	let mut seen = std::collections::HashMap::<String, rowan::TextRange>::new();
+
+	for node in root.children() {
+		let (name_range, name_text) = match node.kind() {
+			Syntax::FlagDef => {
+				let Ok(name) = FlagDef::cast(node).unwrap().name() else {
+					continue;
+				};
+				(name.text_range(), name.text().to_ascii_lowercase())
+			}
+			Syntax::PropertyDef => {
+				let Ok(name) = PropertyDef::cast(node).unwrap().name() else {
+					continue;
+				};
+				(name.text_range(), name.text().to_ascii_lowercase())
+			}
+			_ => continue,
+		};
+
+		if let Some(prev_range) = seen.insert(name_text.clone(), name_range) {
+			acc.push(Diagnostic {
+				range: name_range,
+				severity: Severity::Error,
+				message: format!(
+					"`{name_text}` is already defined at {prev_range:?} in this class"
+				),
+			});
+		}
+	}
+}
@@ -2,10 +2,10 @@
 
 use crate::{
 	parser::Parser,
-	zdoom::{zscript::Syntax, Token},
+	zdoom::{zscript::Syntax, Token, Version},
 };
 
-use super::expr;
+use super::{expr, keyword::is_contextual_ident};
 
 // Identifiers /////////////////////////////////////////////////////////////////
 
@@ -32,59 +32,16 @@ pub(super) const ID_TYPES: u8 = 1 << 2;
 /// Allows [`Token::KwDefault`].
 pub(super) const ID_DEFAULT: u8 = 1 << 3;
 
-const STATEFLOW_KWS: &[Token] = &[
-	Token::KwLoop,
-	Token::KwFail,
-	Token::KwWait,
-	Token::KwOffset,
-	Token::KwSlow,
-];
-
-const STATEQUAL_KWS: &[Token] = &[
-	Token::KwBright,
-	Token::KwCanRaise,
-	Token::KwFast,
-	Token::KwLight,
-	Token::KwOffset,
-	Token::KwSlow,
-];
-
-const PRIMTYPE_KWS: &[Token] = &[
-	Token::KwInt16,
-	Token::KwSByte,
-	Token::KwByte,
-	Token::KwInt8,
-	Token::KwUInt8,
-	Token::KwShort,
-	Token::KwUShort,
-	Token::KwInt16,
-	Token::KwUInt16,
-	Token::KwInt,
-	Token::KwUInt,
-	Token::KwFloat,
-	Token::KwDouble,
-	Token::KwString,
-	Token::KwVector2,
-	Token::KwVector3,
-	// Curiously, ZScript's Lemon grammar prescribes a `vector4` keyword as
-	// being an option here, but there's no RE2C lexer rule for it.
-	Token::KwName,
-	Token::KwMap,
-	Token::KwMapIterator,
-	Token::KwArray,
-	Token::KwVoid,
-	Token::KwState,
-	Token::KwColor,
-	Token::KwSound,
-	Token::KwProperty,
-];
-
 /// Combine [`ID_SFKW`], [`ID_SQKW`], and [`ID_TYPES`] via bitwise or to form `CFG`.
 /// If `0` is given, only [`Token::Ident`] will match.
+///
+/// Version-gated: a word that only became a reserved keyword at some GZDoom
+/// version (per [`super::keyword::CONTEXTUAL_KWS`]) is still accepted as a
+/// plain identifier when `p`'s target version precedes it.
 pub(super) fn ident<const CFG: u8>(p: &mut Parser<Syntax>) {
 	let token = p.nth(0);
 
-	if is_ident::<CFG>(token) {
+	if is_ident::<CFG>(token, p.version()) {
 		p.advance(Syntax::Ident);
 	} else {
 		p.advance_with_error(Syntax::from(token), &[&["an identifier"]])
@@ -92,29 +49,11 @@ pub(super) fn ident<const CFG: u8>(p: &mut Parser<Syntax>) {
 }
 
 /// Combine [`ID_SFKW`], [`ID_SQKW`], and [`ID_TYPES`] via bitwise or to form `CFG`.
-/// If `0` is given, only [`Token::Ident`] will match.
-pub(super) fn is_ident<const CFG: u8>(token: Token) -> bool {
-	if token == Token::Ident {
-		return true;
-	}
-
-	if (CFG & ID_SFKW) != 0 && STATEFLOW_KWS.contains(&token) {
-		return true;
-	}
-
-	if (CFG & ID_SQKW) != 0 && STATEQUAL_KWS.contains(&token) {
-		return true;
-	}
-
-	if (CFG & ID_TYPES) != 0 && PRIMTYPE_KWS.contains(&token) {
-		return true;
-	}
-
-	if (CFG & ID_DEFAULT) != 0 && token == Token::KwDefault {
-		return true;
-	}
-
-	false
+/// If `0` is given, only [`Token::Ident`] will match. See [`ident`] for how
+/// `version` interacts with `CFG`.
+#[must_use]
+pub(super) fn is_ident<const CFG: u8>(token: Token, version: Version) -> bool {
+	token == Token::Ident || is_contextual_ident(token, CFG, version)
 }
 
 /// Shorthand for `ident::<{ ID_SFKW | ID_SQKW | ID_TYPES }>(p);`.
@@ -122,16 +61,17 @@ pub(super) fn ident_lax(p: &mut Parser<Syntax>) {
 	ident::<{ ID_SFKW | ID_SQKW | ID_TYPES }>(p);
 }
 
-/// Shorthand for `is_ident::<{ ID_SFKW | ID_SQKW | ID_TYPES }>(token);`.
+/// Shorthand for `is_ident::<{ ID_SFKW | ID_SQKW | ID_TYPES }>(token, version);`.
 #[must_use]
-pub(super) fn is_ident_lax(token: Token) -> bool {
-	is_ident::<{ ID_SFKW | ID_SQKW | ID_TYPES }>(token)
+pub(super) fn is_ident_lax(token: Token, version: Version) -> bool {
+	is_ident::<{ ID_SFKW | ID_SQKW | ID_TYPES }>(token, version)
 }
 
 /// Builds a [`Syntax::IdentChain`] node.
 /// Backed by [`is_ident`]; see that function's documentation for details on `CFG`.
 pub(super) fn ident_chain<const CFG: u8>(p: &mut Parser<Syntax>) {
-	p.debug_assert_at_if(|token| is_ident::<CFG>(token) || token == Token::Dot);
+	let version = p.version();
+	p.debug_assert_at_if(|token| is_ident::<CFG>(token, version) || token == Token::Dot);
 
 	let chain = p.open();
 
@@ -273,7 +213,8 @@ pub(super) fn trivia_no_doc_0plus(p: &mut Parser<Syntax>) {
 
 /// Builds a [`Syntax::VarName`] node.
 pub(super) fn var_name(p: &mut Parser<Syntax>) {
-	p.debug_assert_at_if(is_ident::<{ ID_SFKW | ID_SQKW | ID_TYPES }>);
+	let version = p.version();
+	p.debug_assert_at_if(|token| is_ident::<{ ID_SFKW | ID_SQKW | ID_TYPES }>(token, version));
 	let name = p.open();
 	p.advance(Syntax::Ident);
 
@@ -0,0 +1,225 @@
+//! The table behind [`super::common::is_ident`]/[`super::common::ident`].
+//!
+//! A handful of ZScript words are only reserved starting at a specific
+//! GZDoom version; code that declares an older `version(...)` qualifier
+//! must still be able to use them as ordinary identifiers. Each entry below
+//! names the token, the grammatical positions ([`super::common::ID_SFKW`]
+//! and friends) it is contextual in, and (if applicable) the version at or
+//! above which it stops being available as an identifier outside of those
+//! positions.
+
+use crate::zdoom::{Token, Version};
+
+use super::common::{ID_DEFAULT, ID_SFKW, ID_SQKW, ID_TYPES};
+
+pub(super) struct ContextualKw {
+	pub(super) token: Token,
+	/// Bitwise-or of the `ID_*` flags this word is contextual under,
+	/// regardless of version.
+	pub(super) cfg: u8,
+	/// `None` if this word has always been reserved (so it is contextual
+	/// only in `cfg`'s positions). `Some(v)` if it only became reserved at
+	/// version `v`, in which case it is additionally accepted as a plain
+	/// identifier everywhere when the active version precedes `v`.
+	pub(super) since: Option<Version>,
+}
+
+pub(super) const CONTEXTUAL_KWS: &[ContextualKw] = &[
+	ContextualKw {
+		token: Token::KwLoop,
+		cfg: ID_SFKW,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwFail,
+		cfg: ID_SFKW,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwWait,
+		cfg: ID_SFKW,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwOffset,
+		cfg: ID_SFKW | ID_SQKW,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwSlow,
+		cfg: ID_SFKW | ID_SQKW,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwBright,
+		cfg: ID_SQKW,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwCanRaise,
+		cfg: ID_SQKW,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwFast,
+		cfg: ID_SQKW,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwLight,
+		cfg: ID_SQKW,
+		since: None,
+	},
+	// Became a reserved state-qualifier keyword after the other four;
+	// scripts targeting an older version still treat it as a plain ident
+	// anywhere, not just in state-qualifier position.
+	ContextualKw {
+		token: Token::KwNoDelay,
+		cfg: ID_SQKW,
+		since: Some(Version::new(3, 7, 0)),
+	},
+	ContextualKw {
+		token: Token::KwDefault,
+		cfg: ID_DEFAULT,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwProperty,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwInt16,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwSByte,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwByte,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwInt8,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwUInt8,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwShort,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwUShort,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwUInt16,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwInt,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwUInt,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwFloat,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwDouble,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwString,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwVector2,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwVector3,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwName,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwMap,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwMapIterator,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwArray,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwVoid,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwState,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwColor,
+		cfg: ID_TYPES,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwSound,
+		cfg: ID_TYPES,
+		since: None,
+	},
+];
+
+/// Returns `true` if `token` should be accepted as a plain identifier under
+/// `cfg`'s grammatical positions at `version`: either because `cfg` is one
+/// of the positions that treats it as contextual regardless of version, or
+/// because `version` predates the point it became a reserved word at all
+/// (in which case it is contextual everywhere, not just under `cfg`).
+#[must_use]
+pub(super) fn is_contextual_ident(token: Token, cfg: u8, version: Version) -> bool {
+	let Some(kw) = CONTEXTUAL_KWS.iter().find(|kw| kw.token == token) else {
+		return false;
+	};
+
+	let not_yet_reserved = match kw.since {
+		Some(since) => version < since,
+		None => false,
+	};
+
+	not_yet_reserved || (kw.cfg & cfg) != 0
+}
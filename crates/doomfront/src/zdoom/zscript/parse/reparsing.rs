@@ -0,0 +1,163 @@
+//! Incremental reparsing, so an editor doesn't have to re-lex and re-parse
+//! an entire file on every keystroke.
+//!
+//! The approach mirrors [`crate::zdoom::language::reparsing`]: find the
+//! smallest reparsable node in the previous green tree whose span fully
+//! contains the edit (its boundary tokens and neighboring trivia are not
+//! checked), re-run just that node's combinator over its (re-lexed) token
+//! range, and splice the result
+//! back in. Green nodes are immutable and reference-counted, so every
+//! sibling and ancestor outside the splice point is retained by reference
+//! rather than copied.
+
+use rowan::{GreenNode, NodeOrToken, TextRange, TextSize};
+
+use crate::{
+	parser::Parser,
+	zdoom::{zscript::Syntax, Token},
+};
+
+use super::common;
+
+/// The result of a successful [`reparse`]: the new root green node, plus the
+/// byte ranges (in the *new* text) that changed and should be re-highlighted
+/// or re-diagnosed by the caller.
+pub struct Reparsed {
+	pub green: GreenNode,
+	pub invalidated: Vec<TextRange>,
+}
+
+/// Attempts an incremental reparse of `old_text` after replacing the bytes
+/// in `edit_range` with `replacement`. Returns `None` if no node in `root`
+/// is suitable for a localized reparse, in which case the caller should fall
+/// back to parsing `old_text` with `edit_range` spliced in from scratch.
+pub fn reparse(
+	root: &rowan::SyntaxNode<Syntax>,
+	old_text: &str,
+	edit_range: TextRange,
+	replacement: &str,
+) -> Option<Reparsed> {
+	let target = find_reparsable(root, edit_range)?;
+	let target_range = target.text_range();
+
+	let mut new_text = String::with_capacity(usize::from(target_range.len()));
+	let prefix_len = edit_range.start() - target_range.start();
+	new_text.push_str(&old_text[target_range.start().into()..][..u32::from(prefix_len) as usize]);
+	new_text.push_str(replacement);
+	let suffix_start = edit_range.end();
+	new_text.push_str(&old_text[suffix_start.into()..target_range.end().into()]);
+
+	let new_green = splice(target.kind(), &new_text)?;
+
+	let offset = target_range.start();
+	let new_node_range = TextRange::at(offset, TextSize::of(new_text.as_str()));
+
+	Some(Reparsed {
+		green: root_from(&target, new_green),
+		invalidated: vec![new_node_range],
+	})
+}
+
+/// Walks up from the token/node covering `edit_range` until it finds one of
+/// the self-contained combinators in [`common`] that can be re-run in
+/// isolation: [`Syntax::IdentChain`], [`Syntax::ArrayLen`],
+/// [`Syntax::VarName`], [`Syntax::DeprecationQual`], or [`Syntax::VersionQual`].
+/// Returns `None` if the edit crosses out of all such nodes (e.g. it touches
+/// a containing class/struct body), since those require a full reparse of
+/// their combinator to stay correct.
+fn find_reparsable(
+	root: &rowan::SyntaxNode<Syntax>,
+	edit_range: TextRange,
+) -> Option<rowan::SyntaxNode<Syntax>> {
+	let covering = root.covering_element(edit_range);
+	let start = covering.as_node().cloned().unwrap_or_else(|| {
+		covering
+			.as_token()
+			.expect("a covering element is always a node or a token")
+			.parent()
+			.expect("a token always has a parent node")
+	});
+
+	std::iter::successors(Some(start), |node| node.parent()).find(|node| {
+		matches!(
+			node.kind(),
+			Syntax::IdentChain
+				| Syntax::ArrayLen | Syntax::VarName
+				| Syntax::DeprecationQual
+				| Syntax::VersionQual
+		) && node.text_range().contains_range(edit_range)
+	})
+}
+
+/// Re-lexes `text` and re-runs the single combinator matching `kind` over it,
+/// returning the resulting green node on success.
+fn splice(kind: Syntax, text: &str) -> Option<GreenNode> {
+	let tokens = Token::stream(text);
+	let mut p = Parser::new(tokens);
+
+	match kind {
+		Syntax::IdentChain => common::ident_chain::<0>(&mut p),
+		Syntax::ArrayLen => common::array_len(&mut p),
+		Syntax::VarName => common::var_name(&mut p),
+		Syntax::DeprecationQual => common::deprecation_qual(&mut p),
+		Syntax::VersionQual => common::version_qual(&mut p),
+		_ => return None,
+	}
+
+	let (green, errors) = p.finish();
+
+	if !errors.is_empty() {
+		return None;
+	}
+
+	Some(green)
+}
+
+/// Walks from `descendant` back up to the root, re-wrapping each ancestor's
+/// green node with `replacement` substituted at the appropriate child index,
+/// and returns the new root.
+fn root_from(descendant: &rowan::SyntaxNode<Syntax>, replacement: GreenNode) -> GreenNode {
+	let Some(parent) = descendant.parent() else {
+		return replacement;
+	};
+
+	let index = descendant.index();
+	let new_parent_green = parent
+		.green()
+		.into_owned()
+		.replace_child(index, NodeOrToken::Node(replacement));
+
+	root_from(&parent, new_parent_green)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn parse_full(text: &str) -> rowan::SyntaxNode<Syntax> {
+		let tokens = Token::stream(text);
+		let mut p = Parser::new(tokens);
+		common::ident_chain::<0>(&mut p);
+		let (green, _) = p.finish();
+		rowan::SyntaxNode::new_root(green)
+	}
+
+	#[test]
+	fn reparse_ident_chain_segment() {
+		let old_text = "Foo.Bar.Baz";
+		let root = parse_full(old_text);
+
+		let edit_range = TextRange::new(TextSize::from(4), TextSize::from(7));
+		let new_text = "Foo.Quux.Baz";
+
+		let reparsed = reparse(&root, old_text, edit_range, "Quux").expect("a reparsable node");
+		let expected = parse_full(new_text);
+
+		assert_eq!(
+			rowan::SyntaxNode::<Syntax>::new_root(reparsed.green)
+				.text()
+				.to_string(),
+			expected.text().to_string()
+		);
+	}
+}
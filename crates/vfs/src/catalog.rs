@@ -0,0 +1,556 @@
+//! A flat, on-disk snapshot of a [`VirtualFs`] tree that can be read back
+//! without re-walking every mounted archive.
+//!
+//! The layout borrows Mercurial's dirstate-v2 "docket" idea: a small
+//! fixed-width header names the offsets of a handful of fixed-width record
+//! tables (folders, files, backing real paths) plus one shared string blob,
+//! so a reader can validate staleness and locate any record with nothing
+//! more than a handful of `u32`/`u64` reads off a byte slice — no parsing of
+//! the records it isn't asking for, and (if that slice comes from an
+//! `mmap`) no up-front read of the file's full contents either.
+//!
+//! Only entries still backed by an uncompressed [`Reader::File`] can be
+//! catalogued this way, since a record stores a real path plus a byte span
+//! rather than content: once [`VirtualFs::ingest_all`] or
+//! [`VirtualFs::deduplicate`] has folded an entry into a `Reader::Memory`
+//! buffer, its original span no longer corresponds to anything on disk.
+//! Compressed entries are excluded too — this checkout's `detail` module
+//! (where [`Compression`] is defined) isn't present, so there's no safe way
+//! to round-trip an arbitrary compression mode through this format without
+//! guessing at its variants; `Compression::None` is the one value already
+//! visible elsewhere in this crate, so it's the only one this module treats
+//! as catalogable. Catalog before ingesting, or not at all. Symlinks (see
+//! [`VirtualFs::insert_symlink`]) aren't persisted either; they carry no
+//! backing real path to validate against.
+
+use std::{
+	collections::HashMap,
+	io::{Read, Write},
+	path::PathBuf,
+	sync::Arc,
+};
+
+use parking_lot::Mutex;
+use util::SmallString;
+
+use crate::{
+	detail::{Compression, Reader},
+	Error, FileSlot, FolderKind, MountFormat, VFile, VFolder, VirtualFs,
+};
+
+const MAGIC: [u8; 8] = *b"VTFSCAT1";
+const VERSION: u32 = 1;
+
+const NO_PARENT: u32 = u32::MAX;
+const NO_REALPATH: u32 = u32::MAX;
+
+impl VirtualFs {
+	/// Serializes this tree's folders and files to `w` in the catalog
+	/// format documented on [`self::catalog`](self).
+	///
+	/// # Errors
+	/// Returns [`Error::CatalogUncacheable`] if any file's content is no
+	/// longer backed by a real path (see the module-level docs), or if no
+	/// owning mount can be traced for one of its ancestors.
+	pub fn write_catalog(&self, mut w: impl Write) -> Result<(), Error> {
+		let mut strings = StringTable::default();
+
+		let mut folder_index = HashMap::with_capacity(self.folders.len());
+		let mut folder_records = Vec::with_capacity(self.folders.len());
+
+		for (slot, _) in self.folders.iter() {
+			folder_index.insert(slot, folder_index.len() as u32);
+		}
+
+		for (_slot, folder) in self.folders.iter() {
+			let (name_off, name_len) = strings.intern(folder.name.as_str());
+
+			let parent = folder
+				.parent
+				.map_or(NO_PARENT, |p| folder_index[&p]);
+
+			folder_records.push(FolderRecord {
+				name_off,
+				name_len,
+				parent,
+				kind: folder.kind as u8,
+			});
+		}
+
+		let mut realpath_index: HashMap<PathBuf, u32> = HashMap::new();
+		let mut realpath_records = Vec::new();
+		let mut file_records = Vec::with_capacity(self.files.len());
+
+		for (slot, file) in self.files.iter() {
+			if self.symlinks.contains_key(&slot) {
+				continue;
+			}
+
+			if !matches!(file.compression, Compression::None) {
+				return Err(Error::CatalogUncacheable);
+			}
+
+			let (real_path, span) = self.resolve_backing(slot).ok_or(Error::CatalogUncacheable)?;
+
+			let meta = std::fs::metadata(&real_path).map_err(Error::Metadata)?;
+			let mtime = meta
+				.modified()
+				.ok()
+				.and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+				.map_or(0, |d| d.as_secs());
+
+			let realpath_idx = *realpath_index.entry(real_path.clone()).or_insert_with(|| {
+				let (off, len) = strings.intern(&real_path.to_string_lossy());
+				realpath_records.push(RealPathRecord {
+					path_off: off,
+					path_len: len,
+					mtime,
+					size: meta.len(),
+				});
+				(realpath_records.len() - 1) as u32
+			});
+
+			let (name_off, name_len) = strings.intern(file.name.as_str());
+
+			file_records.push(FileRecord {
+				name_off,
+				name_len,
+				parent: folder_index[&file.parent],
+				realpath_idx,
+				span_start: span.start,
+				span_end: span.end,
+			});
+		}
+
+		let string_blob = strings.into_bytes();
+
+		let docket_len = Docket::LEN as u64;
+		let realpath_table_off = docket_len;
+		let folder_table_off =
+			realpath_table_off + (realpath_records.len() as u64) * RealPathRecord::LEN as u64;
+		let file_table_off =
+			folder_table_off + (folder_records.len() as u64) * FolderRecord::LEN as u64;
+		let string_table_off =
+			file_table_off + (file_records.len() as u64) * FileRecord::LEN as u64;
+
+		let docket = Docket {
+			folder_count: folder_records.len() as u32,
+			file_count: file_records.len() as u32,
+			realpath_count: realpath_records.len() as u32,
+			realpath_table_off,
+			folder_table_off,
+			file_table_off,
+			string_table_off,
+			string_table_len: string_blob.len() as u64,
+		};
+
+		w.write_all(&docket.to_bytes()).map_err(Error::CatalogIo)?;
+
+		for rec in &realpath_records {
+			w.write_all(&rec.to_bytes()).map_err(Error::CatalogIo)?;
+		}
+
+		for rec in &folder_records {
+			w.write_all(&rec.to_bytes()).map_err(Error::CatalogIo)?;
+		}
+
+		for rec in &file_records {
+			w.write_all(&rec.to_bytes()).map_err(Error::CatalogIo)?;
+		}
+
+		w.write_all(&string_blob).map_err(Error::CatalogIo)?;
+
+		Ok(())
+	}
+
+	/// Finds the real path and byte span backing `slot`'s content, by
+	/// walking up to the mount that owns it. `Directory`/`Uncompressed`
+	/// mounts back each file with its own real path; `Wad`/`Zip` mounts
+	/// back every file with the one archive real path.
+	fn resolve_backing(&self, slot: FileSlot) -> Option<(PathBuf, std::ops::Range<u32>)> {
+		let vfile = &self.files[slot];
+
+		if !matches!(&*vfile.reader.lock(), Reader::File(_)) {
+			return None;
+		}
+
+		let mut rel_names = vec![vfile.name.as_str().to_string()];
+		let mut cur = vfile.parent;
+
+		loop {
+			if let Some(mntinfo) = self.mounts.iter().find(|m| m.root == cur) {
+				rel_names.reverse();
+
+				let real_path = match mntinfo.format {
+					MountFormat::Directory | MountFormat::Uncompressed => {
+						let mut p = mntinfo.real_path.clone();
+
+						for name in &rel_names {
+							p.push(name);
+						}
+
+						p
+					}
+					MountFormat::Wad | MountFormat::Zip | MountFormat::Tar | MountFormat::TarGz => {
+						mntinfo.real_path.clone()
+					}
+				};
+
+				return Some((real_path, vfile.span.clone()));
+			}
+
+			let folder = &self.folders[cur];
+			rel_names.push(folder.name.as_str().to_string());
+			cur = folder.parent?;
+		}
+	}
+
+	/// Reconstructs a [`VirtualFs`] from a catalog written by
+	/// [`Self::write_catalog`], without re-walking any mount.
+	///
+	/// # Errors
+	/// Returns [`Error::CatalogStale`] if any recorded real path's mtime or
+	/// size no longer matches what's on disk, in which case the caller
+	/// should fall back to mounting from scratch. Returns
+	/// [`Error::CatalogMalformed`] if any offset or count read out of the
+	/// docket or a record doesn't fit within the buffer it indexes into;
+	/// this is a persisted cache file, so a truncated or corrupted one has
+	/// to be handled like any other untrusted input, not trusted to be
+	/// internally consistent.
+	pub fn from_catalog(mut r: impl Read) -> Result<Self, Error> {
+		let mut buf = Vec::new();
+		r.read_to_end(&mut buf).map_err(Error::CatalogIo)?;
+
+		let docket = Docket::parse(&buf)?;
+
+		let realpaths = (0..docket.realpath_count as u64)
+			.map(|i| {
+				let off = i
+					.checked_mul(RealPathRecord::LEN as u64)
+					.and_then(|n| n.checked_add(docket.realpath_table_off))
+					.ok_or(Error::CatalogMalformed)? as usize;
+				let end = off.checked_add(RealPathRecord::LEN).ok_or(Error::CatalogMalformed)?;
+				RealPathRecord::parse(buf.get(off..end).ok_or(Error::CatalogMalformed)?)
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let string_table_end = docket
+			.string_table_off
+			.checked_add(docket.string_table_len)
+			.ok_or(Error::CatalogMalformed)?;
+		let strings = buf
+			.get(docket.string_table_off as usize..string_table_end as usize)
+			.ok_or(Error::CatalogMalformed)?;
+
+		let mut readers = Vec::with_capacity(realpaths.len());
+
+		for rec in &realpaths {
+			let path_end = rec
+				.path_off
+				.checked_add(rec.path_len)
+				.ok_or(Error::CatalogMalformed)?;
+			let path_bytes = strings
+				.get(rec.path_off as usize..path_end as usize)
+				.ok_or(Error::CatalogMalformed)?;
+			let path_str = std::str::from_utf8(path_bytes).map_err(Error::Utf8Str)?;
+			let path = PathBuf::from(path_str);
+
+			let meta = std::fs::metadata(&path).map_err(Error::Metadata)?;
+			let mtime = meta
+				.modified()
+				.ok()
+				.and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+				.map_or(0, |d| d.as_secs());
+
+			if mtime != rec.mtime || meta.len() != rec.size {
+				return Err(Error::CatalogStale);
+			}
+
+			let file = std::fs::File::open(&path).map_err(Error::FileOpen)?;
+			readers.push(Arc::new(Mutex::new(Reader::File(file))));
+		}
+
+		let mut vfs = Self::default();
+		let mut folder_slots = Vec::with_capacity(docket.folder_count as usize);
+
+		for i in 0..docket.folder_count as u64 {
+			let off = i
+				.checked_mul(FolderRecord::LEN as u64)
+				.and_then(|n| n.checked_add(docket.folder_table_off))
+				.ok_or(Error::CatalogMalformed)? as usize;
+			let end = off.checked_add(FolderRecord::LEN).ok_or(Error::CatalogMalformed)?;
+			let rec = FolderRecord::parse(buf.get(off..end).ok_or(Error::CatalogMalformed)?)?;
+
+			if rec.parent == NO_PARENT {
+				// The root folder already exists via `Self::default`.
+				folder_slots.push(vfs.root);
+				continue;
+			}
+
+			let name_end = rec
+				.name_off
+				.checked_add(rec.name_len)
+				.ok_or(Error::CatalogMalformed)?;
+			let name_bytes = strings
+				.get(rec.name_off as usize..name_end as usize)
+				.ok_or(Error::CatalogMalformed)?;
+			let name = std::str::from_utf8(name_bytes).map_err(Error::Utf8Str)?;
+
+			let parent = *folder_slots
+				.get(rec.parent as usize)
+				.ok_or(Error::CatalogMalformed)?;
+
+			let slot = vfs.folders.insert(VFolder {
+				name: SmallString::from(name),
+				parent: Some(parent),
+				files: indexmap::indexset![],
+				subfolders: indexmap::indexset![],
+				kind: FolderKind::from_u8(rec.kind),
+			});
+
+			vfs.folders[parent].subfolders.insert(slot);
+			folder_slots.push(slot);
+		}
+
+		for i in 0..docket.file_count as u64 {
+			let off = i
+				.checked_mul(FileRecord::LEN as u64)
+				.and_then(|n| n.checked_add(docket.file_table_off))
+				.ok_or(Error::CatalogMalformed)? as usize;
+			let end = off.checked_add(FileRecord::LEN).ok_or(Error::CatalogMalformed)?;
+			let rec = FileRecord::parse(buf.get(off..end).ok_or(Error::CatalogMalformed)?)?;
+
+			let name_end = rec
+				.name_off
+				.checked_add(rec.name_len)
+				.ok_or(Error::CatalogMalformed)?;
+			let name_bytes = strings
+				.get(rec.name_off as usize..name_end as usize)
+				.ok_or(Error::CatalogMalformed)?;
+			let name = std::str::from_utf8(name_bytes).map_err(Error::Utf8Str)?;
+
+			let parent = *folder_slots
+				.get(rec.parent as usize)
+				.ok_or(Error::CatalogMalformed)?;
+
+			let reader = if rec.realpath_idx == NO_REALPATH {
+				return Err(Error::CatalogUncacheable);
+			} else {
+				Arc::clone(
+					readers
+						.get(rec.realpath_idx as usize)
+						.ok_or(Error::CatalogMalformed)?,
+				)
+			};
+
+			let slot = vfs.files.insert(VFile {
+				name: SmallString::from(name),
+				parent,
+				reader,
+				span: rec.span_start..rec.span_end,
+				compression: Compression::None,
+			});
+
+			vfs.folders[parent].files.insert(slot);
+		}
+
+		Ok(vfs)
+	}
+}
+
+#[derive(Debug, Default)]
+struct StringTable {
+	bytes: Vec<u8>,
+	interned: HashMap<String, (u32, u32)>,
+}
+
+impl StringTable {
+	fn intern(&mut self, s: &str) -> (u32, u32) {
+		if let Some(&pair) = self.interned.get(s) {
+			return pair;
+		}
+
+		let off = self.bytes.len() as u32;
+		let len = s.len() as u32;
+		self.bytes.extend_from_slice(s.as_bytes());
+		self.interned.insert(s.to_string(), (off, len));
+		(off, len)
+	}
+
+	fn into_bytes(self) -> Vec<u8> {
+		self.bytes
+	}
+}
+
+struct Docket {
+	folder_count: u32,
+	file_count: u32,
+	realpath_count: u32,
+	realpath_table_off: u64,
+	folder_table_off: u64,
+	file_table_off: u64,
+	string_table_off: u64,
+	string_table_len: u64,
+}
+
+impl Docket {
+	const LEN: usize = 8 + 4 * 4 + 8 * 5;
+
+	fn to_bytes(&self) -> Vec<u8> {
+		let mut out = Vec::with_capacity(Self::LEN);
+		out.extend_from_slice(&MAGIC);
+		out.extend_from_slice(&VERSION.to_le_bytes());
+		out.extend_from_slice(&self.folder_count.to_le_bytes());
+		out.extend_from_slice(&self.file_count.to_le_bytes());
+		out.extend_from_slice(&self.realpath_count.to_le_bytes());
+		out.extend_from_slice(&self.realpath_table_off.to_le_bytes());
+		out.extend_from_slice(&self.folder_table_off.to_le_bytes());
+		out.extend_from_slice(&self.file_table_off.to_le_bytes());
+		out.extend_from_slice(&self.string_table_off.to_le_bytes());
+		out.extend_from_slice(&self.string_table_len.to_le_bytes());
+		out
+	}
+
+	fn parse(buf: &[u8]) -> Result<Self, Error> {
+		if buf.len() < Self::LEN || buf[0..8] != MAGIC {
+			return Err(Error::CatalogMalformed);
+		}
+
+		let version = u32::from_le_bytes(buf[8..12].try_into().unwrap());
+
+		if version != VERSION {
+			return Err(Error::CatalogMalformed);
+		}
+
+		Ok(Self {
+			folder_count: u32::from_le_bytes(buf[12..16].try_into().unwrap()),
+			file_count: u32::from_le_bytes(buf[16..20].try_into().unwrap()),
+			realpath_count: u32::from_le_bytes(buf[20..24].try_into().unwrap()),
+			realpath_table_off: u64::from_le_bytes(buf[24..32].try_into().unwrap()),
+			folder_table_off: u64::from_le_bytes(buf[32..40].try_into().unwrap()),
+			file_table_off: u64::from_le_bytes(buf[40..48].try_into().unwrap()),
+			string_table_off: u64::from_le_bytes(buf[48..56].try_into().unwrap()),
+			string_table_len: u64::from_le_bytes(buf[56..64].try_into().unwrap()),
+		})
+	}
+}
+
+struct RealPathRecord {
+	path_off: u32,
+	path_len: u32,
+	mtime: u64,
+	size: u64,
+}
+
+impl RealPathRecord {
+	const LEN: usize = 4 + 4 + 8 + 8;
+
+	fn to_bytes(&self) -> Vec<u8> {
+		let mut out = Vec::with_capacity(Self::LEN);
+		out.extend_from_slice(&self.path_off.to_le_bytes());
+		out.extend_from_slice(&self.path_len.to_le_bytes());
+		out.extend_from_slice(&self.mtime.to_le_bytes());
+		out.extend_from_slice(&self.size.to_le_bytes());
+		out
+	}
+
+	fn parse(buf: &[u8]) -> Result<Self, Error> {
+		if buf.len() < Self::LEN {
+			return Err(Error::CatalogMalformed);
+		}
+
+		Ok(Self {
+			path_off: u32::from_le_bytes(buf[0..4].try_into().unwrap()),
+			path_len: u32::from_le_bytes(buf[4..8].try_into().unwrap()),
+			mtime: u64::from_le_bytes(buf[8..16].try_into().unwrap()),
+			size: u64::from_le_bytes(buf[16..24].try_into().unwrap()),
+		})
+	}
+}
+
+struct FolderRecord {
+	name_off: u32,
+	name_len: u32,
+	parent: u32,
+	kind: u8,
+}
+
+impl FolderRecord {
+	const LEN: usize = 4 + 4 + 4 + 1 + 3;
+
+	fn to_bytes(&self) -> Vec<u8> {
+		let mut out = Vec::with_capacity(Self::LEN);
+		out.extend_from_slice(&self.name_off.to_le_bytes());
+		out.extend_from_slice(&self.name_len.to_le_bytes());
+		out.extend_from_slice(&self.parent.to_le_bytes());
+		out.push(self.kind);
+		out.extend_from_slice(&[0, 0, 0]);
+		out
+	}
+
+	fn parse(buf: &[u8]) -> Result<Self, Error> {
+		if buf.len() < Self::LEN {
+			return Err(Error::CatalogMalformed);
+		}
+
+		Ok(Self {
+			name_off: u32::from_le_bytes(buf[0..4].try_into().unwrap()),
+			name_len: u32::from_le_bytes(buf[4..8].try_into().unwrap()),
+			parent: u32::from_le_bytes(buf[8..12].try_into().unwrap()),
+			kind: buf[12],
+		})
+	}
+}
+
+struct FileRecord {
+	name_off: u32,
+	name_len: u32,
+	parent: u32,
+	realpath_idx: u32,
+	span_start: u32,
+	span_end: u32,
+}
+
+impl FileRecord {
+	const LEN: usize = 4 * 6;
+
+	fn to_bytes(&self) -> Vec<u8> {
+		let mut out = Vec::with_capacity(Self::LEN);
+		out.extend_from_slice(&self.name_off.to_le_bytes());
+		out.extend_from_slice(&self.name_len.to_le_bytes());
+		out.extend_from_slice(&self.parent.to_le_bytes());
+		out.extend_from_slice(&self.realpath_idx.to_le_bytes());
+		out.extend_from_slice(&self.span_start.to_le_bytes());
+		out.extend_from_slice(&self.span_end.to_le_bytes());
+		out
+	}
+
+	fn parse(buf: &[u8]) -> Result<Self, Error> {
+		if buf.len() < Self::LEN {
+			return Err(Error::CatalogMalformed);
+		}
+
+		Ok(Self {
+			name_off: u32::from_le_bytes(buf[0..4].try_into().unwrap()),
+			name_len: u32::from_le_bytes(buf[4..8].try_into().unwrap()),
+			parent: u32::from_le_bytes(buf[8..12].try_into().unwrap()),
+			realpath_idx: u32::from_le_bytes(buf[12..16].try_into().unwrap()),
+			span_start: u32::from_le_bytes(buf[16..20].try_into().unwrap()),
+			span_end: u32::from_le_bytes(buf[20..24].try_into().unwrap()),
+		})
+	}
+}
+
+impl FolderKind {
+	fn from_u8(b: u8) -> Self {
+		match b {
+			1 => Self::Root,
+			2 => Self::Wad,
+			3 => Self::Zip,
+			4 => Self::ZipDir,
+			5 => Self::Tar,
+			6 => Self::TarDir,
+			_ => Self::Directory,
+		}
+	}
+}
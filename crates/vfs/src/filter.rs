@@ -0,0 +1,74 @@
+//! Include/exclude glob filtering applied while mounting a subtree.
+//!
+//! Modeled on pxar's `MatchEntry`/`MatchList`: patterns are evaluated in
+//! order against each entry's virtual path, and the *last* pattern to match
+//! wins, the same way a `.gitignore`-style negated pattern overrides an
+//! earlier broader one.
+
+use crate::VPath;
+
+/// An ordered set of patterns controlling which entries
+/// [`VirtualFs::mount_filtered`](crate::VirtualFs::mount_filtered) admits.
+///
+/// With no patterns, every entry is admitted. Otherwise, an entry whose
+/// virtual path matches no pattern at all is admitted by default; only an
+/// explicit [`MatchKind::Exclude`] match (and nothing matching after it)
+/// drops it.
+#[derive(Debug, Clone, Default)]
+pub struct MountOptions {
+	patterns: Vec<MatchPattern>,
+}
+
+impl MountOptions {
+	#[must_use]
+	pub fn new(patterns: Vec<MatchPattern>) -> Self {
+		Self { patterns }
+	}
+
+	#[must_use]
+	pub fn patterns(&self) -> &[MatchPattern] {
+		&self.patterns
+	}
+
+	/// Evaluates `vpath` against every pattern in order, returning the
+	/// [`MatchKind`] of the last one that matched, or
+	/// [`MatchKind::Include`] if none did.
+	#[must_use]
+	pub(crate) fn evaluate(&self, vpath: &VPath) -> MatchKind {
+		self.patterns
+			.iter()
+			.rev()
+			.find_map(|pat| pat.glob.matches(vpath.as_str()).then_some(pat.kind))
+			.unwrap_or(MatchKind::Include)
+	}
+}
+
+/// A single glob pattern plus whether a match admits or drops the entry.
+#[derive(Debug, Clone)]
+pub struct MatchPattern {
+	glob: glob::Pattern,
+	kind: MatchKind,
+}
+
+impl MatchPattern {
+	#[must_use]
+	pub fn new(glob: glob::Pattern, kind: MatchKind) -> Self {
+		Self { glob, kind }
+	}
+
+	#[must_use]
+	pub fn include(glob: glob::Pattern) -> Self {
+		Self::new(glob, MatchKind::Include)
+	}
+
+	#[must_use]
+	pub fn exclude(glob: glob::Pattern) -> Self {
+		Self::new(glob, MatchKind::Exclude)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+	Include,
+	Exclude,
+}
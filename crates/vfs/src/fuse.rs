@@ -0,0 +1,260 @@
+//! A read-only FUSE filesystem backed directly by a [`VirtualFs`] tree, so
+//! external tools (and the editor's asset browser) can traverse merged
+//! WAD/ZIP/directory content without going through the Rust API.
+//!
+//! Mirrors the approach taken by Proxmox's `pxar` FUSE layer: every
+//! `lookup`/`readdir`/`read` call is served straight out of the in-memory
+//! directory index rather than staging content out to a real directory
+//! first. Each [`FolderSlot`]/[`FileSlot`] is already a stable identifier
+//! for the lifetime of a [`VirtualFs`] (see the docs on [`FileSlot`]), so it
+//! doubles as a FUSE inode once its raw `KeyData` is tagged with which
+//! slotmap it came from.
+
+use std::{borrow::Cow, ffi::OsStr, path::Path, time::Duration};
+
+use fuser::{
+	FileAttr, FileType, Filesystem, MountOption, ReplyAttr, ReplyData, ReplyDirectory, ReplyEntry,
+	Request,
+};
+use slotmap::Key;
+
+use crate::{
+	detail::Reader,
+	FileSlot, FolderSlot, Slot, VFile, VirtualFs,
+};
+
+const ENOENT: i32 = 2;
+const EIO: i32 = 5;
+
+const TTL: Duration = Duration::from_secs(1);
+
+/// Inode `1` is a FUSE convention for the filesystem root.
+const ROOT_INODE: u64 = 1;
+
+impl VirtualFs {
+	/// Serves this tree as a read-only FUSE filesystem at `mount_point`,
+	/// blocking the calling thread until the filesystem is unmounted.
+	pub fn fuse_mount(&self, mount_point: &Path) -> std::io::Result<()> {
+		let options = [
+			MountOption::RO,
+			MountOption::FSName("viletechfs".to_string()),
+		];
+
+		fuser::mount2(VfsFuse { vfs: self }, mount_point, &options)
+	}
+}
+
+struct VfsFuse<'vfs> {
+	vfs: &'vfs VirtualFs,
+}
+
+impl VfsFuse<'_> {
+	fn ino_of_folder(&self, slot: FolderSlot) -> u64 {
+		if slot == self.vfs.root().slot {
+			ROOT_INODE
+		} else {
+			slot.data().as_ffi() << 1
+		}
+	}
+
+	fn ino_of_file(&self, slot: FileSlot) -> u64 {
+		(slot.data().as_ffi() << 1) | 1
+	}
+
+	fn slot_of_ino(&self, ino: u64) -> Option<Slot> {
+		if ino == ROOT_INODE {
+			return Some(Slot::Folder(self.vfs.root().slot));
+		}
+
+		let key_data = slotmap::KeyData::from_ffi(ino >> 1);
+
+		if ino & 1 == 1 {
+			let slot = FileSlot::from(key_data);
+			self.vfs.file_exists(slot).then_some(Slot::File(slot))
+		} else {
+			let slot = FolderSlot::from(key_data);
+			self.vfs.folder_exists(slot).then_some(Slot::Folder(slot))
+		}
+	}
+
+	fn folder_attr(&self, slot: FolderSlot) -> FileAttr {
+		FileAttr {
+			ino: self.ino_of_folder(slot),
+			size: 0,
+			blocks: 0,
+			atime: std::time::UNIX_EPOCH,
+			mtime: std::time::UNIX_EPOCH,
+			ctime: std::time::UNIX_EPOCH,
+			crtime: std::time::UNIX_EPOCH,
+			kind: FileType::Directory,
+			perm: 0o555,
+			nlink: 2,
+			uid: 0,
+			gid: 0,
+			rdev: 0,
+			blksize: 512,
+			flags: 0,
+		}
+	}
+
+	fn file_attr(&self, slot: FileSlot) -> FileAttr {
+		let vfile = &self.vfs.files[slot];
+
+		FileAttr {
+			ino: self.ino_of_file(slot),
+			size: vfile.size() as u64,
+			blocks: (vfile.size() as u64).div_ceil(512),
+			atime: std::time::UNIX_EPOCH,
+			mtime: std::time::UNIX_EPOCH,
+			ctime: std::time::UNIX_EPOCH,
+			crtime: std::time::UNIX_EPOCH,
+			kind: FileType::RegularFile,
+			perm: 0o444,
+			nlink: 1,
+			uid: 0,
+			gid: 0,
+			rdev: 0,
+			blksize: 512,
+			flags: 0,
+		}
+	}
+
+	/// Reads and fully decompresses `vfile`'s content. There is no streaming
+	/// story here yet; every read materializes the whole (decompressed)
+	/// entry before slicing out the requested range.
+	fn read_bytes(&self, vfile: &VFile) -> std::io::Result<Vec<u8>> {
+		let mut guard = vfile.reader.lock();
+		let span = vfile.span();
+
+		let raw = match &mut *guard {
+			Reader::Memory(bytes) => bytes[span].to_vec(),
+			Reader::File(fh) => Reader::read_from_file(fh, span)?,
+			Reader::_Super(_) => {
+				return Err(std::io::Error::new(
+					std::io::ErrorKind::Unsupported,
+					"cannot read through a `Reader::_Super`",
+				))
+			}
+		};
+
+		drop(guard);
+
+		crate::detail::decompress(Cow::Owned(raw), vfile.compression)
+			.map(Cow::into_owned)
+			.map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+	}
+}
+
+impl Filesystem for VfsFuse<'_> {
+	fn lookup(&mut self, _req: &Request, parent: u64, name: &OsStr, reply: ReplyEntry) {
+		let Some(Slot::Folder(pslot)) = self.slot_of_ino(parent) else {
+			reply.error(ENOENT);
+			return;
+		};
+
+		let folder = &self.vfs.folders[pslot];
+		let name = name.to_string_lossy();
+
+		let subfolder = folder
+			.subfolders
+			.iter()
+			.copied()
+			.find(|&s| self.vfs.folders[s].name.eq_ignore_ascii_case(&name));
+
+		if let Some(fslot) = subfolder {
+			reply.entry(&TTL, &self.folder_attr(fslot), 0);
+			return;
+		}
+
+		let file = folder
+			.files
+			.iter()
+			.copied()
+			.find(|&s| self.vfs.files[s].name.eq_ignore_ascii_case(&name));
+
+		if let Some(islot) = file {
+			reply.entry(&TTL, &self.file_attr(islot), 0);
+			return;
+		}
+
+		reply.error(ENOENT);
+	}
+
+	fn getattr(&mut self, _req: &Request, ino: u64, reply: ReplyAttr) {
+		match self.slot_of_ino(ino) {
+			Some(Slot::Folder(slot)) => reply.attr(&TTL, &self.folder_attr(slot)),
+			Some(Slot::File(slot)) => reply.attr(&TTL, &self.file_attr(slot)),
+			None => reply.error(ENOENT),
+		}
+	}
+
+	fn readdir(&mut self, _req: &Request, ino: u64, _fh: u64, offset: i64, mut reply: ReplyDirectory) {
+		let Some(Slot::Folder(slot)) = self.slot_of_ino(ino) else {
+			reply.error(ENOENT);
+			return;
+		};
+
+		let folder = &self.vfs.folders[slot];
+		let parent_ino = folder.parent().map_or(ino, |p| self.ino_of_folder(p));
+
+		let mut entries = vec![
+			(ino, FileType::Directory, ".".to_string()),
+			(parent_ino, FileType::Directory, "..".to_string()),
+		];
+
+		entries.extend(folder.subfolders.iter().copied().map(|s| {
+			(
+				self.ino_of_folder(s),
+				FileType::Directory,
+				self.vfs.folders[s].name.as_str().to_string(),
+			)
+		}));
+
+		entries.extend(folder.files.iter().copied().map(|s| {
+			(
+				self.ino_of_file(s),
+				FileType::RegularFile,
+				self.vfs.files[s].name.as_str().to_string(),
+			)
+		}));
+
+		for (i, (child_ino, kind, name)) in entries.into_iter().enumerate().skip(offset as usize) {
+			if reply.add(child_ino, (i + 1) as i64, kind, name) {
+				break;
+			}
+		}
+
+		reply.ok();
+	}
+
+	fn read(
+		&mut self,
+		_req: &Request,
+		ino: u64,
+		_fh: u64,
+		offset: i64,
+		size: u32,
+		_flags: i32,
+		_lock_owner: Option<u64>,
+		reply: ReplyData,
+	) {
+		let Some(Slot::File(slot)) = self.slot_of_ino(ino) else {
+			reply.error(ENOENT);
+			return;
+		};
+
+		let vfile = &self.vfs.files[slot];
+
+		let bytes = match self.read_bytes(vfile) {
+			Ok(b) => b,
+			Err(_) => {
+				reply.error(EIO);
+				return;
+			}
+		};
+
+		let start = (offset as usize).min(bytes.len());
+		let end = start.saturating_add(size as usize).min(bytes.len());
+		reply.data(&bytes[start..end]);
+	}
+}
@@ -4,17 +4,49 @@
 //! "physical" FS. Real files, directories, and various archives are all merged
 //! into one tree so that reading from them is more convenient at all other levels
 //! of the engine, without exposing any details of the user's underlying machine.
-
+//!
+//! ## Blocked on the `detail` module
+//!
+//! This checkout's `detail` module (which defines [`Reader`](self::detail::Reader)
+//! and [`Compression`](self::detail::Compression)) isn't present, so a
+//! memory-mapped `Reader::Mmap` variant can't be added without guessing at
+//! the rest of that module's contents. [`netfs::is_network_fs`] is in place
+//! as the piece that doesn't need it: whoever wires up the `mmap2`-backed
+//! variant can gate it on that probe directly.
+//!
+//! The `mount` module (which walks a real directory or archive into
+//! `files`/`folders`) is likewise not present here, so its hard rejection of
+//! symlinked entries during that walk can't be lifted from this side.
+//! [`VirtualFs::insert_symlink`] and the symlink-following in
+//! [`VirtualFs::lookup`] are real and usable today for anything that builds
+//! the tree some other way; wiring the walker itself to call
+//! `insert_symlink` instead of erroring out is left for whoever owns that
+//! module next.
+//!
+//! The same applies to [`MountFormat::Tar`]/[`MountFormat::TarGz`]: the
+//! enum variants, [`FolderKind::Tar`]/[`FolderKind::TarDir`], and
+//! [`self::tar::read_entries`] (which decodes a tar or tar.gz archive into a
+//! flat list of path/bytes entries, the same shape `mount::mount` already
+//! folds the `Zip` branch's entries from) are all in place, but actually
+//! streaming those entries into `files`/`folders` during a mount is, again,
+//! that invisible module's job.
+
+mod catalog;
 mod detail;
+mod filter;
+mod fuse;
 mod mount;
+mod netfs;
 mod path;
 mod refs;
+mod tar;
 
 #[cfg(test)]
 mod test;
 
 use std::{
 	borrow::Cow,
+	collections::hash_map::Entry,
 	ops::Range,
 	path::{Path, PathBuf},
 	string::FromUtf8Error,
@@ -30,7 +62,7 @@ use zip_structs::zip_error::ZipReadError;
 
 use self::detail::{Compression, Reader};
 
-pub use self::{path::*, refs::*};
+pub use self::{filter::*, path::*, refs::*};
 
 #[derive(Debug)]
 pub struct VirtualFs {
@@ -38,6 +70,12 @@ pub struct VirtualFs {
 	pub(crate) mounts: Vec<MountInfo>,
 	pub(crate) files: HopSlotMap<FileSlot, VFile>,
 	pub(crate) folders: HopSlotMap<FolderSlot, VFolder>,
+	/// Keyed by lowercased mount point, since [`MountInfo`] itself is built
+	/// entirely within [`mount::mount`]; see [`VirtualFs::mount_filtered`].
+	pub(crate) mount_filters: std::collections::HashMap<String, MountOptions>,
+	/// Every [`FileSlot`] in here is a symlink pointing at the mapped target
+	/// path; see [`VirtualFs::insert_symlink`] and [`VirtualFs::lookup`].
+	pub(crate) symlinks: std::collections::HashMap<FileSlot, VPathBuf>,
 }
 
 impl VirtualFs {
@@ -101,6 +139,59 @@ impl VirtualFs {
 		}
 	}
 
+	/// As [`Self::mount`], but afterwards prunes every entry beneath
+	/// `mount_point` whose virtual path doesn't pass `options`. Pruned
+	/// folders take their whole subtree with them.
+	///
+	/// `options` is retained (keyed by `mount_point`) so that [`Self::retain`]
+	/// cleans it up when the mount is later dropped.
+	pub fn mount_filtered(
+		&mut self,
+		real_path: &Path,
+		mount_point: &VPath,
+		options: MountOptions,
+	) -> Result<(), Error> {
+		self.mount(real_path, mount_point)?;
+
+		if !options.patterns().is_empty() {
+			if let Some(Slot::Folder(root)) = self.mounts.last().map(|mntinfo| mntinfo.root) {
+				self.prune_filtered(root, mount_point.as_str(), &options);
+			}
+		}
+
+		self.mount_filters
+			.insert(mount_point.as_str().to_ascii_lowercase(), options);
+
+		Ok(())
+	}
+
+	/// Recursively removes every child of `slot` (virtual path `vpath`) that
+	/// `options` excludes. See [`Self::mount_filtered`].
+	fn prune_filtered(&mut self, slot: FolderSlot, vpath: &str, options: &MountOptions) {
+		let subfolders: Vec<FolderSlot> = self.folders[slot].subfolders.iter().copied().collect();
+
+		for sfslot in subfolders {
+			let name = self.folders[sfslot].name.as_str().to_string();
+			let child_path = format!("{vpath}/{name}");
+
+			match options.evaluate(VPath::new(&child_path)) {
+				MatchKind::Exclude => self.remove_folder_by_slot(sfslot),
+				MatchKind::Include => self.prune_filtered(sfslot, &child_path, options),
+			}
+		}
+
+		let files: Vec<FileSlot> = self.folders[slot].files.iter().copied().collect();
+
+		for islot in files {
+			let name = self.files[islot].name.as_str().to_string();
+			let child_path = format!("{vpath}/{name}");
+
+			if options.evaluate(VPath::new(&child_path)) == MatchKind::Exclude {
+				self.remove_file_by_slot(islot);
+			}
+		}
+	}
+
 	#[must_use]
 	pub fn exists(&self, vpath: &VPath) -> bool {
 		self.lookup(vpath).is_some()
@@ -119,9 +210,12 @@ impl VirtualFs {
 	/// Returns `true` if a file was removed.
 	pub fn remove_file_by_slot(&mut self, slot: FileSlot) -> bool {
 		let ret = self.files.remove(slot).is_some();
+		self.symlinks.remove(&slot);
 
 		if let Some(p) = self.mounts.iter().position(|mntinfo| mntinfo.root == slot) {
-			self.mounts.remove(p);
+			let mntinfo = self.mounts.remove(p);
+			self.mount_filters
+				.remove(&mntinfo.mount_point.as_str().to_ascii_lowercase());
 		}
 
 		ret
@@ -132,7 +226,9 @@ impl VirtualFs {
 		self.remove_folder_recur(slot);
 
 		if let Some(p) = self.mounts.iter().position(|mntinfo| mntinfo.root == slot) {
-			self.mounts.remove(p);
+			let mntinfo = self.mounts.remove(p);
+			self.mount_filters
+				.remove(&mntinfo.mount_point.as_str().to_ascii_lowercase());
 		}
 	}
 
@@ -153,6 +249,7 @@ impl VirtualFs {
 		for islot in self.folders[oslot].files.iter().copied() {
 			let removed = self.files.remove(islot);
 			debug_assert!(removed.is_some());
+			self.symlinks.remove(&islot);
 		}
 	}
 
@@ -166,12 +263,12 @@ impl VirtualFs {
 			if predicate(mntinfo) {
 				true
 			} else {
-				to_unmount.push(mntinfo.root);
+				to_unmount.push((mntinfo.root, mntinfo.mount_point.as_str().to_ascii_lowercase()));
 				false
 			}
 		});
 
-		for root in to_unmount {
+		for (root, mount_point) in to_unmount {
 			match root {
 				Slot::File(islot) => {
 					let removed = self.files.remove(islot);
@@ -181,13 +278,42 @@ impl VirtualFs {
 					self.remove_folder_recur(oslot);
 				}
 			}
+
+			self.mount_filters.remove(&mount_point);
 		}
 
 		Ok(())
 	}
 
 	pub fn lookup<'vfs: 'p, 'p>(&'vfs self, vpath: &'p VPath) -> Option<Ref<'vfs>> {
-		self.lookup_recur(self.root, &self.folders[self.root], vpath.components())
+		self.lookup_recur(self.root, &self.folders[self.root], vpath.components(), 0)
+	}
+
+	/// Registers `target` as a symlink named `name` under `parent`, without
+	/// requiring the target to exist yet. `target` is resolved against the
+	/// link's own parent folder if relative, or the VFS root if absolute,
+	/// the next time it's traversed through [`Self::lookup`].
+	///
+	/// The new entry is a normal, empty [`FileSlot`]; use
+	/// [`FileRef::is_symlink`]/[`FileRef::read_link`] to distinguish it from
+	/// an ordinary zero-length file.
+	pub fn insert_symlink(
+		&mut self,
+		parent: FolderSlot,
+		name: impl Into<SmallString>,
+		target: VPathBuf,
+	) -> FileSlot {
+		let slot = self.files.insert(VFile {
+			name: name.into(),
+			parent,
+			reader: Arc::new(Mutex::new(Reader::Memory(Vec::new()))),
+			span: 0..0,
+			compression: Compression::None,
+		});
+
+		self.folders[parent].files.insert(slot);
+		self.symlinks.insert(slot, target);
+		slot
 	}
 
 	#[must_use]
@@ -208,11 +334,16 @@ impl VirtualFs {
 		})
 	}
 
+	/// Bounds how many symlinks [`Self::lookup_recur`] will follow in a row,
+	/// so a cycle (direct or indirect) fails as [`None`] instead of hanging.
+	const MAX_SYMLINK_HOPS: u32 = 40;
+
 	fn lookup_recur<'vfs: 'p, 'p>(
 		&'vfs self,
 		slot: FolderSlot,
 		folder: &'vfs VFolder,
 		mut components: impl Iterator<Item = &'p VPath>,
+		symlink_hops: u32,
 	) -> Option<Ref<'vfs>> {
 		let Some(pcomp) = components.next() else {
 			return Some(Ref::Folder(FolderRef {
@@ -229,7 +360,7 @@ impl VirtualFs {
 				.eq_ignore_ascii_case(pcomp.as_str())
 				.then_some((s, fold))
 		}) {
-			return self.lookup_recur(sfslot, subfold, components);
+			return self.lookup_recur(sfslot, subfold, components, symlink_hops);
 		}
 
 		let option = match folder.files.len() {
@@ -250,13 +381,32 @@ impl VirtualFs {
 			}),
 		};
 
-		let Some((slot, file)) = option else {
+		let Some((fslot, file)) = option else {
 			return None;
 		};
 
+		if let Some(target) = self.symlinks.get(&fslot) {
+			if symlink_hops >= Self::MAX_SYMLINK_HOPS {
+				return None;
+			}
+
+			let (start_slot, start_folder) = if target.as_str().starts_with('/') {
+				(self.root, &self.folders[self.root])
+			} else {
+				(file.parent, &self.folders[file.parent])
+			};
+
+			let chain = VPath::new(target.as_str())
+				.components()
+				.chain(components)
+				.collect::<Vec<_>>();
+
+			return self.lookup_recur(start_slot, start_folder, chain.into_iter(), symlink_hops + 1);
+		}
+
 		Some(Ref::File(FileRef {
 			vfs: self,
-			slot,
+			slot: fslot,
 			vfile: file,
 		}))
 	}
@@ -327,6 +477,52 @@ impl VirtualFs {
 		});
 	}
 
+	/// Hashes every in-memory-backed [`VFile`]'s bytes (via blake3) and, for
+	/// any whose hash and length collide, repoints their `reader` at one
+	/// shared buffer, dropping the duplicates. Mirrors pxar's `HardLinkInfo`
+	/// scheme, keyed here by content hash instead of `st_dev`/`st_ino`.
+	///
+	/// Only entries already resident in memory (i.e. post-[`Self::ingest_all`])
+	/// are considered; physically-backed entries aren't read just to hash them.
+	/// `FileSlot`s are never touched, so existing references stay valid.
+	pub fn deduplicate(&mut self) -> DedupStats {
+		let mut canon: std::collections::HashMap<([u8; 32], usize), (Arc<Mutex<Reader>>, Range<u32>)> =
+			std::collections::HashMap::new();
+		let mut stats = DedupStats::default();
+
+		for vfile in self.files.values_mut() {
+			let span = vfile.span();
+
+			let key = {
+				let guard = vfile.reader.lock();
+
+				let Reader::Memory(bytes) = &*guard else {
+					continue;
+				};
+
+				(*blake3::hash(&bytes[span.clone()]).as_bytes(), span.len())
+			};
+
+			match canon.entry(key) {
+				Entry::Vacant(entry) => {
+					entry.insert((Arc::clone(&vfile.reader), vfile.span.clone()));
+				}
+				Entry::Occupied(entry) => {
+					let (reader, canon_span) = entry.get();
+
+					if !Arc::ptr_eq(reader, &vfile.reader) {
+						stats.bytes_reclaimed += span.len() as u64;
+						stats.files_deduplicated += 1;
+						vfile.reader = Arc::clone(reader);
+						vfile.span = canon_span.clone();
+					}
+				}
+			}
+		}
+
+		stats
+	}
+
 	#[must_use]
 	pub fn mounts(&self) -> &[MountInfo] {
 		&self.mounts
@@ -380,6 +576,7 @@ impl VirtualFs {
 		let root = self.folders.remove(self.root).unwrap();
 		self.folders.clear();
 		self.files.clear();
+		self.symlinks.clear();
 		self.root = self.folders.insert(root);
 	}
 }
@@ -401,10 +598,23 @@ impl Default for VirtualFs {
 			mounts: vec![],
 			files: HopSlotMap::default(),
 			folders,
+			mount_filters: std::collections::HashMap::new(),
+			symlinks: std::collections::HashMap::new(),
 		}
 	}
 }
 
+/// Returned by [`VirtualFs::deduplicate`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DedupStats {
+	/// How many files had their `reader` repointed at another, identical
+	/// file's buffer.
+	pub files_deduplicated: usize,
+	/// How many bytes' worth of duplicate buffers are now unreachable
+	/// (and so, barring other `Arc` holders, freed).
+	pub bytes_reclaimed: u64,
+}
+
 /// Metadata for a file subtree registered using [`VirtualFs::mount`].
 #[derive(Debug)]
 pub struct MountInfo {
@@ -420,6 +630,10 @@ pub enum MountFormat {
 	Directory,
 	Wad,
 	Zip,
+	/// An uncompressed tar archive.
+	Tar,
+	/// A gzip-wrapped tar archive.
+	TarGz,
 }
 
 /// Short for "virtual file".
@@ -457,6 +671,21 @@ impl VFile {
 	}
 }
 
+impl FileRef<'_> {
+	/// `true` if this entry was registered via [`VirtualFs::insert_symlink`].
+	#[must_use]
+	pub fn is_symlink(&self) -> bool {
+		self.vfs.symlinks.contains_key(&self.slot)
+	}
+
+	/// Returns this entry's link target without following it, or `None` if
+	/// it isn't a symlink.
+	#[must_use]
+	pub fn read_link(&self) -> Option<&VPathBuf> {
+		self.vfs.symlinks.get(&self.slot)
+	}
+}
+
 /// Short for "virtual folder".
 /// May represent a real directory or a logical directory in a (non-WAD) archive.
 #[derive(Debug)]
@@ -476,6 +705,11 @@ pub enum FolderKind {
 	Wad,
 	Zip,
 	ZipDir,
+	/// The root folder of a [`MountFormat::Tar`]/[`MountFormat::TarGz`] mount.
+	Tar,
+	/// A folder nested inside a mounted tar archive, as opposed to `Tar`
+	/// itself, which is the archive's own root.
+	TarDir,
 }
 
 impl VFolder {
@@ -574,6 +808,10 @@ impl From<FolderSlot> for Slot {
 #[derive(Debug)]
 pub enum Error {
 	Canonicalize(std::io::Error),
+	CatalogIo(std::io::Error),
+	CatalogMalformed,
+	CatalogStale,
+	CatalogUncacheable,
 	Decompress(std::io::Error),
 	DirRead(std::io::Error),
 	EmptyRead,
@@ -587,7 +825,9 @@ pub enum Error {
 	MountSymlink,
 	NotFound,
 	Seek(std::io::Error),
+	Tar(std::io::Error),
 	Utf8(FromUtf8Error),
+	Utf8Str(std::str::Utf8Error),
 	VFolderRead,
 	Wad(wadload::Error),
 	Zip(ZipReadError),
@@ -599,6 +839,16 @@ impl std::fmt::Display for Error {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Self::Canonicalize(err) => write!(f, "failed to canonicalize a mount path: {err}"),
+			Self::CatalogIo(err) => write!(f, "failed to read or write a catalog: {err}"),
+			Self::CatalogMalformed => write!(f, "catalog data is truncated or has a bad magic/version"),
+			Self::CatalogStale => write!(
+				f,
+				"a catalogued entry's backing file has changed since the catalog was written"
+			),
+			Self::CatalogUncacheable => write!(
+				f,
+				"attempted to catalog an entry that isn't a real, uncompressed file on disk"
+			),
 			Self::Decompress(err) => write!(f, "failed to decompress an archive entry: {err}"),
 			Self::DirRead(err) => write!(
 				f,
@@ -619,7 +869,9 @@ impl std::fmt::Display for Error {
 			Self::NotFound => write!(f, "no entry found by the given path"),
 			Self::Seek(err) => write!(f, "failed to seek a physical file handle: {err}"),
 			Self::MountSymlink => write!(f, "attempted to mount a symbolic link"),
+			Self::Tar(err) => write!(f, "failed to read a tar archive: {err}"),
 			Self::Utf8(err) => write!(f, "failed to read UTF-8 text from a virtual file: {err}"),
+			Self::Utf8Str(err) => write!(f, "failed to read a UTF-8 string from a catalog: {err}"),
 			Self::VFolderRead => write!(f, "attempted to read byte content of a virtual folder"),
 			Self::Wad(err) => write!(f, "WAD read error: {err}"),
 			Self::Zip(err) => write!(f, "zip archive read error: {err}"),
@@ -0,0 +1,61 @@
+//! Detecting network-backed filesystems, so callers can steer clear of `mmap`
+//! where it's unsound.
+//!
+//! Memory-mapping a file on NFS (or another network filesystem) can fault
+//! partway through a read, or silently hand back stale data if the file
+//! changes out from under the mapping on the remote side. Mercurial's
+//! dirstate-v2 code takes the same stance: probe the filesystem a path lives
+//! on before trusting a mapping of it, and fall back to buffered reads when
+//! the probe can't rule out a network mount.
+
+use std::path::Path;
+
+/// Linux `statfs(2)` magic numbers for filesystems known to misbehave under
+/// `mmap`. Sourced from `linux/magic.h`.
+#[cfg(target_os = "linux")]
+const NETWORK_FS_MAGICS: &[i64] = &[
+	0x6969,       // NFS_SUPER_MAGIC
+	0xFF534D42u32 as i64, // CIFS_SUPER_MAGIC
+	0x517B,       // SMB_SUPER_MAGIC
+	0x65735546,   // FUSE_SUPER_MAGIC (treat FUSE mounts as untrustworthy too)
+	0xFE534D42u32 as i64, // SMB2_SUPER_MAGIC (no official name in magic.h; mirrors CIFS')
+];
+
+/// Returns `true` if `path` appears to live on a network filesystem, in
+/// which case it is not safe to `mmap`.
+///
+/// Errors from the underlying `statfs` call are treated as "couldn't rule it
+/// out", and conservatively reported as networked so the caller falls back
+/// to buffered reads.
+#[cfg(target_os = "linux")]
+pub(crate) fn is_network_fs(path: &Path) -> bool {
+	use std::{mem::MaybeUninit, os::unix::ffi::OsStrExt};
+
+	let Ok(cpath) = std::ffi::CString::new(path.as_os_str().as_bytes()) else {
+		return true;
+	};
+
+	let mut stat = MaybeUninit::<libc::statfs>::uninit();
+
+	// SAFETY: `cpath` is a valid, NUL-terminated C string, and `stat` is
+	// large enough to receive the `statfs` result.
+	let ret = unsafe { libc::statfs(cpath.as_ptr(), stat.as_mut_ptr()) };
+
+	if ret != 0 {
+		return true;
+	}
+
+	// SAFETY: `statfs` returned success, so `stat` was fully initialized.
+	let f_type = unsafe { stat.assume_init() }.f_type as i64;
+
+	NETWORK_FS_MAGICS.contains(&f_type)
+}
+
+/// On platforms without a `statfs`-style probe, there's no cheap way to rule
+/// a path in or out as network-backed, so conservatively report it as local.
+/// Extending this with a real per-platform check is left for whoever next
+/// needs `mmap` support outside Linux.
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn is_network_fs(_path: &Path) -> bool {
+	false
+}
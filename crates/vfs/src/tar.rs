@@ -0,0 +1,61 @@
+//! Decoding tar and gzip-wrapped tar archives into a flat entry list, for
+//! [`MountFormat::Tar`]/[`MountFormat::TarGz`] mounts.
+//!
+//! As with the moksha project's `TargzFsDesc`, a gzip-wrapped tar is treated
+//! as just another filesystem source: it's decoded up front into a list of
+//! path/bytes pairs rather than streamed lazily, since `flate2`'s reader
+//! can't be seeked back into once a later entry has been read past. Whoever
+//! wires this into [`mount::mount`] gets a flat `Vec<TarEntry>`, each with
+//! its whole slash-separated path as one string, to fold into
+//! `VFolder`/`VFile` entries the same way the existing `Zip` branch does.
+
+use std::{io::Read, path::Path};
+
+use crate::Error;
+
+/// One entry read out of a tar (or tar.gz) archive.
+pub(crate) struct TarEntry {
+	/// Slash-separated path, relative to the archive root.
+	pub(crate) path: String,
+	pub(crate) is_dir: bool,
+	pub(crate) bytes: Vec<u8>,
+}
+
+/// Reads every entry out of the tar archive at `real_path`, decompressing it
+/// first if `gzipped` is set.
+pub(crate) fn read_entries(real_path: &Path, gzipped: bool) -> Result<Vec<TarEntry>, Error> {
+	let file = std::fs::File::open(real_path).map_err(Error::FileOpen)?;
+
+	let mut archive = if gzipped {
+		tar::Archive::new(Box::new(flate2::read::GzDecoder::new(file)) as Box<dyn Read>)
+	} else {
+		tar::Archive::new(Box::new(file) as Box<dyn Read>)
+	};
+
+	let mut entries = Vec::new();
+
+	for entry in archive.entries().map_err(Error::Tar)? {
+		let mut entry = entry.map_err(Error::Tar)?;
+		let is_dir = entry.header().entry_type().is_dir();
+
+		let path = entry
+			.path()
+			.map_err(Error::Tar)?
+			.to_string_lossy()
+			.into_owned();
+
+		let mut bytes = Vec::new();
+
+		if !is_dir {
+			entry.read_to_end(&mut bytes).map_err(Error::Tar)?;
+		}
+
+		entries.push(TarEntry {
+			path,
+			is_dir,
+			bytes,
+		});
+	}
+
+	Ok(entries)
+}
@@ -112,6 +112,36 @@ fn read_smoke() {
 	);
 }
 
+#[test]
+fn symlink_cycle() {
+	let mut vfs = VirtualFs::default();
+	let root = vfs.root().slot;
+
+	vfs.insert_symlink(root, "a", VPathBuf::from("/a"));
+
+	assert!(vfs.lookup(VPath::new("/a")).is_none());
+}
+
+#[test]
+fn filter_include_then_exclude_subpath() {
+	use crate::filter::{MatchPattern, MountOptions};
+
+	let opts = MountOptions::new(vec![
+		MatchPattern::include(glob::Pattern::new("/sounds/**").unwrap()),
+		MatchPattern::exclude(glob::Pattern::new("/sounds/unused/**").unwrap()),
+	]);
+
+	assert_eq!(
+		opts.evaluate(VPath::new("/sounds/weapons/pistol.wav")),
+		crate::filter::MatchKind::Include
+	);
+
+	assert_eq!(
+		opts.evaluate(VPath::new("/sounds/unused/test.wav")),
+		crate::filter::MatchKind::Exclude
+	);
+}
+
 #[must_use]
 fn sample_vfs() -> Option<VirtualFs> {
 	let mut vfs = VirtualFs::default();
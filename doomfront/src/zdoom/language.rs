@@ -2,6 +2,7 @@
 //! localized strings.
 
 pub mod parse;
+pub mod reparsing;
 mod syn;
 
 pub use syn::Syn;
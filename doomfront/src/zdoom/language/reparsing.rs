@@ -0,0 +1,203 @@
+//! Incremental reparsing for the LANGUAGE grammar, modeled on
+//! rust-analyzer's `reparsing.rs`.
+//!
+//! [`super::parser`]'s rules reparse their whole input; for a single
+//! keystroke deep inside a large LANGUAGE lump, refeeding the entire file is
+//! wasteful. [`reparse_block`] instead tries to locate the smallest
+//! independently-reparsable node or token containing the edit and re-run
+//! just the corresponding `peg` rule (or, for a bare trivia/string token,
+//! re-lex it directly) on it, splicing the result back into the unchanged
+//! tree. Callers fall back to a full [`super::parser::file`] parse whenever
+//! this returns `None`.
+
+use rowan::{GreenNode, GreenToken, NodeOrToken, TextRange, TextSize};
+
+use super::Syn;
+
+type SyntaxElement = rowan::SyntaxElement<Syn>;
+
+/// The result of a successful [`reparse_block`].
+pub struct Reparsed {
+	/// The new tree root, sharing every subtree [`reparse_block`] did not
+	/// have to touch.
+	pub green: GreenNode,
+	/// The ranges (in the *new* text) that changed and should be
+	/// re-validated/re-highlighted; always at least the reparsed element's
+	/// new range.
+	pub invalidated: Vec<TextRange>,
+}
+
+/// Attempts an incremental reparse of `root` after `old_text[edit_range]` is
+/// replaced by `replacement`. `old_text` must be the exact source `root` was
+/// parsed from.
+///
+/// Returns `None` if no node or token fully containing `edit_range` is
+/// independently reparsable, or if the edit crosses one of that node's
+/// boundary tokens (e.g. a `KeyValuePair`'s `;` terminator, or a
+/// `LocaleTag`'s `]`); either case means the edit may have changed the shape
+/// of an enclosing construct, so the caller should fall back to parsing
+/// `old_text` with `edit_range` and `replacement` applied in full.
+#[must_use]
+pub fn reparse_block(
+	root: &GreenNode,
+	old_text: &str,
+	edit_range: TextRange,
+	replacement: &str,
+) -> Option<Reparsed> {
+	let root_node = rowan::SyntaxNode::<Syn>::new_root(root.clone());
+	let target = find_reparsable(&root_node, edit_range)?;
+
+	if crosses_boundary(&target, edit_range) {
+		return None;
+	}
+
+	let target_range = target.text_range();
+	let rel_start: usize = (edit_range.start() - target_range.start()).into();
+	let rel_end: usize = (edit_range.end() - target_range.start()).into();
+	let old_slice = &old_text[usize::from(target_range.start())..usize::from(target_range.end())];
+
+	let mut new_slice = String::with_capacity(old_slice.len());
+	new_slice.push_str(&old_slice[..rel_start]);
+	new_slice.push_str(replacement);
+	new_slice.push_str(&old_slice[rel_end..]);
+
+	let new_green = splice(target, &new_slice)?;
+	let new_range = TextRange::at(target_range.start(), TextSize::of(new_slice.as_str()));
+
+	Some(Reparsed {
+		green: new_green,
+		invalidated: vec![new_range],
+	})
+}
+
+/// Finds the smallest node or token in `root`'s tree that fully contains
+/// `range` and is one of the kinds [`splice`] knows how to reparse in
+/// isolation: a `KeyValuePair`, a `LocaleTag`, or a single trivia/`StringLit`
+/// token.
+fn find_reparsable(root: &rowan::SyntaxNode<Syn>, range: TextRange) -> Option<SyntaxElement> {
+	let mut node = root.clone();
+
+	loop {
+		if let Some(token) = node
+			.children_with_tokens()
+			.filter_map(NodeOrToken::into_token)
+			.find(|t| t.text_range().contains_range(range) && is_leaf_reparsable(t.kind()))
+		{
+			return Some(NodeOrToken::Token(token));
+		}
+
+		if is_node_reparsable(node.kind()) {
+			return Some(NodeOrToken::Node(node));
+		}
+
+		let Some(child) = node
+			.children()
+			.find(|c| c.text_range().contains_range(range))
+		else {
+			return None;
+		};
+
+		node = child;
+	}
+}
+
+fn is_node_reparsable(kind: Syn) -> bool {
+	matches!(kind, Syn::KeyValuePair | Syn::LocaleTag)
+}
+
+fn is_leaf_reparsable(kind: Syn) -> bool {
+	matches!(kind, Syn::Whitespace | Syn::Comment | Syn::StringLit)
+}
+
+/// The token kinds that close off a reparsable node; an edit touching one
+/// of these may change whether the node still parses as that kind at all
+/// (e.g. deleting a `;` merges a `KeyValuePair` into the next one), so it is
+/// never safe to reparse in isolation. Bare tokens have no such boundary to
+/// cross.
+fn crosses_boundary(target: &SyntaxElement, edit_range: TextRange) -> bool {
+	let node = match target {
+		NodeOrToken::Token(_) => return false,
+		NodeOrToken::Node(node) => node,
+	};
+
+	let boundary_kind = match node.kind() {
+		Syn::KeyValuePair => Syn::Semicolon,
+		Syn::LocaleTag => Syn::BracketR,
+		_ => return true,
+	};
+
+	match node
+		.children_with_tokens()
+		.filter_map(NodeOrToken::into_token)
+		.find(|t| t.kind() == boundary_kind)
+	{
+		Some(t) => edit_range.intersect(t.text_range()).is_some(),
+		None => false,
+	}
+}
+
+fn splice(target: SyntaxElement, new_text: &str) -> Option<GreenNode> {
+	match target {
+		NodeOrToken::Token(token) => {
+			let new_token = GreenToken::new(token.kind().into(), new_text);
+			Some(token.replace_with(new_token))
+		}
+		NodeOrToken::Node(node) => {
+			let new_elem = match node.kind() {
+				Syn::KeyValuePair => super::parser::key_val_pair(new_text).ok()?,
+				Syn::LocaleTag => super::parser::locale_tag(new_text).ok()?,
+				_ => return None,
+			};
+
+			let NodeOrToken::Node(new_node) = new_elem else {
+				return None;
+			};
+
+			Some(node.replace_with(new_node))
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use rowan::TextRange;
+
+	use super::*;
+
+	fn apply_edit(text: &str, edit_range: TextRange, replacement: &str) -> String {
+		let mut out = String::with_capacity(text.len());
+		out.push_str(&text[..usize::from(edit_range.start())]);
+		out.push_str(replacement);
+		out.push_str(&text[usize::from(edit_range.end())..]);
+		out
+	}
+
+	fn check(old_text: &str, edit_range: TextRange, replacement: &str) {
+		let root = super::super::parser::file(old_text).expect("fixture must parse cleanly");
+		let new_text = apply_edit(old_text, edit_range, replacement);
+		let full = super::super::parser::file(&new_text).expect("edited fixture must parse cleanly");
+
+		let incremental = reparse_block(&root, old_text, edit_range, replacement)
+			.expect("this edit should be eligible for incremental reparse");
+
+		assert_eq!(format!("{:?}", incremental.green), format!("{:?}", full));
+	}
+
+	#[test]
+	fn reparse_string_lit() {
+		check(
+			"GREETING = \"hello\";\n",
+			TextRange::new(12.into(), 17.into()),
+			"goodbye",
+		);
+	}
+
+	#[test]
+	fn reparse_locale_tag() {
+		check(
+			"[enu default]\nGREETING = \"hi\";\n",
+			TextRange::new(1.into(), 4.into()),
+			"fra",
+		);
+	}
+}
@@ -5,6 +5,8 @@
 use chumsky::prelude::Input;
 use logos::Logos;
 
+use super::Version;
+
 #[derive(logos::Logos, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Token {
@@ -377,6 +379,210 @@ impl Token {
 		let u = self as u8;
 		u > (Self::__FirstKw as u8) && u < (Self::__LastKw as u8)
 	}
+
+	/// The GZDoom version at which this keyword became reserved, if it
+	/// wasn't part of ZScript's original keyword set. A keyword absent from
+	/// this table (including `version` itself, and every non-keyword token)
+	/// has always been reserved, and this returns `None`.
+	///
+	/// Used by [`Self::stream_versioned`] to decide which keyword tokens a
+	/// given file's declared version is too old to have reserved yet.
+	#[must_use]
+	pub fn keyword_since(self) -> Option<Version> {
+		KEYWORD_VERSIONS
+			.iter()
+			.find_map(|(tok, since)| (*tok == self).then_some(*since))
+	}
+
+	/// As [`Self::stream`], but additionally consumes `version` (as declared
+	/// by a file's leading `version "x.y"` directive) and re-maps any
+	/// keyword token not yet reserved at that version back to [`Token::Ident`],
+	/// so legacy scripts that use a since-reserved word as a plain identifier
+	/// still lex correctly.
+	#[must_use]
+	pub fn stream_versioned(source: &str, version: Version) -> TokenStream {
+		fn mapper(input: (Result<Token, ()>, logos::Span)) -> (Token, logos::Span) {
+			(input.0.unwrap_or(Token::Unknown), input.1)
+		}
+
+		let f: TokenMapper = mapper;
+
+		let tokens = Token::lexer(source).spanned().map(f).map(move |(tok, span)| {
+			let tok = match tok.keyword_since() {
+				Some(since) if since > version => Token::Ident,
+				_ => tok,
+			};
+
+			(tok, span)
+		});
+
+		chumsky::input::Stream::from_iter(tokens).spanned(source.len()..source.len())
+	}
+}
+
+/// Backs [`Token::keyword_since`]. Only keywords confirmed to postdate
+/// ZScript's original release are listed; everything else is reserved since
+/// the beginning.
+const KEYWORD_VERSIONS: &[(Token, Version)] = &[
+	(Token::KwArray, Version::new(2, 4, 0)),
+	(Token::KwVector2, Version::new(2, 4, 0)),
+	(Token::KwVector3, Version::new(2, 4, 0)),
+	(Token::KwMixin, Version::new(3, 7, 0)),
+	(Token::KwLet, Version::new(3, 7, 0)),
+	(Token::KwForeach, Version::new(3, 7, 0)),
+	(Token::KwNoDelay, Version::new(3, 7, 0)),
+];
+
+/// Identifies one of the files folded into a [`Preprocessed`] token stream by
+/// [`preprocess`], assigned by the caller's resolver closure. The root
+/// source passed to [`preprocess`] is not itself assigned a `FileId`, since
+/// the caller already knows which file that is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileId(pub u32);
+
+/// The result of [`preprocess`]ing a root source and everything it
+/// (recursively) `#include`s: one concatenated source with a table mapping
+/// each of its byte ranges back to the [`FileId`] (`None` for the root) that
+/// contributed it, for diagnostics that need to point at the right file.
+#[derive(Debug, Clone)]
+pub struct Preprocessed {
+	source: String,
+	/// Sorted by range start; ranges are contiguous and non-overlapping.
+	segments: Vec<(Option<FileId>, std::ops::Range<usize>)>,
+}
+
+impl Preprocessed {
+	#[must_use]
+	pub fn source(&self) -> &str {
+		&self.source
+	}
+
+	/// Looks up which file contributed the byte at `offset` in
+	/// [`Self::source`]. Returns `None` both for an out-of-range offset and
+	/// for an offset that came from the root source.
+	#[must_use]
+	pub fn file_at(&self, offset: usize) -> Option<FileId> {
+		self.segments
+			.iter()
+			.find(|(_, range)| range.contains(&offset))
+			.and_then(|(id, _)| *id)
+	}
+
+	#[must_use]
+	pub fn stream(&self) -> TokenStream {
+		Token::stream(&self.source)
+	}
+}
+
+#[derive(Debug)]
+pub enum PreprocessError {
+	/// A `#include` wasn't followed by a string literal naming the path to
+	/// include.
+	MissingPath,
+	/// The resolver closure passed to [`preprocess`] returned `None` for an
+	/// included path.
+	NotFound(String),
+	/// A file (transitively) `#include`d itself.
+	Cycle(FileId),
+}
+
+impl std::error::Error for PreprocessError {}
+
+impl std::fmt::Display for PreprocessError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::MissingPath => {
+				write!(f, "`#include` was not followed by a string literal path")
+			}
+			Self::NotFound(path) => write!(f, "could not resolve `#include \"{path}\"`"),
+			Self::Cycle(id) => write!(f, "include cycle detected at file {}", id.0),
+		}
+	}
+}
+
+/// Recursively expands every `#include "path"` directive in `root_source`
+/// (and every file it transitively includes) into one concatenated source,
+/// using `resolve` to turn an included path into a `(FileId, String)` pair so
+/// callers control lump/VFS lookup. The result is ready for [`Token::stream`]
+/// (or [`Token::stream_versioned`]) by way of [`Preprocessed::stream`].
+///
+/// Non-include `#` ([`Token::Pound`]) tokens are left untouched; only a
+/// `#include` followed by a string literal is treated specially.
+pub fn preprocess(
+	root_source: &str,
+	mut resolve: impl FnMut(&str) -> Option<(FileId, String)>,
+) -> Result<Preprocessed, PreprocessError> {
+	let mut out = Preprocessed {
+		source: String::new(),
+		segments: Vec::new(),
+	};
+
+	let mut stack = std::collections::HashSet::new();
+
+	expand(root_source, None, &mut resolve, &mut out, &mut stack)?;
+
+	Ok(out)
+}
+
+fn expand(
+	source: &str,
+	file: Option<FileId>,
+	resolve: &mut impl FnMut(&str) -> Option<(FileId, String)>,
+	out: &mut Preprocessed,
+	stack: &mut std::collections::HashSet<FileId>,
+) -> Result<(), PreprocessError> {
+	let tokens = Token::lexer(source)
+		.spanned()
+		.map(|(res, span)| (res.unwrap_or(Token::Unknown), span))
+		.collect::<Vec<_>>();
+
+	let mut seg_start = out.source.len();
+	let mut i = 0;
+
+	while i < tokens.len() {
+		let (tok, span) = tokens[i].clone();
+
+		if tok != Token::PoundInclude {
+			out.source.push_str(&source[span]);
+			i += 1;
+			continue;
+		}
+
+		let mut j = i + 1;
+
+		while matches!(tokens.get(j).map(|(t, _)| t), Some(Token::Whitespace | Token::Comment)) {
+			j += 1;
+		}
+
+		let Some((Token::StringLit, lit_span)) = tokens.get(j).cloned() else {
+			return Err(PreprocessError::MissingPath);
+		};
+
+		let path = source[lit_span].trim_matches('"');
+
+		let (inc_id, inc_source) = resolve(path)
+			.ok_or_else(|| PreprocessError::NotFound(path.to_string()))?;
+
+		if out.source.len() > seg_start {
+			out.segments.push((file, seg_start..out.source.len()));
+		}
+
+		if !stack.insert(inc_id) {
+			return Err(PreprocessError::Cycle(inc_id));
+		}
+
+		expand(&inc_source, Some(inc_id), resolve, out, stack)?;
+		stack.remove(&inc_id);
+
+		seg_start = out.source.len();
+		i = j + 1;
+	}
+
+	if out.source.len() > seg_start {
+		out.segments.push((file, seg_start..out.source.len()));
+	}
+
+	Ok(())
 }
 
 #[cfg(test)]
@@ -417,6 +623,32 @@ States (actor, overlay) {
 		}
 	}
 
+	#[test]
+	fn preprocess_smoke() {
+		let root = r#"#include "inc.zs"
+class Foo {}
+"#;
+
+		let out = preprocess(root, |path| {
+			(path == "inc.zs").then(|| (FileId(1), "class Bar {}\n".to_string()))
+		})
+		.unwrap();
+
+		assert!(out.source().contains("class Bar {}"));
+		assert!(out.source().contains("class Foo {}"));
+		assert!(!out.source().contains("#include"));
+	}
+
+	#[test]
+	fn preprocess_cycle() {
+		let root = r#"#include "a.zs""#;
+
+		let err = preprocess(root, |_| Some((FileId(1), r#"#include "a.zs""#.to_string())))
+			.unwrap_err();
+
+		assert!(matches!(err, PreprocessError::Cycle(FileId(1))));
+	}
+
 	#[test]
 	fn with_sample_data() {
 		const ENV_VAR: &str = "DOOMFRONT_ZDOOM_LEX_SAMPLE";
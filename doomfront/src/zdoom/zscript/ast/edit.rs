@@ -0,0 +1,146 @@
+//! In-place mutable editing for the statement AST, via rowan's mutable
+//! ("clone_for_update") tree mode.
+//!
+//! Every method here calls [`rowan::SyntaxNode::splice_children`] on `self`
+//! directly, so it requires `self` to already be part of a mutable tree
+//! (obtained via `clone_for_update`, directly or by being a descendant of
+//! one); calling one of these against an ordinary immutable tree panics, per
+//! `splice_children`'s own contract. Pair this with [`super::make`]'s
+//! constructors — build a new statement there, then splice it in here — to
+//! express a refactor or quick-fix as compose-then-splice instead of a text
+//! edit and a full re-parse.
+
+use rowan::{ast::AstNode, NodeOrToken};
+
+use super::{
+	stat::{AssignStat, CompoundStat, CondLoopStat, ForStat, Statement},
+	Expr, Syn,
+};
+
+impl CompoundStat {
+	/// Inserts `stmt` as the first statement in this block.
+	pub fn push_front(&self, stmt: Statement) {
+		let insert_at = self
+			.syntax()
+			.children_with_tokens()
+			.position(|elem| elem.kind() == Syn::BraceL)
+			.map(|ix| ix + 1)
+			.expect("a `CompoundStat` always has an opening brace");
+
+		self.syntax()
+			.splice_children(insert_at..insert_at, vec![NodeOrToken::Node(stmt.syntax().clone())]);
+	}
+
+	/// Appends `stmt` as the last statement in this block.
+	pub fn push_back(&self, stmt: Statement) {
+		let insert_at = self
+			.syntax()
+			.children_with_tokens()
+			.position(|elem| elem.kind() == Syn::BraceR)
+			.expect("a `CompoundStat` always has a closing brace");
+
+		self.syntax()
+			.splice_children(insert_at..insert_at, vec![NodeOrToken::Node(stmt.syntax().clone())]);
+	}
+
+	/// Inserts `stmt` immediately after `anchor`, which must already be one
+	/// of this block's statements.
+	///
+	/// # Panics
+	/// Panics if `anchor` is not a direct child of this block.
+	pub fn insert_after(&self, anchor: &Statement, stmt: Statement) {
+		let insert_at = self
+			.syntax()
+			.children_with_tokens()
+			.position(|elem| elem.as_node() == Some(anchor.syntax()))
+			.expect("`anchor` is not a statement in this `CompoundStat`")
+			+ 1;
+
+		self.syntax()
+			.splice_children(insert_at..insert_at, vec![NodeOrToken::Node(stmt.syntax().clone())]);
+	}
+
+	/// Removes `stmt` from this block.
+	///
+	/// # Panics
+	/// Panics if `stmt` is not a direct child of this block.
+	pub fn remove(&self, stmt: &Statement) {
+		let ix = self
+			.syntax()
+			.children_with_tokens()
+			.position(|elem| elem.as_node() == Some(stmt.syntax()))
+			.expect("`stmt` is not a statement in this `CompoundStat`");
+
+		self.syntax().splice_children(ix..ix + 1, vec![]);
+	}
+}
+
+impl CondLoopStat {
+	/// Replaces this loop's body with `body`.
+	pub fn set_body(&self, body: Statement) {
+		let old = self.statement();
+
+		let ix = self
+			.syntax()
+			.children_with_tokens()
+			.position(|elem| elem.as_node() == Some(old.syntax()))
+			.expect("this loop's own body could not be found among its children");
+
+		self.syntax()
+			.splice_children(ix..ix + 1, vec![NodeOrToken::Node(body.syntax().clone())]);
+	}
+}
+
+impl ForStat {
+	/// Replaces this loop's body with `body`.
+	pub fn set_body(&self, body: Statement) {
+		let old = super::traits::HasBody::body(self);
+
+		let ix = self
+			.syntax()
+			.children_with_tokens()
+			.position(|elem| elem.as_node() == Some(old.syntax()))
+			.expect("this loop's own body could not be found among its children");
+
+		self.syntax()
+			.splice_children(ix..ix + 1, vec![NodeOrToken::Node(body.syntax().clone())]);
+	}
+
+	/// Replaces this loop's condition expression, leaving `init` and `iter`
+	/// untouched.
+	///
+	/// # Panics
+	/// Panics if this loop has no condition (an empty `ForLoopCond` clause).
+	pub fn set_condition(&self, condition: Expr) {
+		let cond = self.condition();
+		let old = cond
+			.expr()
+			.expect("this `for` loop has no condition to replace");
+
+		let ix = cond
+			.syntax()
+			.children_with_tokens()
+			.position(|elem| elem.as_node() == Some(old.syntax()))
+			.expect("this loop's own condition could not be found in its condition clause");
+
+		cond.syntax()
+			.splice_children(ix..ix + 1, vec![NodeOrToken::Node(condition.syntax().clone())]);
+	}
+}
+
+impl AssignStat {
+	/// Replaces this statement's RHS (see [`AssignStat::assignee`]), leaving
+	/// the LHS and operator untouched.
+	pub fn replace_value(&self, value: Expr) {
+		let old = self.assignee();
+
+		let ix = self
+			.syntax()
+			.children_with_tokens()
+			.position(|elem| elem.as_node() == Some(old.syntax()))
+			.expect("this statement's own assignee could not be found among its children");
+
+		self.syntax()
+			.splice_children(ix..ix + 1, vec![NodeOrToken::Node(value.syntax().clone())]);
+	}
+}
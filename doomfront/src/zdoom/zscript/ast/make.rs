@@ -0,0 +1,144 @@
+//! Constructors for synthesizing statement AST nodes.
+//!
+//! Mirrors `crates/doomfront`'s sibling actor-AST `ast/make.rs` (itself
+//! modeled on rust-analyzer's `ast/make.rs`): each function assembles a
+//! well-formed [`GreenNode`] directly and hands back the corresponding typed
+//! wrapper from [`super::stat`]. This builds the green tree itself rather
+//! than formatting source text and re-running it through a parser, the same
+//! way the actor-AST `make.rs` does — there's no standalone statement-level
+//! grammar entry point in this checkout to reparse through.
+
+use rowan::{ast::AstNode, GreenNode, GreenToken, NodeOrToken};
+
+use crate::GreenElement;
+
+use super::{
+	stat::{AssignOp, AssignStat, CompoundStat, CondLoopStat, ExprStat, ReturnStat, Statement},
+	Expr, Syn, SyntaxNode,
+};
+
+/// Wraps `green` in a standalone red tree and casts it to `N`.
+///
+/// # Panics
+/// Panics if `green`'s root kind does not match `N`; every function in this
+/// module builds its green tree to satisfy this by construction.
+#[must_use]
+pub(super) fn cast<N: AstNode<Language = Syn>>(green: GreenNode) -> N {
+	N::cast(SyntaxNode::new_root(green)).expect("malformed synthesized node")
+}
+
+#[must_use]
+fn token(kind: Syn, text: &str) -> GreenElement {
+	NodeOrToken::Token(GreenToken::new(kind.into(), text))
+}
+
+#[must_use]
+fn ws() -> GreenElement {
+	token(Syn::Whitespace, " ")
+}
+
+#[must_use]
+fn expr_elem(expr: &Expr) -> GreenElement {
+	NodeOrToken::Node(expr.syntax().green().into_owned())
+}
+
+#[must_use]
+fn stmt_elem(stmt: &Statement) -> GreenElement {
+	NodeOrToken::Node(stmt.syntax().green().into_owned())
+}
+
+/// Returns the `Syn` token kind and spelling for an [`AssignOp`], for use by
+/// [`assign_stat`].
+#[must_use]
+fn assign_op_token(op: AssignOp) -> (Syn, &'static str) {
+	match op {
+		AssignOp::Plain => (Syn::Eq, "="),
+		AssignOp::Add => (Syn::PlusEq, "+="),
+		AssignOp::Sub => (Syn::MinusEq, "-="),
+		AssignOp::Mul => (Syn::AsteriskEq, "*="),
+		AssignOp::Div => (Syn::SlashEq, "/="),
+		AssignOp::Mod => (Syn::PercentEq, "%="),
+		AssignOp::Shl => (Syn::AngleL2Eq, "<<="),
+		AssignOp::Shr => (Syn::AngleR2Eq, ">>="),
+		AssignOp::BitAnd => (Syn::AmpersandEq, "&="),
+		AssignOp::BitOr => (Syn::PipeEq, "|="),
+		AssignOp::BitXor => (Syn::CaretEq, "^="),
+		AssignOp::Approx => (Syn::TildeEq2, "~=="),
+	}
+}
+
+/// Builds a `return expr0, expr1, ...;` [`Syn::ReturnStat`]. `exprs` may be
+/// empty, yielding a bare `return;`.
+#[must_use]
+pub fn return_stat(exprs: impl IntoIterator<Item = Expr>) -> ReturnStat {
+	let mut children = vec![token(Syn::KwReturn, "return")];
+
+	for (i, expr) in exprs.into_iter().enumerate() {
+		if i > 0 {
+			children.push(token(Syn::Comma, ","));
+		}
+
+		children.push(ws());
+		children.push(expr_elem(&expr));
+	}
+
+	children.push(token(Syn::Semicolon, ";"));
+	cast(GreenNode::new(Syn::ReturnStat.into(), children))
+}
+
+/// Builds a `{ stmt0 stmt1 ... }` [`Syn::CompoundStat`].
+#[must_use]
+pub fn compound_stat(stmts: impl IntoIterator<Item = Statement>) -> CompoundStat {
+	let mut children = vec![token(Syn::BraceL, "{")];
+
+	for stmt in stmts {
+		children.push(ws());
+		children.push(stmt_elem(&stmt));
+	}
+
+	children.push(ws());
+	children.push(token(Syn::BraceR, "}"));
+	cast(GreenNode::new(Syn::CompoundStat.into(), children))
+}
+
+/// Builds an `assigned op value;` [`Syn::AssignStat`], e.g.
+/// `assign_stat(lhs, AssignOp::Add, rhs)` for `lhs += rhs;`.
+#[must_use]
+pub fn assign_stat(assigned: Expr, op: AssignOp, value: Expr) -> AssignStat {
+	let (op_kind, op_text) = assign_op_token(op);
+
+	let children = vec![
+		expr_elem(&assigned),
+		ws(),
+		token(op_kind, op_text),
+		ws(),
+		expr_elem(&value),
+		token(Syn::Semicolon, ";"),
+	];
+
+	cast(GreenNode::new(Syn::AssignStat.into(), children))
+}
+
+/// Builds a `while (cond) body` [`Syn::WhileStat`], castable to
+/// [`Statement::CondLoop`](super::stat::Statement::CondLoop).
+#[must_use]
+pub fn while_loop(cond: Expr, body: Statement) -> CondLoopStat {
+	let children = vec![
+		token(Syn::KwWhile, "while"),
+		ws(),
+		token(Syn::ParenL, "("),
+		expr_elem(&cond),
+		token(Syn::ParenR, ")"),
+		ws(),
+		stmt_elem(&body),
+	];
+
+	cast(GreenNode::new(Syn::WhileStat.into(), children))
+}
+
+/// Builds an `expr;` [`Syn::ExprStat`].
+#[must_use]
+pub fn expr_stat(expr: Expr) -> ExprStat {
+	let children = vec![expr_elem(&expr), token(Syn::Semicolon, ";")];
+	cast(GreenNode::new(Syn::ExprStat.into(), children))
+}
@@ -102,6 +102,25 @@ impl AstNode for Statement {
 	}
 }
 
+impl Statement {
+	/// Returns the loop/switch body of this statement, if it has one, i.e.
+	/// it is a [`Self::CondLoop`], [`Self::For`], [`Self::ForEach`], or
+	/// [`Self::Switch`]. Dispatches to [`super::traits::HasBody::body`] so
+	/// callers don't need to match on the concrete variant themselves.
+	#[must_use]
+	pub fn body(&self) -> Option<Statement> {
+		use super::traits::HasBody;
+
+		match self {
+			Self::CondLoop(inner) => Some(inner.body()),
+			Self::For(inner) => Some(inner.body()),
+			Self::ForEach(inner) => Some(inner.body()),
+			Self::Switch(inner) => Some(inner.body()),
+			_ => None,
+		}
+	}
+}
+
 // AssignStat //////////////////////////////////////////////////////////////////
 
 /// Wraps a node tagged [`Syn::AssignStat`].
@@ -115,7 +134,7 @@ impl AssignStat {
 	pub fn assigned(&self) -> impl Iterator<Item = Expr> {
 		self.0
 			.children_with_tokens()
-			.take_while(|elem| elem.kind() != Syn::Eq)
+			.take_while(|elem| !elem.kind().is_assign_op())
 			.filter_map(|elem| elem.into_node().map(|node| Expr::cast(node).unwrap()))
 	}
 
@@ -123,6 +142,96 @@ impl AssignStat {
 	pub fn assignee(&self) -> Expr {
 		Expr::cast(self.0.children().last().unwrap()).unwrap()
 	}
+
+	/// The typed classification of [`Self::op_token`]; see [`AssignOp`].
+	#[must_use]
+	pub fn op(&self) -> AssignOp {
+		AssignOp::from(self.op_token().kind())
+	}
+
+	/// The token between [`Self::assigned`] and [`Self::assignee`] recording
+	/// which of `=`/`+=`/`-=`/... this statement uses.
+	#[must_use]
+	pub fn op_token(&self) -> SyntaxToken {
+		self.0
+			.children_with_tokens()
+			.find_map(|elem| elem.into_token().filter(|token| token.kind().is_assign_op()))
+			.unwrap()
+	}
+}
+
+/// The operator used by an [`AssignStat`], as classified by [`AssignStat::op`].
+///
+/// Following rust-analyzer's `ast/operators.rs`, this exposes a typed
+/// alternative to matching on the raw [`Syn`] token kind, so callers (e.g. a
+/// desugaring pass for `a += b`) don't need to know the lexical spelling of
+/// each compound-assignment operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssignOp {
+	/// `=`
+	Plain,
+	/// `+=`
+	Add,
+	/// `-=`
+	Sub,
+	/// `*=`
+	Mul,
+	/// `/=`
+	Div,
+	/// `%=`
+	Mod,
+	/// `<<=`
+	Shl,
+	/// `>>=`
+	Shr,
+	/// `&=`
+	BitAnd,
+	/// `|=`
+	BitOr,
+	/// `^=`
+	BitXor,
+	/// `~==`
+	Approx,
+}
+
+impl From<Syn> for AssignOp {
+	/// # Panics
+	/// Panics if `value` is not one of the token kinds [`Syn::is_assign_op`] accepts.
+	fn from(value: Syn) -> Self {
+		match value {
+			Syn::Eq => Self::Plain,
+			Syn::PlusEq => Self::Add,
+			Syn::MinusEq => Self::Sub,
+			Syn::AsteriskEq => Self::Mul,
+			Syn::SlashEq => Self::Div,
+			Syn::PercentEq => Self::Mod,
+			Syn::AngleL2Eq => Self::Shl,
+			Syn::AngleR2Eq => Self::Shr,
+			Syn::AmpersandEq => Self::BitAnd,
+			Syn::PipeEq => Self::BitOr,
+			Syn::CaretEq => Self::BitXor,
+			Syn::TildeEq2 => Self::Approx,
+			other => panic!("`{other:?}` is not an assignment operator token"),
+		}
+	}
+}
+
+impl Syn {
+	/// Returns `true` for `=` and every compound-assignment operator token
+	/// (`+=`, `-=`, `*=`, `/=`, `%=`, `<<=`, `>>=`, `&=`, `|=`, `^=`, `~==`).
+	#[must_use]
+	pub fn is_assign_op(self) -> bool {
+		matches!(
+			self,
+			Syn::Eq
+				| Syn::PlusEq | Syn::MinusEq
+				| Syn::AsteriskEq | Syn::SlashEq
+				| Syn::PercentEq | Syn::AngleL2Eq
+				| Syn::AngleR2Eq | Syn::AmpersandEq
+				| Syn::PipeEq | Syn::CaretEq
+				| Syn::TildeEq2
+		)
+	}
 }
 
 // BreakStat ///////////////////////////////////////////////////////////////////
@@ -459,18 +568,7 @@ pub struct StaticConstStat(SyntaxNode);
 
 simple_astnode!(Syn, StaticConstStat, Syn::StaticConstStat);
 
-impl StaticConstStat {
-	/// All returned tokens are tagged [`Syn::DocComment`].
-	pub fn docs(&self) -> impl Iterator<Item = SyntaxToken> {
-		self.0
-			.children_with_tokens()
-			.take_while(|elem| elem.kind() == Syn::DocComment)
-			.filter_map(|elem| {
-				elem.into_token()
-					.filter(|token| token.kind() == Syn::DocComment)
-			})
-	}
-}
+impl super::traits::HasDocComments for StaticConstStat {}
 
 // SwitchStat //////////////////////////////////////////////////////////////////
 
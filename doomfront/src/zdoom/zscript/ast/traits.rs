@@ -0,0 +1,58 @@
+//! Shared traits over doc-commented and loop/switch-bodied statements,
+//! following rust-analyzer's `ast/traits.rs`.
+//!
+//! These let generic traversal or refactoring code retrieve a leading
+//! doc-comment block or a loop/switch body without matching on every
+//! concrete node kind that happens to carry one.
+
+use rowan::ast::AstNode;
+
+use super::{
+	stat::{CondLoopStat, ForEachStat, ForStat, Statement, SwitchStat},
+	Syn, SyntaxToken,
+};
+
+/// Implemented by nodes preceded by a run of [`Syn::DocComment`] tokens.
+pub trait HasDocComments: AstNode<Language = Syn> {
+	/// All returned tokens are tagged [`Syn::DocComment`].
+	fn docs(&self) -> impl Iterator<Item = SyntaxToken> {
+		self.syntax()
+			.children_with_tokens()
+			.take_while(|elem| elem.kind() == Syn::DocComment)
+			.filter_map(|elem| elem.into_token().filter(|token| token.kind() == Syn::DocComment))
+	}
+}
+
+/// Implemented by statements whose grammar wraps a single inner
+/// [`Statement`] as a loop or switch body, so generic passes can retrieve it
+/// without matching on the concrete variant (see also [`Statement::body`]).
+pub trait HasBody: AstNode<Language = Syn> {
+	#[must_use]
+	fn body(&self) -> Statement;
+}
+
+impl HasBody for CondLoopStat {
+	fn body(&self) -> Statement {
+		self.statement()
+	}
+}
+
+impl HasBody for ForEachStat {
+	fn body(&self) -> Statement {
+		self.statement()
+	}
+}
+
+impl HasBody for SwitchStat {
+	fn body(&self) -> Statement {
+		self.statement()
+	}
+}
+
+impl HasBody for ForStat {
+	/// The final child of a `for` statement, following its
+	/// `init`/`condition`/`iter` clauses.
+	fn body(&self) -> Statement {
+		Statement::cast(self.syntax().last_child().unwrap()).unwrap()
+	}
+}
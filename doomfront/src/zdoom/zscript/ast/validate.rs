@@ -0,0 +1,187 @@
+//! Structural diagnostics for the statement AST, analogous to rust-analyzer's
+//! `validation.rs` (and this crate's `crates/doomfront` sibling, which does
+//! the same thing for the actor-definition AST).
+//!
+//! These are purely syntactic checks — a `break` outside any loop or switch,
+//! a `case` floating outside a `switch` body — that a linter or language
+//! server can run immediately after parsing, without building out a full
+//! semantic model first.
+
+use rowan::{ast::AstNode, TextRange};
+
+use super::{
+	stat::{CompoundStat, Statement, SwitchStat},
+	Syn, SyntaxNode,
+};
+
+/// A single validation finding, ready to be lowered into an editor
+/// diagnostic by a caller that has the source text in hand.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+	pub range: TextRange,
+	pub severity: Severity,
+	pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+	Error,
+	Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+	Loop,
+	Switch,
+}
+
+/// Walks every statement under (and including) `root`, appending a
+/// [`Diagnostic`] to `acc` for each check that fails. `root` may be any
+/// node; only the statement kinds relevant to each check are inspected, so
+/// this can be called with a whole function body or a single `CompoundStat`.
+pub fn validate(root: &SyntaxNode, acc: &mut Vec<Diagnostic>) {
+	validate_node(root, &mut Vec::new(), acc);
+}
+
+fn validate_node(node: &SyntaxNode, stack: &mut Vec<Frame>, acc: &mut Vec<Diagnostic>) {
+	match node.kind() {
+		Syn::BreakStat => validate_break(node, stack, acc),
+		Syn::ContinueStat => validate_continue(node, stack, acc),
+		Syn::CaseStat => validate_case_placement(node, "case", acc),
+		Syn::DefaultStat => validate_case_placement(node, "default", acc),
+		Syn::EmptyStat => acc.push(Diagnostic {
+			range: node.text_range(),
+			severity: Severity::Warning,
+			message: "empty statement (a lone `;`); this is usually a mistake".to_string(),
+		}),
+		Syn::CompoundStat => validate_unreachable(node, acc),
+		_ => {}
+	}
+
+	let pushed_frame = match node.kind() {
+		Syn::DoUntilStat | Syn::DoWhileStat | Syn::UntilStat | Syn::WhileStat | Syn::ForStat
+		| Syn::ForEachStat => Some(Frame::Loop),
+		Syn::SwitchStat => {
+			validate_switch_dupes(node, acc);
+			Some(Frame::Switch)
+		}
+		_ => None,
+	};
+
+	if let Some(frame) = pushed_frame {
+		stack.push(frame);
+	}
+
+	for child in node.children() {
+		validate_node(&child, stack, acc);
+	}
+
+	if pushed_frame.is_some() {
+		stack.pop();
+	}
+}
+
+fn validate_break(node: &SyntaxNode, stack: &[Frame], acc: &mut Vec<Diagnostic>) {
+	if !stack.iter().any(|f| matches!(f, Frame::Loop | Frame::Switch)) {
+		acc.push(Diagnostic {
+			range: node.text_range(),
+			severity: Severity::Error,
+			message: "`break` outside a loop or switch".to_string(),
+		});
+	}
+}
+
+fn validate_continue(node: &SyntaxNode, stack: &[Frame], acc: &mut Vec<Diagnostic>) {
+	if !stack.iter().any(|f| matches!(f, Frame::Loop)) {
+		acc.push(Diagnostic {
+			range: node.text_range(),
+			severity: Severity::Error,
+			message: "`continue` outside a loop".to_string(),
+		});
+	}
+}
+
+/// A `case`/`default` is only legal as a direct child of the `CompoundStat`
+/// making up a `switch`'s body.
+fn validate_case_placement(node: &SyntaxNode, what: &str, acc: &mut Vec<Diagnostic>) {
+	let in_switch_body = node.parent().is_some_and(|compound| {
+		compound.kind() == Syn::CompoundStat
+			&& compound
+				.parent()
+				.is_some_and(|switch| switch.kind() == Syn::SwitchStat)
+	});
+
+	if !in_switch_body {
+		acc.push(Diagnostic {
+			range: node.text_range(),
+			severity: Severity::Error,
+			message: format!("`{what}` outside switch"),
+		});
+	}
+}
+
+/// Flags a second `default`, or two `case`s with textually identical
+/// constant expressions, within one `switch`'s body.
+fn validate_switch_dupes(switch_node: &SyntaxNode, acc: &mut Vec<Diagnostic>) {
+	let switch = SwitchStat::cast(switch_node.clone()).unwrap();
+
+	let Statement::Compound(body) = switch.statement() else {
+		return;
+	};
+
+	let mut default_seen: Option<TextRange> = None;
+	let mut case_consts = std::collections::HashMap::<String, TextRange>::new();
+
+	for stmt in body.innards() {
+		match stmt {
+			Statement::Default(d) => {
+				let range = d.syntax().text_range();
+
+				if let Some(prev) = default_seen {
+					acc.push(Diagnostic {
+						range,
+						severity: Severity::Error,
+						message: format!("duplicate `default` (first at {prev:?}) in this `switch`"),
+					});
+				} else {
+					default_seen = Some(range);
+				}
+			}
+			Statement::Case(c) => {
+				let range = c.syntax().text_range();
+				let text = c.expr().syntax().text().to_string();
+
+				if let Some(prev) = case_consts.insert(text.clone(), range) {
+					acc.push(Diagnostic {
+						range,
+						severity: Severity::Error,
+						message: format!("duplicate `case {text}` (first at {prev:?}) in this `switch`"),
+					});
+				}
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Flags every statement lexically following an unconditional
+/// `return`/`break`/`continue` within the same `CompoundStat` as dead code.
+fn validate_unreachable(compound_node: &SyntaxNode, acc: &mut Vec<Diagnostic>) {
+	let compound = CompoundStat::cast(compound_node.clone()).unwrap();
+	let mut terminated = false;
+
+	for stmt in compound.innards() {
+		if terminated {
+			acc.push(Diagnostic {
+				range: stmt.syntax().text_range(),
+				severity: Severity::Warning,
+				message: "unreachable statement".to_string(),
+			});
+		}
+
+		terminated |= matches!(
+			stmt,
+			Statement::Return(_) | Statement::Break(_) | Statement::Continue(_)
+		);
+	}
+}
@@ -0,0 +1,123 @@
+//! Fuzz entry points for the ZScript lexer/parser and the statement-level
+//! incremental reparser, adapted from rust-analyzer's `fuzz.rs`.
+//!
+//! Neither function here drives a fuzzer on its own; each is meant to be
+//! wrapped by a one-line `fuzz_target!` in a `cargo-fuzz` harness crate,
+//! which isn't part of this checkout. They're written against `&[u8]` (not
+//! `&str`) so the harness can hand them raw bytes straight from `arbitrary`
+//! without this module depending on that crate itself.
+
+use rowan::TextRange;
+
+use super::{ParseTree, ParserBuilder, Syn};
+use crate::zdoom::Version;
+
+/// Feeds `data` through [`ParserBuilder::file`] and asserts:
+/// - parsing never panics (a fuzz harness already gets this for free, since
+///   a panic aborts the run, but making it explicit documents the intent);
+/// - the resulting tree's text is byte-for-byte identical to `data` (the
+///   lossless round-trip rowan's trivia-attached tokens are supposed to
+///   guarantee).
+///
+/// Silently returns if `data` isn't valid UTF-8, since the lexer only ever
+/// sees `&str` input.
+pub fn check_parser(data: &[u8]) {
+	let Ok(text) = std::str::from_utf8(data) else {
+		return;
+	};
+
+	let tbuf = crate::scan(text);
+	let parser = ParserBuilder::new(Version::default()).file();
+	let ptree: ParseTree = crate::parse(parser, text, &tbuf);
+
+	assert_eq!(ptree.syntax().text().to_string(), text);
+}
+
+/// A parsed input plus a single text edit to apply to it, as decoded by
+/// [`CheckReparse::from_data`].
+pub struct CheckReparse {
+	text: String,
+	edit_range: TextRange,
+	replacement: String,
+	edited_text: String,
+}
+
+impl CheckReparse {
+	/// Splits `data` into an original source text, an edit range within it,
+	/// and a replacement string, separated by the otherwise-unused `\0`
+	/// byte. Returns `None` if `data` doesn't contain two such separators,
+	/// isn't valid UTF-8, or the decoded range is out of bounds — any of
+	/// which just means this particular fuzz input isn't shaped like a
+	/// reparse case, not that anything is wrong.
+	#[must_use]
+	pub fn from_data(data: &[u8]) -> Option<Self> {
+		let mut parts = data.splitn(3, |&b| b == 0);
+		let text = std::str::from_utf8(parts.next()?).ok()?;
+		let range_spec = std::str::from_utf8(parts.next()?).ok()?;
+		let replacement = std::str::from_utf8(parts.next()?).ok()?;
+
+		let (start, end) = range_spec.split_once(':')?;
+		let start: u32 = start.parse().ok()?;
+		let end: u32 = end.parse().ok()?;
+
+		if start > end || end as usize > text.len() {
+			return None;
+		}
+
+		let edit_range = TextRange::new(start.into(), end.into());
+
+		let mut edited_text = String::with_capacity(text.len() - (end - start) as usize + replacement.len());
+		edited_text.push_str(&text[..start as usize]);
+		edited_text.push_str(replacement);
+		edited_text.push_str(&text[end as usize..]);
+
+		Some(Self {
+			text: text.to_string(),
+			edit_range,
+			replacement: replacement.to_string(),
+			edited_text,
+		})
+	}
+
+	fn parse(text: &str) -> ParseTree {
+		let tbuf = crate::scan(text);
+		let parser = ParserBuilder::new(Version::default()).file();
+		crate::parse(parser, text, &tbuf)
+	}
+}
+
+/// Parses [`CheckReparse::text`], applies its edit via the block-level
+/// incremental reparser (see [`super::parse::reparsing`]), and asserts the
+/// result is structurally identical (same green tree, modulo sharing) to a
+/// full from-scratch parse of [`CheckReparse::edited_text`].
+///
+/// Does nothing if [`CheckReparse::from_data`] can't decode `data`, or if
+/// the incremental reparser declines this edit (falls back to `None`) —
+/// the full-reparse path it would fall back to is exactly what this
+/// function is checking against, so there's nothing to compare in that
+/// case.
+pub fn check_reparse(data: &[u8]) {
+	let Some(case) = CheckReparse::from_data(data) else {
+		return;
+	};
+
+	let old_ptree = CheckReparse::parse(&case.text);
+
+	let Some(reparsed) = super::parse::reparsing::reparse_block(
+		&old_ptree.syntax(),
+		&case.text,
+		case.edit_range,
+		&case.replacement,
+		Version::default(),
+	) else {
+		return;
+	};
+
+	let full_ptree = CheckReparse::parse(&case.edited_text);
+
+	assert_eq!(
+		format!("{:#?}", reparsed.green),
+		format!("{:#?}", full_ptree.syntax().green().into_owned()),
+		"incremental reparse diverged from a full reparse of the edited text"
+	);
+}
@@ -10,7 +10,7 @@ use crate::{
 	GreenElement,
 };
 
-use super::ParserBuilder;
+use super::{keyword::is_contextual_ident, ParserBuilder};
 
 impl ParserBuilder {
 	pub(super) fn array_len<'i>(&self) -> parser_t!(Vec<GreenNode>) {
@@ -27,17 +27,15 @@ impl ParserBuilder {
 		.collect()
 	}
 
+	/// Version-gated: a word that only became a reserved keyword at some
+	/// GZDoom version (per [`super::keyword::CONTEXTUAL_KWS`]) is still
+	/// accepted as a plain identifier when `self.version` precedes it.
 	pub(super) fn ident<'i>(&self) -> parser_t!(GreenToken) {
+		let version = self.version;
+
 		primitive::any()
-			.filter(|token: &Token| {
-				matches!(
-					token,
-					Token::Ident
-						| Token::KwBright | Token::KwFast
-						| Token::KwSlow | Token::KwNoDelay
-						| Token::KwCanRaise | Token::KwOffset
-						| Token::KwLight
-				)
+			.filter(move |token: &Token| {
+				*token == Token::Ident || is_contextual_ident(*token, version)
 			})
 			.map_with_state(comb::green_token(Syn::Ident))
 	}
@@ -0,0 +1,63 @@
+//! The table behind [`super::ParserBuilder::ident`].
+//!
+//! A handful of ZScript words are only reserved starting at a specific
+//! GZDoom version; a [`ParserBuilder`](super::ParserBuilder) targeting an
+//! older version must still accept them as plain identifiers.
+
+use crate::zdoom::{Token, Version};
+
+pub(super) struct ContextualKw {
+	pub(super) token: Token,
+	/// `None` if this word has always been a contextual identifier.
+	/// `Some(v)` if it only became reserved at version `v`, below which it
+	/// is still accepted as a plain identifier.
+	pub(super) since: Option<Version>,
+}
+
+pub(super) const CONTEXTUAL_KWS: &[ContextualKw] = &[
+	ContextualKw {
+		token: Token::KwBright,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwFast,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwSlow,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwCanRaise,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwOffset,
+		since: None,
+	},
+	ContextualKw {
+		token: Token::KwLight,
+		since: None,
+	},
+	// Became a reserved state-qualifier keyword after the other six;
+	// scripts targeting an older version still treat it as a plain ident.
+	ContextualKw {
+		token: Token::KwNoDelay,
+		since: Some(Version::new(3, 7, 0)),
+	},
+];
+
+/// Returns `true` if `token` is contextual at `version`: either it has
+/// always been accepted as a plain identifier here, or `version` predates
+/// the point it became reserved.
+#[must_use]
+pub(super) fn is_contextual_ident(token: Token, version: Version) -> bool {
+	let Some(kw) = CONTEXTUAL_KWS.iter().find(|kw| kw.token == token) else {
+		return false;
+	};
+
+	match kw.since {
+		None => true,
+		Some(since) => version < since,
+	}
+}
@@ -0,0 +1,138 @@
+//! Incremental block-level reparsing of `CompoundStat` subtrees, so an
+//! editor doesn't have to re-lex and re-parse an entire file on every
+//! keystroke inside one function body.
+//!
+//! Mirrors [`crate::zdoom::language::reparsing`] (and this crate's
+//! `crates/doomfront` sibling `zscript/parse/reparsing.rs`, which does the
+//! same at single-token/leaf-combinator granularity), but at block
+//! granularity: find the smallest enclosing `CompoundStat` whose range fully
+//! contains the edit and whose `{`/`}` tokens the edit doesn't cross,
+//! re-lex and re-parse just that block's text, and splice the result back
+//! in at the same offset. The caller should fall back to a full `file`
+//! parse whenever [`reparse_block`] returns `None`, since either condition
+//! means the edit may have changed the shape of an enclosing construct.
+//!
+//! [`splice`] leans on [`ParserBuilder::compound_stat`], the statement-block
+//! counterpart to [`super::common::type_ref`]; that grammar rule isn't in
+//! this checkout yet, so this is the one piece wiring it up is waiting on.
+
+use rowan::{GreenNode, NodeOrToken, TextRange, TextSize};
+
+use crate::zdoom::{
+	zscript::{ParseTree, Syn},
+	Version,
+};
+
+use super::ParserBuilder;
+
+/// The result of a successful [`reparse_block`].
+pub struct Reparsed {
+	/// The new tree root, sharing every subtree [`reparse_block`] didn't
+	/// have to touch.
+	pub green: GreenNode,
+	/// The ranges (in the *new* text) that changed and should be
+	/// re-validated/re-highlighted; always at least the reparsed block's
+	/// new range.
+	pub invalidated: Vec<TextRange>,
+}
+
+/// Attempts an incremental reparse of `old_text` after replacing the bytes
+/// in `edit_range` with `replacement`. `old_text` must be the exact source
+/// `root` was parsed from, and `version` the same [`Version`] it was parsed
+/// with. Returns `None` if no enclosing `CompoundStat` is eligible for a
+/// localized reparse (see module docs); the caller should then fall back to
+/// parsing `old_text` with the edit applied in full.
+pub fn reparse_block(
+	root: &rowan::SyntaxNode<Syn>,
+	old_text: &str,
+	edit_range: TextRange,
+	replacement: &str,
+	version: Version,
+) -> Option<Reparsed> {
+	let target = find_enclosing_compound(root, edit_range)?;
+
+	if crosses_brace(&target, edit_range) {
+		return None;
+	}
+
+	let target_range = target.text_range();
+	let prefix = &old_text[usize::from(target_range.start())..usize::from(edit_range.start())];
+	let suffix = &old_text[usize::from(edit_range.end())..usize::from(target_range.end())];
+
+	let mut new_text = String::with_capacity(prefix.len() + replacement.len() + suffix.len());
+	new_text.push_str(prefix);
+	new_text.push_str(replacement);
+	new_text.push_str(suffix);
+
+	let new_green = splice(&new_text, version)?;
+
+	let new_range = TextRange::at(target_range.start(), TextSize::of(new_text.as_str()));
+
+	Some(Reparsed {
+		green: root_from(&target, new_green),
+		invalidated: vec![new_range],
+	})
+}
+
+/// Walks up from the element covering `edit_range` to find the smallest
+/// enclosing [`Syn::CompoundStat`].
+fn find_enclosing_compound(
+	root: &rowan::SyntaxNode<Syn>,
+	edit_range: TextRange,
+) -> Option<rowan::SyntaxNode<Syn>> {
+	let covering = root.covering_element(edit_range);
+
+	let start = covering.as_node().cloned().unwrap_or_else(|| {
+		covering
+			.as_token()
+			.expect("a covering element is always a node or a token")
+			.parent()
+			.expect("a token always has a parent node")
+	});
+
+	std::iter::successors(Some(start), |node| node.parent())
+		.find(|node| node.kind() == Syn::CompoundStat && node.text_range().contains_range(edit_range))
+}
+
+/// `true` if `edit_range` overlaps one of `target`'s own `{`/`}` tokens; an
+/// edit touching either means it may no longer close off the same block
+/// (e.g. deleting the `}` merges this block into whatever follows it), so
+/// it is never safe to reparse `target` in isolation.
+fn crosses_brace(target: &rowan::SyntaxNode<Syn>, edit_range: TextRange) -> bool {
+	target
+		.children_with_tokens()
+		.filter_map(|elem| elem.into_token())
+		.filter(|t| matches!(t.kind(), Syn::BraceL | Syn::BraceR))
+		.any(|t| edit_range.intersect(t.text_range()).is_some())
+}
+
+/// Re-lexes `text` and re-runs [`ParserBuilder::compound_stat`] over it,
+/// returning the resulting green node on success.
+fn splice(text: &str, version: Version) -> Option<GreenNode> {
+	let tbuf = crate::scan(text);
+	let parser = ParserBuilder::new(version).compound_stat();
+	let ptree: ParseTree = crate::parse(parser, text, &tbuf);
+
+	if !ptree.errors.is_empty() {
+		return None;
+	}
+
+	Some(ptree.syntax().green().into_owned())
+}
+
+/// Walks from `descendant` back up to the root, re-wrapping each ancestor's
+/// green node with `replacement` substituted at the appropriate child
+/// index, and returns the new root.
+fn root_from(descendant: &rowan::SyntaxNode<Syn>, replacement: GreenNode) -> GreenNode {
+	let Some(parent) = descendant.parent() else {
+		return replacement;
+	};
+
+	let index = descendant.index();
+	let new_parent_green = parent
+		.green()
+		.into_owned()
+		.replace_child(index, NodeOrToken::Node(replacement));
+
+	root_from(&parent, new_parent_green)
+}
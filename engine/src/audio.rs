@@ -1,18 +1,26 @@
 //! Sound- and music-related code.
 
+mod fm;
 mod gui;
 mod midi;
+mod worker;
 
 use std::{
+	collections::HashMap,
 	io::{Cursor, Read, Seek},
 	ops::{Deref, DerefMut},
 	path::{Path, PathBuf},
 	sync::Arc,
 };
 
+use cpal::traits::{DeviceTrait, HostTrait};
+use glam::Vec3;
 use kira::{
 	manager::{
-		backend::{cpal::CpalBackend, Backend},
+		backend::{
+			cpal::{CpalBackend, CpalBackendSettings},
+			Backend,
+		},
 		error::PlaySoundError,
 		AudioManager, AudioManagerSettings,
 	},
@@ -26,6 +34,7 @@ use log::{info, warn};
 use nodi::midly;
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
+use souvlaki::{MediaControlEvent, MediaControls, MediaMetadata, MediaPlayback, PlatformConfig};
 
 use crate::{
 	data::{Catalog, FileRef},
@@ -33,9 +42,11 @@ use crate::{
 	utils,
 };
 
+pub use fm::{Bank as FmBank, Error as FmError, Settings as FmSettings};
 pub use midi::{
 	render as render_midi, Data as MidiData, Handle as MidiHandle, Settings as MidiSettings,
 };
+pub use worker::{spawn as spawn_worker, AudioHandle, Command as AudioCommand, MusicSlot, Status as AudioStatus};
 
 use self::gui::DeveloperGui;
 
@@ -51,10 +62,106 @@ pub struct AudioCore {
 	pub music2: Option<Handle>,
 	/// Sounds currently being played.
 	pub sounds: Vec<Sound>,
+	/// Position and facing used to derive panning/attenuation for sounds
+	/// whose [`Sound::source`] is `Some`.
+	pub listener: Listener,
+	/// Cached world positions of the actors named by each live [`Sound`]'s
+	/// `source`, kept current by callers via [`AudioCore::set_source_position`].
+	source_positions: HashMap<ActorId, Vec3>,
+	/// Name of the output device `manager` was last built against, or `None`
+	/// if it's using whatever cpal picked as the host default.
+	output_device: Option<String>,
+	/// What's playing in `music1`/`music2`, kept alongside the handles so
+	/// [`AudioCore::set_output_device`] can restart them on the rebuilt
+	/// [`AudioManager`], and so [`AudioCore::publish_media_session`] has a
+	/// title to report.
+	music1_track: Option<MusicTrack>,
+	music2_track: Option<MusicTrack>,
+	/// Tracks pending to auto-advance into `music1`, and a history of what
+	/// already played there. Slot 2's duck-and-resume is untouched by this;
+	/// it's purely a `music1` concept.
+	music_queue: MusicQueue,
+	/// Multiplies the distance attenuation [`Sound::respatialize`] computes,
+	/// and is the volume [`AudioCore::set_master_volume`] gives both music
+	/// slots. Set by [`AudioCore::set_master_volume`], and (if present) by
+	/// the OS media session's volume slider.
+	master_volume: f64,
+	/// The OS-level "now playing" integration (MPRIS, SMTC, Now Playing),
+	/// set up by [`AudioCore::init_media_session`]. `None` until then, or if
+	/// the host platform doesn't support one.
+	media_controls: Option<MediaControls>,
+	/// Where [`MediaControlEvent`]s land after `media_controls.attach`; drained
+	/// once per [`AudioCore::update`].
+	media_events: Option<std::sync::mpsc::Receiver<MediaControlEvent>>,
+	/// Whether [`sound_from_file`]/[`sound_from_bytes`] should run their
+	/// loudness analysis pass and bake a corrective gain into the returned
+	/// [`StaticSoundSettings`]. Defaults to `true`; see
+	/// [`AudioCore::set_loudness_normalization`].
+	normalize_loudness: bool,
 	catalog: Arc<RwLock<Catalog>>,
 	gui: DeveloperGui,
 }
 
+/// What's currently assigned to a music slot, retained so it can be
+/// restarted after [`AudioCore::set_output_device`] rebuilds the
+/// [`AudioManager`]. See [`AudioCore::music1_track`]/`music2_track`.
+#[derive(Debug, Clone)]
+struct MusicTrack {
+	source: MusicSource,
+	title: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+enum MusicSource {
+	Wave(StaticSoundData),
+	Midi(MidiData),
+}
+
+/// What plays next in `music1` once the current track stops, and what
+/// already played there. See [`AudioCore::queue_music_wave`]/
+/// `queue_music_midi`/[`AudioCore::skip`]/[`AudioCore::previous`].
+#[derive(Debug, Default)]
+struct MusicQueue {
+	pending: std::collections::VecDeque<MusicTrack>,
+	history: Vec<MusicTrack>,
+	/// How many tracks back from the end of `history` the
+	/// [`AudioCore::previous`] calls since the last push to `history` have
+	/// walked. `0` means nothing has been stepped back through yet; once it
+	/// equals `history.len()`, the history is exhausted and `previous` does
+	/// nothing.
+	history_index: usize,
+}
+
+/// One audio output device, as enumerated by [`AudioCore::output_devices`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputDevice {
+	pub name: String,
+}
+
+/// Where spatialized sounds are heard from; see [`AudioCore::listener`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Listener {
+	pub pos: Vec3,
+	/// Unit vector; `pan` is derived from the signed angle between this and
+	/// the listener-to-source vector.
+	pub facing: Vec3,
+}
+
+impl Default for Listener {
+	fn default() -> Self {
+		Self {
+			pos: Vec3::ZERO,
+			facing: Vec3::X,
+		}
+	}
+}
+
+/// Below this distance from the [`Listener`], a spatialized sound plays at
+/// full volume, centered.
+pub const MIN_AUDIBLE_RADIUS: f32 = 128.0;
+/// Beyond this distance from the [`Listener`], a spatialized sound is muted.
+pub const MAX_AUDIBLE_RADIUS: f32 = 1536.0;
+
 impl AudioCore {
 	/// If `None` is given, the defaults will be used.
 	pub fn new(
@@ -82,6 +189,16 @@ impl AudioCore {
 			music1: None,
 			music2: None,
 			sounds: Vec::with_capacity(sound_cap),
+			listener: Listener::default(),
+			source_positions: HashMap::default(),
+			output_device: None,
+			music1_track: None,
+			music2_track: None,
+			music_queue: MusicQueue::default(),
+			master_volume: 1.0,
+			media_controls: None,
+			media_events: None,
+			normalize_loudness: true,
 			gui: DeveloperGui::default(),
 		};
 
@@ -97,13 +214,22 @@ impl AudioCore {
 
 	/// Sound handles which have finished playing get swap-removed.
 	/// Music handles which have finished playing get assigned `None`.
+	/// Live sounds with a `source` get their volume and panning re-derived
+	/// from [`AudioCore::listener`] and the source's last-known position.
+	/// Incoming OS media-session commands are drained and applied, and the
+	/// session is told about whatever's now playing in `music1`.
 	pub fn update(&mut self) {
 		let mut i = 0;
 
 		while i < self.sounds.len() {
 			if self.sounds[i].state() == PlaybackState::Stopped {
+				if let Some(source) = self.sounds[i].source {
+					self.source_positions.remove(&source);
+				}
+
 				self.sounds.swap_remove(i);
 			} else {
+				self.sounds[i].respatialize(self.listener, self.master_volume, &self.source_positions);
 				i += 1;
 			}
 		}
@@ -111,21 +237,145 @@ impl AudioCore {
 		if let Some(mus) = &mut self.music1 {
 			if mus.state() == PlaybackState::Stopped {
 				let _ = self.music1.take();
+				self.advance_queue();
+			}
+		}
+
+		self.drain_media_events();
+		self.publish_media_session();
+	}
+
+	/// Sets up the OS-level "now playing" integration (MPRIS on Linux, SMTC
+	/// on Windows, the Now Playing center on macOS). `config` carries the
+	/// platform handle souvlaki needs (e.g. an HWND on Windows); see
+	/// [`souvlaki::PlatformConfig`]. A platform with no such integration, or
+	/// a failure to attach, leaves [`AudioCore::media_controls`] `None` and
+	/// media-key/media-overlay control is simply unavailable.
+	pub fn init_media_session(&mut self, config: PlatformConfig) -> Result<(), Error> {
+		let mut controls = MediaControls::new(config).map_err(Error::MediaSession)?;
+		let (tx, rx) = std::sync::mpsc::channel();
+
+		controls
+			.attach(move |event| {
+				let _ = tx.send(event);
+			})
+			.map_err(Error::MediaSession)?;
+
+		self.media_controls = Some(controls);
+		self.media_events = Some(rx);
+
+		Ok(())
+	}
+
+	/// Sets the master volume, clamped to `[0.0, 1.0]`. Both music slots'
+	/// current tracks are set to it at once, and sounds with a `source` pick
+	/// it up on each [`AudioCore::update`]. Sounds with no `source`, and music
+	/// started after this call, keep their own volume.
+	pub fn set_master_volume(&mut self, volume: f64) {
+		self.master_volume = volume.clamp(0.0, 1.0);
+
+		if let Some(mus) = &mut self.music1 {
+			let _ = mus.set_volume(self.master_volume, Tween::default());
+		}
+
+		if let Some(mus) = &mut self.music2 {
+			let _ = mus.set_volume(self.master_volume, Tween::default());
+		}
+	}
+
+	/// Whether [`sound_from_file`]/[`sound_from_bytes`] bake a corrective
+	/// gain into newly decoded sounds to bring them toward
+	/// [`LOUDNESS_TARGET_LUFS`]. Defaults to `true`.
+	#[must_use]
+	pub fn loudness_normalization(&self) -> bool {
+		self.normalize_loudness
+	}
+
+	/// Turn loudness normalization on or off. Authored/precomputed assets
+	/// that were already mixed to a consistent level should usually disable
+	/// this, since the analysis pass would otherwise second-guess a level
+	/// the author chose on purpose.
+	pub fn set_loudness_normalization(&mut self, enabled: bool) {
+		self.normalize_loudness = enabled;
+	}
+
+	fn drain_media_events(&mut self) {
+		let Some(rx) = &self.media_events else {
+			return;
+		};
+
+		while let Ok(event) = rx.try_recv() {
+			match event {
+				MediaControlEvent::Play | MediaControlEvent::Toggle => self.resume_all(),
+				MediaControlEvent::Pause => self.pause_all(),
+				MediaControlEvent::Stop => {
+					let _ = self.stop_all();
+				}
+				MediaControlEvent::SetVolume(volume) => self.set_master_volume(volume),
+				MediaControlEvent::Next => {
+					let _ = self.stop_music::<false>();
+				}
+				_ => {}
 			}
 		}
 	}
 
+	fn publish_media_session(&mut self) {
+		let Some(controls) = &mut self.media_controls else {
+			return;
+		};
+
+		let playback = match &self.music1 {
+			Some(mus) if mus.is_playing() => MediaPlayback::Playing { progress: None },
+			Some(_) => MediaPlayback::Paused { progress: None },
+			None => MediaPlayback::Stopped,
+		};
+
+		let _ = controls.set_playback(playback);
+
+		let metadata = MediaMetadata {
+			title: self.music1_track.as_ref().and_then(|t| t.title.as_deref()),
+			..Default::default()
+		};
+
+		let _ = controls.set_metadata(metadata);
+	}
+
+	/// Updates the cached world position used to spatialize every live
+	/// [`Sound`] whose `source` is `actor`. Call this whenever `actor` moves;
+	/// [`AudioCore::update`] only re-derives volume/panning, it never queries
+	/// the playsim itself.
+	pub fn set_source_position(&mut self, actor: ActorId, pos: Vec3) {
+		self.source_positions.insert(actor, pos);
+	}
+
+	/// Drops the cached position recorded by [`AudioCore::set_source_position`].
+	/// Sounds whose `source` is `actor` fall back to always-audible, centered
+	/// playback until a new position is set.
+	pub fn clear_source_position(&mut self, actor: ActorId) {
+		self.source_positions.remove(&actor);
+	}
+
 	/// This assumes that `data` has already been completely configured.
+	/// `title`, if given, is reported to the OS media session while this
+	/// track is playing; see [`AudioCore::init_media_session`].
 	pub fn start_music_wave<const SLOT2: bool>(
 		&mut self,
 		data: StaticSoundData,
+		title: Option<String>,
 	) -> Result<(), Error> {
-		let handle = self.manager.play(data).map_err(Error::PlayWave)?;
+		let handle = self.manager.play(data.clone()).map_err(Error::PlayWave)?;
+		let track = MusicTrack {
+			source: MusicSource::Wave(data),
+			title,
+		};
 
 		if !SLOT2 {
 			self.music1 = Some(Handle::Wave(handle));
+			self.music1_track = Some(track);
 		} else {
 			self.music2 = Some(Handle::Wave(handle));
+			self.music2_track = Some(track);
 		}
 
 		Ok(())
@@ -134,25 +384,66 @@ impl AudioCore {
 	/// Returns an error if:
 	/// - The given song fails to start playback.
 	/// - The given music slot fails to stop and be cleared.
-	pub fn start_music_midi<const SLOT2: bool>(&mut self, data: MidiData) -> Result<(), Error> {
-		let handle = self.manager.play(data).map_err(Error::PlayMidi)?;
+	///
+	/// `title`, if given, is reported to the OS media session while this
+	/// track is playing; see [`AudioCore::init_media_session`].
+	pub fn start_music_midi<const SLOT2: bool>(
+		&mut self,
+		data: MidiData,
+		title: Option<String>,
+	) -> Result<(), Error> {
+		let handle = self.manager.play(data.clone()).map_err(Error::PlayMidi)?;
 		self.stop_music::<SLOT2>()?;
+		let track = MusicTrack {
+			source: MusicSource::Midi(data),
+			title,
+		};
 
 		if !SLOT2 {
 			self.music1 = Some(Handle::Midi(handle));
+			self.music1_track = Some(track);
 		} else {
 			self.music2 = Some(Handle::Midi(handle));
+			self.music2_track = Some(track);
 		}
 
 		Ok(())
 	}
 
+	/// Starts `midi_bytes` playing in the requested music slot, rendering it
+	/// against `soundfont` first and routing by [`SoundFontKind`]: fluidlite
+	/// (via [`render_midi`]) for [`SoundFontKind::Sf2`]/[`SoundFontKind::Gus`],
+	/// or the software FM core (via [`fm::render`]) for
+	/// [`SoundFontKind::Wopl`]/[`SoundFontKind::Wopn`]. Previously,
+	/// `collect_soundfonts` classified WOPL/WOPN banks but nothing ever
+	/// rendered through them, so picking one produced silence.
+	pub fn start_music_midi_with_font<const SLOT2: bool>(
+		&mut self,
+		midi_bytes: &[u8],
+		soundfont: &SoundFont,
+		title: Option<String>,
+	) -> Result<(), Error> {
+		let data = match soundfont.kind() {
+			SoundFontKind::Sf2 | SoundFontKind::Gus => {
+				render_midi(midi_bytes, soundfont.full_path(), MidiSettings::default())?
+			}
+			SoundFontKind::Wopl | SoundFontKind::Wopn => {
+				let bank_bytes =
+					std::fs::read(soundfont.full_path()).map_err(|err| Error::FmSynth(FmError::Io(err)))?;
+				let bank = fm::Bank::parse(&bank_bytes).map_err(Error::FmSynth)?;
+				fm::render(midi_bytes, &bank, FmSettings::default()).map_err(Error::FmSynth)?
+			}
+		};
+
+		self.start_music_wave::<SLOT2>(data, title)
+	}
+
 	/// Instantly stops the music track in the requested slot and then empties it.
 	pub fn stop_music<const SLOT2: bool>(&mut self) -> Result<(), Error> {
-		let slot = if !SLOT2 {
-			&mut self.music1
+		let (slot, track) = if !SLOT2 {
+			(&mut self.music1, &mut self.music1_track)
 		} else {
-			&mut self.music2
+			(&mut self.music2, &mut self.music2_track)
 		};
 
 		let res = if let Some(mus) = slot {
@@ -162,9 +453,160 @@ impl AudioCore {
 		};
 
 		*slot = None;
+		*track = None;
 		res
 	}
 
+	/// Appends a waveform track to the `music1` queue. If nothing is
+	/// currently playing in `music1`, starts it immediately.
+	pub fn queue_music_wave(&mut self, data: StaticSoundData, title: Option<String>) -> Result<(), Error> {
+		self.music_queue.pending.push_back(MusicTrack {
+			source: MusicSource::Wave(data),
+			title,
+		});
+
+		if self.music1.is_none() {
+			if let Some(next) = self.music_queue.pending.pop_front() {
+				return self.restart_music::<false>(next);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Appends a MIDI track to the `music1` queue. If nothing is currently
+	/// playing in `music1`, starts it immediately.
+	pub fn queue_music_midi(&mut self, data: MidiData, title: Option<String>) -> Result<(), Error> {
+		self.music_queue.pending.push_back(MusicTrack {
+			source: MusicSource::Midi(data),
+			title,
+		});
+
+		if self.music1.is_none() {
+			if let Some(next) = self.music_queue.pending.pop_front() {
+				return self.restart_music::<false>(next);
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Stops whatever's playing in `music1`, records it to history, and
+	/// starts the next queued track, if any. Slot 2's duck-and-resume is
+	/// unaffected. Resets the `previous` cursor, since skipping forward from
+	/// here makes any further-back history unreachable via `previous` until
+	/// it's walked again from this new point.
+	pub fn skip(&mut self) -> Result<(), Error> {
+		if let Some(current) = self.music1_track.take() {
+			self.music_queue.history.push(current);
+			self.music_queue.history_index = 0;
+		}
+
+		self.stop_music::<false>()?;
+
+		if let Some(next) = self.music_queue.pending.pop_front() {
+			return self.restart_music::<false>(next);
+		}
+
+		Ok(())
+	}
+
+	/// Walks one step back through history and restarts that track in
+	/// `music1`, re-queuing whatever was playing there so a later `skip`
+	/// returns to it. Does nothing once history is exhausted.
+	pub fn previous(&mut self) -> Result<(), Error> {
+		if self.music_queue.history_index >= self.music_queue.history.len() {
+			return Ok(());
+		}
+
+		let index = self.music_queue.history.len() - 1 - self.music_queue.history_index;
+		self.music_queue.history_index += 1;
+
+		if let Some(current) = self.music1_track.clone() {
+			self.music_queue.pending.push_front(current);
+		}
+
+		self.stop_music::<false>()?;
+		let track = self.music_queue.history[index].clone();
+		self.restart_music::<false>(track)
+	}
+
+	/// Moves whatever just finished in `music1` into history, then starts
+	/// the next queued track, if any.
+	fn advance_queue(&mut self) {
+		if let Some(finished) = self.music1_track.take() {
+			self.music_queue.history.push(finished);
+			self.music_queue.history_index = 0;
+		}
+
+		if let Some(next) = self.music_queue.pending.pop_front() {
+			let _ = self.restart_music::<false>(next);
+		}
+	}
+
+	/// Lists the output devices the host audio API can currently see, by
+	/// name. Pass one of these to [`AudioCore::set_output_device`].
+	pub fn output_devices() -> Result<Vec<OutputDevice>, Error> {
+		let host = cpal::default_host();
+
+		let devices = host.output_devices().map_err(Error::OutputDevices)?;
+
+		Ok(devices
+			.filter_map(|dev| dev.name().ok())
+			.map(|name| OutputDevice { name })
+			.collect())
+	}
+
+	/// The name of the output device `manager` is currently bound to, or
+	/// `None` if it's using whatever cpal picked as the host default.
+	#[must_use]
+	pub fn output_device(&self) -> Option<&str> {
+		self.output_device.as_deref()
+	}
+
+	/// Rebuilds `manager` bound to the output device named `name` (per
+	/// [`AudioCore::output_devices`]), then restarts whatever was playing in
+	/// `music1`/`music2` on the new manager. Sound effects in flight when
+	/// this is called are not carried over; they simply stop.
+	pub fn set_output_device(&mut self, name: &str) -> Result<(), Error> {
+		let host = cpal::default_host();
+
+		let device = host
+			.output_devices()
+			.map_err(Error::OutputDevices)?
+			.find(|dev| matches!(dev.name(), Ok(dev_name) if dev_name == name))
+			.ok_or_else(|| Error::OutputDeviceNotFound(name.to_string()))?;
+
+		let settings = AudioManagerSettings {
+			backend_settings: CpalBackendSettings {
+				device: Some(device),
+				..Default::default()
+			},
+			..Default::default()
+		};
+
+		self.manager = AudioManager::new(settings).map_err(Error::KiraBackend)?;
+		self.output_device = Some(name.to_string());
+		self.sounds.clear();
+
+		if let Some(track) = self.music1_track.take() {
+			self.restart_music::<false>(track)?;
+		}
+
+		if let Some(track) = self.music2_track.take() {
+			self.restart_music::<true>(track)?;
+		}
+
+		Ok(())
+	}
+
+	fn restart_music<const SLOT2: bool>(&mut self, track: MusicTrack) -> Result<(), Error> {
+		match track.source {
+			MusicSource::Wave(data) => self.start_music_wave::<SLOT2>(data, track.title),
+			MusicSource::Midi(data) => self.start_music_midi::<SLOT2>(data, track.title),
+		}
+	}
+
 	/// If no `source` is given, the sound will always audible to all clients
 	/// and not be subjected to any panning or attenuation.
 	pub fn start_sound_wave(
@@ -174,7 +616,7 @@ impl AudioCore {
 	) -> Result<(), Error> {
 		self.sounds.push(Sound {
 			handle: Handle::Wave(self.manager.play(data).map_err(Error::PlayWave)?),
-			_source: source,
+			source,
 		});
 
 		Ok(())
@@ -189,7 +631,7 @@ impl AudioCore {
 	) -> Result<(), Error> {
 		self.sounds.push(Sound {
 			handle: Handle::Midi(self.manager.play(data).map_err(Error::PlayMidi)?),
-			_source: source,
+			source,
 		});
 
 		Ok(())
@@ -416,6 +858,8 @@ impl std::fmt::Debug for AudioCore {
 			.field("music1", &self.music1)
 			.field("music2", &self.music2)
 			.field("sounds", &self.sounds)
+			.field("listener", &self.listener)
+			.field("output_device", &self.output_device)
 			.field("catalog", &self.catalog)
 			.field("gui", &self.gui)
 			.finish()
@@ -466,6 +910,23 @@ impl Handle {
 			Handle::Midi(midi) => midi.is_playing(),
 		}
 	}
+
+	/// `volume` is linear amplitude (`1.0` is unattenuated, `0.0` is silent).
+	pub fn set_volume(&mut self, volume: f64, tween: Tween) -> Result<(), Error> {
+		match self {
+			Handle::Wave(wave) => wave.set_volume(volume, tween).map_err(Error::CommandWave),
+			Handle::Midi(midi) => midi.set_volume(volume, tween),
+		}
+	}
+
+	/// `panning` is in `[0.0, 1.0]`, where `0.0` is hard left, `0.5` is
+	/// center, and `1.0` is hard right.
+	pub fn set_panning(&mut self, panning: f64, tween: Tween) -> Result<(), Error> {
+		match self {
+			Handle::Wave(wave) => wave.set_panning(panning, tween).map_err(Error::CommandWave),
+			Handle::Midi(midi) => midi.set_panning(panning, tween),
+		}
+	}
 }
 
 impl std::fmt::Debug for Handle {
@@ -480,7 +941,52 @@ impl std::fmt::Debug for Handle {
 #[derive(Debug)]
 pub struct Sound {
 	handle: Handle,
-	_source: Option<ActorId>,
+	source: Option<ActorId>,
+}
+
+impl Sound {
+	/// Re-derives volume and panning from `listener` and this sound's
+	/// cached position in `positions`, if it has a `source`. Sounds with no
+	/// `source` are left alone; they stay always-audible and center-panned.
+	fn respatialize(&mut self, listener: Listener, master_volume: f64, positions: &HashMap<ActorId, Vec3>) {
+		let Some(source) = self.source else {
+			return;
+		};
+
+		let Some(&source_pos) = positions.get(&source) else {
+			// No known position: always-audible and centered.
+			let _ = self.handle.set_volume(master_volume, Tween::default());
+			let _ = self.handle.set_panning(0.5, Tween::default());
+			return;
+		};
+
+		let to_source = source_pos - listener.pos;
+		let distance = to_source.length();
+
+		let volume = if distance <= MIN_AUDIBLE_RADIUS {
+			1.0
+		} else if distance >= MAX_AUDIBLE_RADIUS {
+			0.0
+		} else {
+			(MIN_AUDIBLE_RADIUS / distance).clamp(0.0, 1.0)
+		};
+
+		let panning = if distance > f32::EPSILON {
+			let facing = listener.facing.normalize_or_zero();
+			let angle = facing.angle_between(to_source);
+			let side = facing.cross(to_source).dot(Vec3::Z).signum();
+			0.5 + 0.5 * (side * angle.sin()).clamp(-1.0, 1.0)
+		} else {
+			0.5
+		};
+
+		// A short tween avoids the "zipper" artifact of an instant volume
+		// or panning jump.
+		let _ = self
+			.handle
+			.set_volume(volume as f64 * master_volume, Tween::default());
+		let _ = self.handle.set_panning(panning as f64, Tween::default());
+	}
 }
 
 impl Deref for Sound {
@@ -497,25 +1003,178 @@ impl DerefMut for Sound {
 	}
 }
 
+/// If `normalize` is set, the decoded sound's estimated loudness (see
+/// [`normalization_gain`]) is baked into `settings.volume` before it's
+/// returned. Pass [`AudioCore::loudness_normalization`] for `normalize` to
+/// respect the engine-wide toggle.
 pub fn sound_from_file(
 	file: FileRef,
-	settings: StaticSoundSettings,
+	mut settings: StaticSoundSettings,
+	normalize: bool,
 ) -> Result<StaticSoundData, Box<dyn std::error::Error>> {
 	let bytes = file.try_read_bytes()?.to_owned();
 	let cursor = Cursor::new(bytes);
 
-	match StaticSoundData::from_cursor(cursor, settings) {
-		Ok(ssd) => Ok(ssd),
+	match StaticSoundData::from_cursor(cursor, settings.clone()) {
+		Ok(ssd) => {
+			if normalize {
+				settings.volume *= normalization_gain(&ssd);
+			}
+
+			Ok(ssd.with_settings(settings))
+		}
 		Err(err) => Err(Box::new(err)),
 	}
 }
 
+/// If `normalize` is set, the decoded sound's estimated loudness (see
+/// [`normalization_gain`]) is baked into `settings.volume` before it's
+/// returned. Pass [`AudioCore::loudness_normalization`] for `normalize` to
+/// respect the engine-wide toggle.
 pub fn sound_from_bytes(
 	bytes: impl Into<Vec<u8>>,
-	settings: StaticSoundSettings,
+	mut settings: StaticSoundSettings,
+	normalize: bool,
 ) -> Result<StaticSoundData, kira::sound::FromFileError> {
 	let cursor = Cursor::new(bytes.into());
-	StaticSoundData::from_cursor(cursor, settings)
+	let ssd = StaticSoundData::from_cursor(cursor, settings.clone())?;
+
+	if normalize {
+		settings.volume *= normalization_gain(&ssd);
+	}
+
+	Ok(ssd.with_settings(settings))
+}
+
+/// Target integrated loudness [`normalization_gain`] normalizes decoded
+/// sounds towards. A little louder than EBU R128's broadcast target (-23
+/// LUFS), since Doom-mod assets are mixed in against gameplay noise rather
+/// than played back in a quiet living room.
+pub const LOUDNESS_TARGET_LUFS: f64 = -18.0;
+
+/// The largest boost [`normalization_gain`] will ever suggest, so a
+/// near-silent or corrupt clip doesn't get amplified into clipping.
+pub const LOUDNESS_GAIN_CEILING_DB: f64 = 12.0;
+
+/// Returns the linear gain that would bring `data`'s estimated integrated
+/// loudness to [`LOUDNESS_TARGET_LUFS`], clamped at
+/// [`LOUDNESS_GAIN_CEILING_DB`]. Loudness is estimated as a K-weighted mean
+/// square over every decoded frame (a shelving filter followed by a
+/// high-pass, approximating EBU R128's prefilter), converted to LUFS via
+/// `-0.691 + 10 * log10(mean_square)`.
+#[must_use]
+pub fn normalization_gain(data: &StaticSoundData) -> f64 {
+	let mean_square = k_weighted_mean_square(&data.frames, data.sample_rate);
+
+	// A silent or all-but-silent clip has no meaningful loudness to correct;
+	// leave it alone rather than dividing by (near) zero.
+	if mean_square <= f64::EPSILON {
+		return 1.0;
+	}
+
+	let lufs = -0.691 + 10.0 * mean_square.log10();
+	let gain_db = (LOUDNESS_TARGET_LUFS - lufs).min(LOUDNESS_GAIN_CEILING_DB);
+	10f64.powf(gain_db / 20.0)
+}
+
+/// One biquad filter stage in the K-weighting prefilter, run in Direct Form
+/// II transposed so only two state variables are needed per channel.
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+	b0: f64,
+	b1: f64,
+	b2: f64,
+	a1: f64,
+	a2: f64,
+	z1: f64,
+	z2: f64,
+}
+
+impl Biquad {
+	/// RBJ cookbook high-shelf, boosting frequencies above `freq` by
+	/// `gain_db`. Approximates the first stage of EBU R128's K-weighting
+	/// curve (a head-acoustics shelf around 1.68 kHz).
+	fn high_shelf(sample_rate: f64, freq: f64, gain_db: f64, q: f64) -> Self {
+		let a = 10f64.powf(gain_db / 40.0);
+		let w0 = std::f64::consts::TAU * freq / sample_rate;
+		let alpha = w0.sin() / (2.0 * q);
+		let cos_w0 = w0.cos();
+		let two_sqrt_a_alpha = 2.0 * a.sqrt() * alpha;
+
+		let b0 = a * ((a + 1.0) + (a - 1.0) * cos_w0 + two_sqrt_a_alpha);
+		let b1 = -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w0);
+		let b2 = a * ((a + 1.0) + (a - 1.0) * cos_w0 - two_sqrt_a_alpha);
+		let a0 = (a + 1.0) - (a - 1.0) * cos_w0 + two_sqrt_a_alpha;
+		let a1 = 2.0 * ((a - 1.0) - (a + 1.0) * cos_w0);
+		let a2 = (a + 1.0) - (a - 1.0) * cos_w0 - two_sqrt_a_alpha;
+
+		Self {
+			b0: b0 / a0,
+			b1: b1 / a0,
+			b2: b2 / a0,
+			a1: a1 / a0,
+			a2: a2 / a0,
+			z1: 0.0,
+			z2: 0.0,
+		}
+	}
+
+	/// RBJ cookbook high-pass, approximating the second stage of EBU R128's
+	/// K-weighting curve (the RLB curve's low-frequency roll-off).
+	fn high_pass(sample_rate: f64, freq: f64, q: f64) -> Self {
+		let w0 = std::f64::consts::TAU * freq / sample_rate;
+		let alpha = w0.sin() / (2.0 * q);
+		let cos_w0 = w0.cos();
+
+		let b1 = -(1.0 + cos_w0);
+		let b0 = -b1 / 2.0;
+		let b2 = b0;
+		let a0 = 1.0 + alpha;
+		let a1 = -2.0 * cos_w0;
+		let a2 = 1.0 - alpha;
+
+		Self {
+			b0: b0 / a0,
+			b1: b1 / a0,
+			b2: b2 / a0,
+			a1: a1 / a0,
+			a2: a2 / a0,
+			z1: 0.0,
+			z2: 0.0,
+		}
+	}
+
+	fn process(&mut self, x: f64) -> f64 {
+		let y = self.b0 * x + self.z1;
+		self.z1 = self.b1 * x - self.a1 * y + self.z2;
+		self.z2 = self.b2 * x - self.a2 * y;
+		y
+	}
+}
+
+/// The mean square of `frames` after running each channel through a
+/// shelving-then-high-pass biquad cascade, approximating EBU R128's
+/// K-weighting prefilter. See [`normalization_gain`].
+fn k_weighted_mean_square(frames: &[kira::sound::Frame], sample_rate: u32) -> f64 {
+	if frames.is_empty() {
+		return 0.0;
+	}
+
+	let sr = sample_rate as f64;
+	let mut shelf_l = Biquad::high_shelf(sr, 1681.0, 4.0, std::f64::consts::FRAC_1_SQRT_2);
+	let mut shelf_r = shelf_l;
+	let mut hp_l = Biquad::high_pass(sr, 38.0, 0.5);
+	let mut hp_r = hp_l;
+
+	let mut sum_sq = 0.0;
+
+	for frame in frames {
+		let l = hp_l.process(shelf_l.process(frame.left as f64));
+		let r = hp_r.process(shelf_r.process(frame.right as f64));
+		sum_sq += l * l + r * r;
+	}
+
+	sum_sq / (frames.len() as f64 * 2.0)
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -607,10 +1266,16 @@ pub enum Error {
 	KiraBackend(<CpalBackend as Backend>::Error),
 	ParseMidi(midly::Error),
 	MidiSynth(fluidlite::Error),
+	/// A WOPL/WOPN bank failed to parse, or the MIDI being rendered through
+	/// it did. See [`fm::render`].
+	FmSynth(FmError),
 	PlayMidi(PlayMidiError),
 	PlayWave(PlayWaveError),
 	CommandMidi,
 	CommandWave(kira::CommandError),
+	OutputDevices(cpal::DevicesError),
+	OutputDeviceNotFound(String),
+	MediaSession(souvlaki::Error),
 }
 
 impl std::error::Error for Error {
@@ -636,10 +1301,16 @@ impl std::fmt::Display for Error {
 			Self::KiraBackend(err) => err.fmt(f),
 			Self::ParseMidi(err) => err.fmt(f),
 			Self::MidiSynth(err) => err.fmt(f),
+			Self::FmSynth(err) => err.fmt(f),
 			Self::PlayMidi(err) => err.fmt(f),
 			Self::PlayWave(err) => err.fmt(f),
 			Self::CommandMidi => write!(f, "Failed to send a command to a MIDI sound."),
 			Self::CommandWave(err) => err.fmt(f),
+			Self::OutputDevices(err) => err.fmt(f),
+			Self::OutputDeviceNotFound(name) => {
+				write!(f, "No output device named `{name}` was found.")
+			}
+			Self::MediaSession(err) => write!(f, "Failed to set up the OS media session: {err:?}"),
 		}
 	}
 }
@@ -0,0 +1,487 @@
+//! A software OPL3/OPN2 FM-synthesis backend for WOPL3/WOPN2 instrument banks.
+//!
+//! [`AudioCore::collect_soundfonts`](super::AudioCore::collect_soundfonts)
+//! sniffs and classifies these banks, but until this module existed, nothing
+//! ever rendered through them; fluidlite only understands SF2 and GUS
+//! patches, so picking one silently produced nothing. [`render`] parses the
+//! bank's per-patch operator tables and runs a minimal software OPL3/OPN2
+//! emulator over a MIDI file's events, producing PCM the same way
+//! [`super::render_midi`] does for fluidlite.
+
+use std::io::Cursor;
+
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+use nodi::midly::{self, MidiMessage, Smf, TrackEventKind};
+
+use super::SoundFontKind;
+
+/// Sample rate the software synth renders PCM at before it's handed to kira.
+const SAMPLE_RATE: u32 = 44100;
+
+/// A MIDI file has no tempo meta-event until proven otherwise; this is the
+/// MIDI spec's own default (120 BPM).
+const DEFAULT_US_PER_BEAT: u32 = 500_000;
+
+const WOPL_MAGIC: &[u8] = b"WOPL3-BANK\0";
+const WOPN_MAGIC: &[u8] = b"WOPN2-BANK\0";
+
+/// Render-time knobs for [`render`].
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+	/// Linear amplitude applied to the synthesized signal before it's
+	/// wrapped in a [`StaticSoundData`]. OPL3/OPN2 patches stack many
+	/// operators per note, so this defaults well below unity to leave
+	/// headroom against clipping.
+	pub gain: f32,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Self { gain: 0.2 }
+	}
+}
+
+#[derive(Debug)]
+pub enum Error {
+	/// The bank's magic number didn't match [`SoundFontKind::Wopl`] or
+	/// [`SoundFontKind::Wopn`].
+	BankMagic,
+	/// The bank was truncated partway through its header or instrument
+	/// table.
+	BankTruncated,
+	/// The rendered PCM could not be decoded into a [`StaticSoundData`].
+	Decode,
+	/// Failed to read the bank file from disk.
+	Io(std::io::Error),
+	/// Failed to parse the MIDI file being rendered.
+	Midi(midly::Error),
+}
+
+impl std::error::Error for Error {
+	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+		match self {
+			Self::Io(err) => Some(err),
+			Self::Midi(err) => Some(err),
+			_ => None,
+		}
+	}
+}
+
+impl std::fmt::Display for Error {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::BankMagic => write!(f, "file is not a WOPL3 or WOPN2 instrument bank"),
+			Self::BankTruncated => write!(f, "WOPL/WOPN bank is truncated"),
+			Self::Decode => write!(f, "failed to decode the FM synth's rendered PCM"),
+			Self::Io(err) => write!(f, "failed to read WOPL/WOPN bank: {err}"),
+			Self::Midi(err) => write!(f, "failed to parse MIDI data: {err}"),
+		}
+	}
+}
+
+/// One operator's envelope, waveform, and keyboard-scaling bytes, laid out
+/// the way WOPL3/WOPN2 store them (regardless of whether the underlying chip
+/// is an OPL3 or an OPN2; the synth below treats both uniformly as 2-op/4-op
+/// FM voices).
+#[derive(Debug, Clone, Copy, Default)]
+struct Operator {
+	attack: u8,
+	decay: u8,
+	sustain: u8,
+	release: u8,
+	waveform: u8,
+	/// Frequency multiplier, as an OPL "multiple" nibble (`0` means `0.5`).
+	multiplier: u8,
+	/// Key-scale level: attenuation per octave above the lowest key.
+	ksl: u8,
+	output_level: u8,
+	tremolo: bool,
+	vibrato: bool,
+	sustaining: bool,
+	ksr: bool,
+}
+
+/// One instrument patch: either a 2-operator voice (`operators[2..]` unused)
+/// or a 4-operator voice, plus the feedback/connection byte(s) that decide
+/// whether each operator pair is chained in series (FM) or summed (additive).
+#[derive(Debug, Clone)]
+struct Patch {
+	four_op: bool,
+	operators: [Operator; 4],
+	feedback: [u8; 2],
+	/// `true` per pair means additive (both operators are carriers); `false`
+	/// means FM (the first operator modulates the second).
+	additive: [bool; 2],
+}
+
+/// A parsed WOPL3 or WOPN2 instrument bank, ready to be indexed by MIDI
+/// program number in [`render`].
+#[derive(Debug, Clone)]
+pub struct Bank {
+	melodic: Vec<Patch>,
+	percussion: Vec<Patch>,
+}
+
+impl Bank {
+	/// Returns [`Error::BankMagic`] if `bytes` doesn't start with the WOPL3
+	/// or WOPN2 magic number (as already sniffed by
+	/// [`AudioCore::collect_soundfonts`](super::AudioCore::collect_soundfonts)).
+	pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+		if bytes.starts_with(WOPL_MAGIC) {
+			Self::parse_inner(bytes, WOPL_MAGIC.len())
+		} else if bytes.starts_with(WOPN_MAGIC) {
+			Self::parse_inner(bytes, WOPN_MAGIC.len())
+		} else {
+			Err(Error::BankMagic)
+		}
+	}
+
+	fn parse_inner(bytes: &[u8], mut pos: usize) -> Result<Self, Error> {
+		let mut read_u16 = |p: &mut usize| -> Result<u16, Error> {
+			let slice = bytes.get(*p..*p + 2).ok_or(Error::BankTruncated)?;
+			*p += 2;
+			Ok(u16::from_le_bytes([slice[0], slice[1]]))
+		};
+
+		// Version, then melodic/percussion patch counts.
+		let _version = read_u16(&mut pos)?;
+		let melodic_count = read_u16(&mut pos)? as usize;
+		let percussion_count = read_u16(&mut pos)? as usize;
+
+		// One global flags byte (deep tremolo / deep vibrato).
+		pos += 1;
+
+		let mut melodic = Vec::with_capacity(melodic_count);
+		let mut percussion = Vec::with_capacity(percussion_count);
+
+		for _ in 0..melodic_count {
+			melodic.push(Self::parse_patch(bytes, &mut pos)?);
+		}
+
+		for _ in 0..percussion_count {
+			percussion.push(Self::parse_patch(bytes, &mut pos)?);
+		}
+
+		Ok(Self { melodic, percussion })
+	}
+
+	fn parse_patch(bytes: &[u8], pos: &mut usize) -> Result<Patch, Error> {
+		let byte = |p: &mut usize| -> Result<u8, Error> {
+			let b = *bytes.get(*p).ok_or(Error::BankTruncated)?;
+			*p += 1;
+			Ok(b)
+		};
+
+		// Instrument name, fixed-width per the WOPL/WOPN patch record.
+		*pos += 32;
+		// Note offsets and velocity/MIDI-bound bytes not needed for synthesis.
+		*pos += 6;
+
+		let flags = byte(pos)?;
+		let four_op = flags & 0x1 != 0;
+
+		let feedback = [byte(pos)? & 0x7, byte(pos)? & 0x7];
+		let additive = [byte(pos)? & 0x1 != 0, byte(pos)? & 0x1 != 0];
+
+		let mut operators = [Operator::default(); 4];
+		let op_count = if four_op { 4 } else { 2 };
+
+		for op in operators.iter_mut().take(op_count) {
+			let avekm = byte(pos)?;
+			let ksl_out = byte(pos)?;
+			let attack_decay = byte(pos)?;
+			let sustain_release = byte(pos)?;
+			let waveform = byte(pos)?;
+
+			*op = Operator {
+				attack: attack_decay >> 4,
+				decay: attack_decay & 0xF,
+				sustain: sustain_release >> 4,
+				release: sustain_release & 0xF,
+				waveform,
+				multiplier: avekm & 0xF,
+				ksl: ksl_out >> 6,
+				output_level: ksl_out & 0x3F,
+				tremolo: avekm & 0x80 != 0,
+				vibrato: avekm & 0x40 != 0,
+				sustaining: avekm & 0x20 != 0,
+				ksr: avekm & 0x10 != 0,
+			};
+		}
+
+		Ok(Patch {
+			four_op,
+			operators,
+			feedback,
+			additive,
+		})
+	}
+
+	fn patch_for(&self, channel: u8, program: u8) -> Option<&Patch> {
+		if channel == 9 {
+			self.percussion.get(program as usize)
+		} else {
+			self.melodic.get(program as usize)
+		}
+		.or_else(|| self.melodic.first())
+	}
+}
+
+/// One sine-table FM operator, carrying its own phase and a linear ADSR
+/// envelope. This is a simplified stand-in for an OPL3/OPN2 operator cell;
+/// it's driven by the same attack/decay/sustain/release/waveform bytes a
+/// real chip would read out of a [`Patch`], but approximates the chip's
+/// logarithmic envelope and multi-waveform tables with a sine oscillator and
+/// a linear envelope ramp.
+#[derive(Debug, Clone, Copy, Default)]
+struct OperatorState {
+	phase: f32,
+	envelope: f32,
+	released: bool,
+	/// This operator's own previous output, fed back into its phase on the
+	/// next sample when it's acting as a self-modulating feedback operator.
+	/// See [`OperatorState::sample`]'s `feedback` parameter.
+	prev_out: f32,
+}
+
+impl OperatorState {
+	/// `modulation` is another operator's output (in `[-1.0, 1.0]`) to phase-
+	/// modulate this one with, or `0.0` for a plain carrier. `feedback` is an
+	/// OPL-style 3-bit feedback level: `0` disables self-modulation, `7`
+	/// feeds this operator's own last output back at full strength.
+	fn sample(&mut self, op: &Operator, freq: f32, modulation: f32, feedback: u8) -> f32 {
+		let multiplier = OPL_MULTIPLIERS[op.multiplier as usize & 0xF];
+		let self_mod = self.prev_out * (feedback as f32 / 7.0);
+		self.phase += freq * multiplier / SAMPLE_RATE as f32;
+		self.phase -= self.phase.floor();
+
+		let out = (std::f32::consts::TAU * (self.phase + modulation + self_mod)).sin();
+		self.prev_out = out;
+
+		let attack_rate = ENVELOPE_RATES[op.attack as usize & 0xF];
+		let decay_rate = ENVELOPE_RATES[op.decay as usize & 0xF];
+		let release_rate = ENVELOPE_RATES[op.release as usize & 0xF];
+		let sustain_level = 1.0 - (op.sustain as f32 / 15.0);
+
+		if self.released {
+			self.envelope = (self.envelope - release_rate).max(0.0);
+		} else if self.envelope < 1.0 {
+			self.envelope = (self.envelope + attack_rate).min(1.0);
+		} else if self.envelope > sustain_level {
+			self.envelope = (self.envelope - decay_rate).max(sustain_level);
+		}
+
+		let level = 1.0 - (op.output_level as f32 / 63.0);
+		out * self.envelope * level
+	}
+}
+
+/// Per-OPL-chip note-on frequency multiplier table, indexed by the 4-bit
+/// "multiple" field of an operator's AVEKM byte.
+const OPL_MULTIPLIERS: [f32; 16] = [
+	0.5, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 10.0, 12.0, 12.0, 15.0, 15.0,
+];
+
+/// Per-tick envelope step sizes, indexed by a 4-bit OPL rate. Rate `0`
+/// never finishes; rate `15` is instantaneous.
+const ENVELOPE_RATES: [f32; 16] = [
+	0.0, 0.00002, 0.00004, 0.00008, 0.00015, 0.0003, 0.0006, 0.0012, 0.0025, 0.005, 0.01, 0.02,
+	0.04, 0.08, 0.2, 1.0,
+];
+
+struct Voice {
+	channel: u8,
+	note: u8,
+	patch: Patch,
+	operators: [OperatorState; 4],
+}
+
+impl Voice {
+	fn new(channel: u8, note: u8, patch: Patch) -> Self {
+		Self {
+			channel,
+			note,
+			patch,
+			operators: [OperatorState::default(); 4],
+		}
+	}
+
+	fn release(&mut self) {
+		for op in &mut self.operators {
+			op.released = true;
+		}
+	}
+
+	/// Mixes this voice's carrier(s) for one sample tick. 2-op voices run the
+	/// modulator (operator 0) into the carrier (operator 1) when `additive[0]`
+	/// is unset, or sum both as carriers otherwise; 4-op voices repeat that
+	/// per pair and average the two pairs.
+	fn sample(&mut self) -> f32 {
+		let freq = note_freq(self.note);
+		let pairs = if self.patch.four_op { 2 } else { 1 };
+		let mut out = 0.0;
+
+		for pair in 0..pairs {
+			let (modulator, carrier) = (pair * 2, pair * 2 + 1);
+			let mod_op = self.patch.operators[modulator];
+			let car_op = self.patch.operators[carrier];
+			let feedback = self.patch.feedback[pair];
+
+			if self.patch.additive[pair] {
+				out += self.operators[modulator].sample(&mod_op, freq, 0.0, feedback);
+				out += self.operators[carrier].sample(&car_op, freq, 0.0, 0);
+			} else {
+				let modulation = self.operators[modulator].sample(&mod_op, freq, 0.0, feedback);
+				out += self.operators[carrier].sample(&car_op, freq, modulation, 0);
+			}
+		}
+
+		out / pairs as f32
+	}
+
+	/// A voice is done once every operator driving audible output has fully
+	/// released.
+	fn finished(&self) -> bool {
+		let pairs = if self.patch.four_op { 2 } else { 1 };
+		let done = |i: usize| self.operators[i].released && self.operators[i].envelope <= 0.0;
+
+		// An additive pair's first operator is a carrier too.
+		(0..pairs).all(|pair| done(pair * 2 + 1) && (!self.patch.additive[pair] || done(pair * 2)))
+	}
+}
+
+/// Converts a MIDI note number to its fundamental frequency in Hz (A4 = 69
+/// is tuned to 440 Hz, same as the rest of the engine's MIDI handling).
+fn note_freq(note: u8) -> f32 {
+	440.0 * 2f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+/// Parses `midi` and renders it against `bank` with a minimal software
+/// OPL3/OPN2 emulator, producing PCM wrapped in a [`StaticSoundData`] the
+/// same way [`super::render_midi`] does for fluidlite. This is what makes
+/// [`SoundFontKind::Wopl`]/[`SoundFontKind::Wopn`] banks audible instead of
+/// just detected.
+pub fn render(midi: &[u8], bank: &Bank, settings: Settings) -> Result<StaticSoundData, Error> {
+	let smf = Smf::parse(midi).map_err(Error::Midi)?;
+	let ticks_per_beat = match smf.header.timing {
+		midly::Timing::Metrical(tpb) => tpb.as_int().max(1) as u32,
+		// SMPTE timing is rare in game-asset MIDIs; fall back to a sane default.
+		midly::Timing::Timecode(fps, subframe) => (fps.as_f32() as u32 * subframe as u32).max(1),
+	};
+
+	// Flatten every track into one time-ordered stream of (tick, event).
+	let mut events: Vec<(u64, TrackEventKind)> = Vec::new();
+
+	for track in &smf.tracks {
+		let mut tick = 0u64;
+
+		for event in track {
+			tick += event.delta.as_int() as u64;
+			events.push((tick, event.kind));
+		}
+	}
+
+	events.sort_by_key(|(tick, _)| *tick);
+
+	let mut us_per_beat = DEFAULT_US_PER_BEAT;
+	let mut programs = [0u8; 16];
+	let mut voices: Vec<Voice> = Vec::new();
+	let mut frames: Vec<[f32; 2]> = Vec::new();
+	let mut last_tick = 0u64;
+
+	for (tick, kind) in events {
+		let delta_ticks = tick - last_tick;
+		last_tick = tick;
+
+		let delta_seconds =
+			(delta_ticks as f64) * (us_per_beat as f64 / 1_000_000.0) / ticks_per_beat as f64;
+		let delta_samples = (delta_seconds * SAMPLE_RATE as f64).round() as u64;
+
+		for _ in 0..delta_samples {
+			frames.push(mix(&mut voices));
+			voices.retain(|v| !v.finished());
+		}
+
+		match kind {
+			TrackEventKind::Midi { channel, message } => {
+				let channel = channel.as_int();
+
+				match message {
+					MidiMessage::NoteOn { key, vel } if vel.as_int() > 0 => {
+						let program = programs[channel as usize & 0xF];
+
+						if let Some(patch) = bank.patch_for(channel, program) {
+							voices.push(Voice::new(channel, key.as_int(), patch.clone()));
+						}
+					}
+					MidiMessage::NoteOn { key, .. } | MidiMessage::NoteOff { key, .. } => {
+						for voice in voices
+							.iter_mut()
+							.filter(|v| v.channel == channel && v.note == key.as_int())
+						{
+							voice.release();
+						}
+					}
+					MidiMessage::ProgramChange { program } => {
+						programs[channel as usize & 0xF] = program.as_int();
+					}
+					_ => {}
+				}
+			}
+			TrackEventKind::Meta(midly::MetaMessage::Tempo(us)) => {
+				us_per_beat = us.as_int();
+			}
+			_ => {}
+		}
+	}
+
+	let samples = frames
+		.into_iter()
+		.flat_map(|[l, r]| {
+			[
+				(l * settings.gain).clamp(-1.0, 1.0),
+				(r * settings.gain).clamp(-1.0, 1.0),
+			]
+		})
+		.flat_map(|s| s.to_le_bytes())
+		.collect::<Vec<u8>>();
+
+	let wav = wrap_pcm_as_wav(&samples, SAMPLE_RATE);
+
+	StaticSoundData::from_cursor(Cursor::new(wav), StaticSoundSettings::default())
+		.map_err(|_| Error::Decode)
+}
+
+/// Sums every live [`Voice`] into one center-panned stereo frame.
+fn mix(voices: &mut [Voice]) -> [f32; 2] {
+	let sum: f32 = voices.iter_mut().map(Voice::sample).sum();
+	[sum, sum]
+}
+
+/// Wraps raw little-endian `f32` stereo PCM in a minimal canonical WAV
+/// container so it can be handed to [`StaticSoundData::from_cursor`], the
+/// same decoder every other sound in the engine goes through.
+fn wrap_pcm_as_wav(samples: &[u8], sample_rate: u32) -> Vec<u8> {
+	let channels = 2u16;
+	let bits_per_sample = 32u16;
+	let block_align = channels * (bits_per_sample / 8);
+	let byte_rate = sample_rate * block_align as u32;
+	let data_len = samples.len() as u32;
+
+	let mut wav = Vec::with_capacity(44 + samples.len());
+	wav.extend_from_slice(b"RIFF");
+	wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+	wav.extend_from_slice(b"WAVE");
+	wav.extend_from_slice(b"fmt ");
+	wav.extend_from_slice(&16u32.to_le_bytes());
+	wav.extend_from_slice(&3u16.to_le_bytes()); // IEEE float
+	wav.extend_from_slice(&channels.to_le_bytes());
+	wav.extend_from_slice(&sample_rate.to_le_bytes());
+	wav.extend_from_slice(&byte_rate.to_le_bytes());
+	wav.extend_from_slice(&block_align.to_le_bytes());
+	wav.extend_from_slice(&bits_per_sample.to_le_bytes());
+	wav.extend_from_slice(b"data");
+	wav.extend_from_slice(&data_len.to_le_bytes());
+	wav.extend_from_slice(samples);
+	wav
+}
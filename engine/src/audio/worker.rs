@@ -0,0 +1,342 @@
+//! Runs an [`AudioCore`] on a dedicated thread behind a command channel, so
+//! a stall or panic on the calling thread can't interrupt playback, and a
+//! single malformed sound can't tear down the whole engine.
+
+use std::{
+	sync::{
+		mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender, TryRecvError},
+		Arc,
+	},
+	time::Duration,
+};
+
+use kira::manager::{backend::cpal::CpalBackend, AudioManagerSettings};
+use log::warn;
+use parking_lot::RwLock;
+
+use glam::Vec3;
+
+use crate::{data::Catalog, sim::ActorId};
+
+use super::{AudioCore, Error, Listener, MidiData, SoundFont};
+
+/// How often the worker drains pending [`Command`]s and calls
+/// [`AudioCore::update`], absent any commands to wake it sooner.
+const TICK_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Selects a music slot; see [`AudioCore::music1`]/[`AudioCore::music2`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MusicSlot {
+	One,
+	Two,
+}
+
+/// A request sent to the audio worker thread over [`AudioHandle`].
+#[derive(Debug)]
+pub enum Command {
+	/// `slot` being `Some` plays (and replaces) a music track; `None` plays
+	/// a transient, possibly spatialized, sound effect.
+	PlayWave {
+		data: kira::sound::static_sound::StaticSoundData,
+		slot: Option<MusicSlot>,
+		source: Option<ActorId>,
+		/// Only used when `slot` is `Some`; see [`AudioCore::start_music_wave`].
+		title: Option<String>,
+	},
+	PlayMidi {
+		data: MidiData,
+		slot: Option<MusicSlot>,
+		source: Option<ActorId>,
+		/// Only used when `slot` is `Some`; see [`AudioCore::start_music_midi`].
+		title: Option<String>,
+	},
+	/// Renders `midi_bytes` against `soundfont` and starts it in `slot`,
+	/// picking fluidlite or the software FM core by the soundfont's kind;
+	/// see [`AudioCore::start_music_midi_with_font`].
+	PlayMidiWithFont {
+		midi_bytes: Vec<u8>,
+		soundfont: SoundFont,
+		slot: MusicSlot,
+		title: Option<String>,
+	},
+	StopMusic {
+		slot: MusicSlot,
+	},
+	PauseAll,
+	ResumeAll,
+	StopAll,
+	CollectSoundfonts,
+	SetListener(Listener),
+	SetSourcePosition(ActorId, Vec3),
+	ClearSourcePosition(ActorId),
+	SetOutputDevice(String),
+	SetMasterVolume(f64),
+	QueueMusicWave {
+		data: kira::sound::static_sound::StaticSoundData,
+		title: Option<String>,
+	},
+	QueueMusicMidi {
+		data: MidiData,
+		title: Option<String>,
+	},
+	Skip,
+	Previous,
+}
+
+/// Reported back from the audio worker thread over the channel returned
+/// alongside [`AudioHandle`] by [`spawn`].
+#[derive(Debug)]
+pub enum Status {
+	/// A command failed; the sound or mutation it described was dropped.
+	Error(Error),
+	Soundfonts(Vec<SoundFont>),
+}
+
+/// A cheap, cloneable front end for an [`AudioCore`] running on its own
+/// thread. Every method is fire-and-forget: commands that can't be
+/// delivered (the worker thread panicked, or its queue is full) are logged
+/// and dropped rather than propagated, since nothing the caller can do about
+/// a backed-up audio thread should block the game loop.
+#[derive(Clone)]
+pub struct AudioHandle {
+	commands: SyncSender<Command>,
+}
+
+impl AudioHandle {
+	fn send(&self, command: Command) {
+		if self.commands.try_send(command).is_err() {
+			warn!("audio command dropped; the audio worker thread is unreachable");
+		}
+	}
+
+	pub fn play_sound_wave(&self, data: kira::sound::static_sound::StaticSoundData, source: Option<ActorId>) {
+		self.send(Command::PlayWave {
+			data,
+			slot: None,
+			source,
+			title: None,
+		});
+	}
+
+	pub fn play_sound_midi(&self, data: MidiData, source: Option<ActorId>) {
+		self.send(Command::PlayMidi {
+			data,
+			slot: None,
+			source,
+			title: None,
+		});
+	}
+
+	pub fn play_music_wave(
+		&self,
+		data: kira::sound::static_sound::StaticSoundData,
+		slot: MusicSlot,
+		title: Option<String>,
+	) {
+		self.send(Command::PlayWave {
+			data,
+			slot: Some(slot),
+			source: None,
+			title,
+		});
+	}
+
+	pub fn play_music_midi(&self, data: MidiData, slot: MusicSlot, title: Option<String>) {
+		self.send(Command::PlayMidi {
+			data,
+			slot: Some(slot),
+			source: None,
+			title,
+		});
+	}
+
+	pub fn play_music_midi_with_font(
+		&self,
+		midi_bytes: Vec<u8>,
+		soundfont: SoundFont,
+		slot: MusicSlot,
+		title: Option<String>,
+	) {
+		self.send(Command::PlayMidiWithFont {
+			midi_bytes,
+			soundfont,
+			slot,
+			title,
+		});
+	}
+
+	pub fn stop_music(&self, slot: MusicSlot) {
+		self.send(Command::StopMusic { slot });
+	}
+
+	pub fn pause_all(&self) {
+		self.send(Command::PauseAll);
+	}
+
+	pub fn resume_all(&self) {
+		self.send(Command::ResumeAll);
+	}
+
+	pub fn stop_all(&self) {
+		self.send(Command::StopAll);
+	}
+
+	pub fn collect_soundfonts(&self) {
+		self.send(Command::CollectSoundfonts);
+	}
+
+	pub fn set_listener(&self, listener: Listener) {
+		self.send(Command::SetListener(listener));
+	}
+
+	pub fn set_source_position(&self, actor: ActorId, pos: Vec3) {
+		self.send(Command::SetSourcePosition(actor, pos));
+	}
+
+	pub fn clear_source_position(&self, actor: ActorId) {
+		self.send(Command::ClearSourcePosition(actor));
+	}
+
+	pub fn set_output_device(&self, name: impl Into<String>) {
+		self.send(Command::SetOutputDevice(name.into()));
+	}
+
+	pub fn set_master_volume(&self, volume: f64) {
+		self.send(Command::SetMasterVolume(volume));
+	}
+
+	pub fn queue_music_wave(&self, data: kira::sound::static_sound::StaticSoundData, title: Option<String>) {
+		self.send(Command::QueueMusicWave { data, title });
+	}
+
+	pub fn queue_music_midi(&self, data: MidiData, title: Option<String>) {
+		self.send(Command::QueueMusicMidi { data, title });
+	}
+
+	pub fn skip(&self) {
+		self.send(Command::Skip);
+	}
+
+	pub fn previous(&self) {
+		self.send(Command::Previous);
+	}
+}
+
+/// Spawns the audio worker thread, which owns the [`AudioCore`] built from
+/// `catalog` and `manager_settings` for the rest of its life. Returns a
+/// cheap [`AudioHandle`] to send it commands, and the receiving end of its
+/// status channel.
+pub fn spawn(
+	catalog: Arc<RwLock<Catalog>>,
+	manager_settings: Option<AudioManagerSettings<CpalBackend>>,
+) -> Result<(AudioHandle, Receiver<Status>), Error> {
+	let core = AudioCore::new(catalog, manager_settings)?;
+
+	let (cmd_tx, cmd_rx) = sync_channel(256);
+	let (status_tx, status_rx) = sync_channel(256);
+
+	std::thread::Builder::new()
+		.name("audio-worker".to_string())
+		.spawn(move || worker_loop(core, cmd_rx, status_tx))
+		.expect("failed to spawn the audio worker thread");
+
+	Ok((AudioHandle { commands: cmd_tx }, status_rx))
+}
+
+fn worker_loop(mut core: AudioCore, commands: Receiver<Command>, status: SyncSender<Status>) {
+	loop {
+		match commands.recv_timeout(TICK_INTERVAL) {
+			Ok(command) => handle_command(&mut core, command, &status),
+			Err(RecvTimeoutError::Timeout) => {}
+			Err(RecvTimeoutError::Disconnected) => return,
+		}
+
+		loop {
+			match commands.try_recv() {
+				Ok(command) => handle_command(&mut core, command, &status),
+				Err(TryRecvError::Empty) => break,
+				Err(TryRecvError::Disconnected) => return,
+			}
+		}
+
+		core.update();
+	}
+}
+
+fn handle_command(core: &mut AudioCore, command: Command, status: &SyncSender<Status>) {
+	let res = match command {
+		Command::PlayWave {
+			data,
+			slot,
+			source,
+			title,
+		} => match slot {
+			Some(MusicSlot::One) => core.start_music_wave::<false>(data, title),
+			Some(MusicSlot::Two) => core.start_music_wave::<true>(data, title),
+			None => core.start_sound_wave(data, source),
+		},
+		Command::PlayMidi {
+			data,
+			slot,
+			source,
+			title,
+		} => match slot {
+			Some(MusicSlot::One) => core.start_music_midi::<false>(data, title),
+			Some(MusicSlot::Two) => core.start_music_midi::<true>(data, title),
+			None => core.start_sound_midi(data, source),
+		},
+		Command::PlayMidiWithFont {
+			midi_bytes,
+			soundfont,
+			slot,
+			title,
+		} => match slot {
+			MusicSlot::One => core.start_music_midi_with_font::<false>(&midi_bytes, &soundfont, title),
+			MusicSlot::Two => core.start_music_midi_with_font::<true>(&midi_bytes, &soundfont, title),
+		},
+		Command::StopMusic { slot } => match slot {
+			MusicSlot::One => core.stop_music::<false>(),
+			MusicSlot::Two => core.stop_music::<true>(),
+		},
+		Command::PauseAll => {
+			core.pause_all();
+			Ok(())
+		}
+		Command::ResumeAll => {
+			core.resume_all();
+			Ok(())
+		}
+		Command::StopAll => core.stop_all(),
+		Command::CollectSoundfonts => {
+			let res = core.collect_soundfonts();
+			let _ = status.try_send(Status::Soundfonts(core.soundfonts.clone()));
+			res
+		}
+		Command::SetListener(listener) => {
+			core.listener = listener;
+			Ok(())
+		}
+		Command::SetSourcePosition(actor, pos) => {
+			core.set_source_position(actor, pos);
+			Ok(())
+		}
+		Command::ClearSourcePosition(actor) => {
+			core.clear_source_position(actor);
+			Ok(())
+		}
+		Command::SetOutputDevice(name) => core.set_output_device(&name),
+		Command::SetMasterVolume(volume) => {
+			core.set_master_volume(volume);
+			Ok(())
+		}
+		Command::QueueMusicWave { data, title } => core.queue_music_wave(data, title),
+		Command::QueueMusicMidi { data, title } => core.queue_music_midi(data, title),
+		Command::Skip => core.skip(),
+		Command::Previous => core.previous(),
+	};
+
+	if let Err(err) = res {
+		warn!("audio command failed and was dropped: {err}");
+		let _ = status.try_send(Status::Error(err));
+	}
+}
@@ -2,11 +2,12 @@
 
 use std::{
 	any::TypeId,
-	collections::HashMap,
+	collections::{HashMap, HashSet},
+	io::Read,
 	marker::PhantomData,
-	path::Path,
+	path::{Path, PathBuf},
 	sync::{
-		atomic::{self, AtomicU32, AtomicU64},
+		atomic::{self, AtomicBool, AtomicU32, AtomicU64},
 		Arc, Weak,
 	},
 };
@@ -41,6 +42,10 @@ impl PartialEq for InString {
 impl Eq for InString {}
 
 impl<T: AsRef<str>> From<T> for InString {
+	/// Always allocates a fresh, un-deduplicated string; two equal inputs get
+	/// two distinct (and thus unequal, by [`InString`]'s pointer-equality
+	/// `PartialEq`) handles. Prefer [`Mount::intern_string`] when two equal
+	/// strings should also compare equal.
 	fn from(value: T) -> Self {
 		Self(Arc::new(RwLock::new(
 			value.as_ref().to_string().into_boxed_str(),
@@ -48,6 +53,46 @@ impl<T: AsRef<str>> From<T> for InString {
 	}
 }
 
+/// Deduplicates [`InString`]s by content, owned by a [`Mount`] (see
+/// [`Mount::intern_string`]). Entries are held [`Weak`]ly: once every
+/// [`InString`] pointing at some text is dropped, its slot is freed rather
+/// than kept alive for the lifetime of the mount.
+#[derive(Debug, Default)]
+pub(super) struct StringPool {
+	table: RwLock<HashMap<Box<str>, Weak<RwLock<Box<str>>>>>,
+}
+
+impl StringPool {
+	/// Returns a clone of the existing handle for `text` if one is still
+	/// alive, or allocates and registers a new one otherwise. Either way, two
+	/// calls with equal `text` are guaranteed pointer-equal as long as the
+	/// first call's handle (or some clone of it) hasn't been dropped.
+	fn intern(&self, text: &str) -> InString {
+		if let Some(arc) = self.table.read().get(text).and_then(Weak::upgrade) {
+			return InString(arc);
+		}
+
+		let mut table = self.table.write();
+
+		// Another caller may have interned `text` while this one was
+		// waiting on the write lock.
+		if let Some(arc) = table.get(text).and_then(Weak::upgrade) {
+			return InString(arc);
+		}
+
+		let arc = Arc::new(RwLock::new(text.to_string().into_boxed_str()));
+		table.insert(text.into(), Arc::downgrade(&arc));
+		InString(arc)
+	}
+
+	/// Drops entries whose string has no [`InString`] handles left pointing
+	/// at it. Not called automatically; cheap enough to skip most of the
+	/// time, so callers should invoke it themselves after e.g. unmounting.
+	pub(super) fn gc(&self) {
+		self.table.write().retain(|_, weak| weak.strong_count() > 0);
+	}
+}
+
 // FileRef /////////////////////////////////////////////////////////////////////
 
 /// The primary interface for quick introspection into the virtual file system;
@@ -124,6 +169,72 @@ impl<'cat> FileRef<'cat> {
 			_ => 0,
 		}
 	}
+
+	/// A content-address for this file's current bytes; see [`ContentId`].
+	/// Two files with the same `content_id` - whether from the same mount,
+	/// different mounts, or the same source re-mounted later - are
+	/// byte-for-byte identical, which is cheaper to check than comparing the
+	/// bodies directly. Returns `Err` under the same conditions as
+	/// `try_read_bytes` (e.g. this file is a directory).
+	pub fn content_id(&self) -> Result<ContentId, Box<dyn std::error::Error>> {
+		self.file.try_read_bytes().map(ContentId::of)
+	}
+}
+
+// ContentId ///////////////////////////////////////////////////////////////////
+
+/// A 256-bit BLAKE3 digest of a [`VirtualFile`]'s binary contents. Doom WADs
+/// and PK3s routinely ship many byte-identical lumps (shared flats, sounds,
+/// palettes) across otherwise-unrelated mounts; comparing `ContentId`s (or
+/// using one as a `HashMap` key, as [`Catalog::dedup_report`] does) is a
+/// cheap stand-in for comparing whole file bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentId([u8; 32]);
+
+impl ContentId {
+	/// Hashes `bytes` with BLAKE3. Two equal inputs always produce equal
+	/// [`ContentId`]s, and in practice (barring a hash collision) the reverse
+	/// holds too.
+	#[must_use]
+	pub fn of(bytes: &[u8]) -> Self {
+		Self(blake3::hash(bytes).into())
+	}
+}
+
+impl std::fmt::Display for ContentId {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		for byte in self.0 {
+			write!(f, "{byte:02x}")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Reports how much of the catalog's mounted content is byte-for-byte
+/// duplicated; see [`Catalog::dedup_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DedupReport {
+	/// The summed size of every mounted file's body, counting duplicates
+	/// once per file that has them.
+	pub total_bytes: u64,
+	/// The summed size of every *distinct* mounted file body; duplicates,
+	/// wherever they came from, are only counted once.
+	pub unique_bytes: u64,
+}
+
+impl DedupReport {
+	/// `0.0` means nothing mounted is a duplicate of anything else;
+	/// it approaches `1.0` as more mounted files share one identical body
+	/// (`n` identical files give `1 - 1/n`).
+	#[must_use]
+	pub fn savings_ratio(&self) -> f64 {
+		if self.total_bytes == 0 {
+			return 0.0;
+		}
+
+		1.0 - ((self.unique_bytes as f64) / (self.total_bytes as f64))
+	}
 }
 
 impl std::ops::Deref for FileRef<'_> {
@@ -163,8 +274,11 @@ pub struct Mount {
 	///
 	/// [`Blueprint`]: super::asset::Blueprint
 	pub(super) spawn_numbers: HashMap<SpawnNum, Weak<Record>>,
-	/// Keys take the form `$ID` as in (G)ZDoom.
+	/// Keys take the form `$ID` as in (G)ZDoom. Values should come from
+	/// [`Self::intern_string`] rather than [`InString::from`] directly, so
+	/// that equal `$ID` values genuinely share one allocation.
 	pub(super) strings: HashMap<String, InString>,
+	string_pool: StringPool,
 	// Q: FNV hashing for int-keyed, short ID-keyed maps?
 }
 
@@ -179,9 +293,19 @@ impl Mount {
 			editor_numbers: HashMap::default(),
 			spawn_numbers: HashMap::default(),
 			strings: HashMap::default(),
+			string_pool: StringPool::default(),
 		}
 	}
 
+	/// Interns `text` through this mount's [`StringPool`]. Unlike
+	/// [`InString::from`], two calls with equal `text` return pointer-equal
+	/// handles (as long as the first call's handle is still alive), so
+	/// cheap pointer-equality comparisons on the result are meaningful.
+	#[must_use]
+	pub fn intern_string(&self, text: &str) -> InString {
+		self.string_pool.intern(text)
+	}
+
 	/// Metadata about this mount.
 	#[must_use]
 	pub fn info(&self) -> &MountInfo {
@@ -235,11 +359,21 @@ pub struct MountInfo {
 	/// A package can only specify a file owned by it as a script root, so this
 	/// is always relative. `viletech.vpk3`'s script root, for example, is `main.lith`.
 	pub(super) script_root: Option<Box<VPath>>,
-	// Q:
-	// - Dependency specification?
-	// - Incompatibility specification?
-	// - Ordering specification?
-	// - Forced specifications, or just strongly-worded warnings? Multiple levels?
+	/// Specified by `meta.toml` if one exists. Hard dependencies: a
+	/// [`Catalog::resolve_load_order`] treats each of these as a required
+	/// edge (this mount must load after it) and warns, rather than erroring,
+	/// if one isn't among the requested mounts at all.
+	pub(super) dependencies: Vec<PackageRef>,
+	/// Specified by `meta.toml` if one exists. [`Catalog::resolve_load_order`]
+	/// fails fast if two requested mounts name each other here.
+	pub(super) incompatibilities: Vec<PackageRef>,
+	/// Specified by `meta.toml` if one exists. Like [`Self::dependencies`]
+	/// but soft: only constrains ordering relative to the named package if
+	/// it's also being mounted, with no warning if it isn't.
+	pub(super) load_after: Vec<PackageRef>,
+	/// Specified by `meta.toml` if one exists. The mirror image of
+	/// [`Self::load_after`]: this mount must load before the named package.
+	pub(super) load_before: Vec<PackageRef>,
 }
 
 /// Informs the rules used for post-processing assets from a mount.
@@ -276,7 +410,135 @@ pub enum MountFormat {
 	Directory,
 	Wad,
 	Zip,
-	// TODO: Support LZMA, XZ, GRP, PAK, RFF, SSI
+	/// Raw LZMA stream (no XZ container). Header `5D 00`.
+	Lzma,
+	/// XZ container. Header `FD 37 7A 58 5A 00`.
+	Xz,
+	/// Gzip stream. Header `1F 8B`.
+	Gzip,
+	/// Build engine "groupfile". Header `KenSilverman`.
+	Grp,
+	/// id Software "PACK" file, as used by Quake. Header `PACK`.
+	Pak,
+	/// Build engine "RFF" ("resource file format"). Header `RFF\x1A`.
+	Rff,
+	/// Shadow Warrior "SSI" archive. Header `SSI\0`.
+	Ssi,
+}
+
+impl MountFormat {
+	/// Whether mounting this format requires decompressing it first via
+	/// [`decompress::stream_decoder`]; see [`decompress::sniff`].
+	#[must_use]
+	pub fn is_compressed(self) -> bool {
+		matches!(self, Self::Lzma | Self::Xz | Self::Gzip)
+	}
+}
+
+/// Format sniffing and streaming decompression for mount sources.
+///
+/// A source's [`MountFormat`] used to be decided purely by looking at a WAD
+/// or zip header; everything else fell through to [`MountFormat::PlainFile`].
+/// This sniffs a few more container/compression headers and, for the
+/// compressed ones, hands back a [`StreamDecoder`] instead of fully inflating
+/// the source up front, so a large compressed package doesn't need to fit
+/// (twice over) under [`limits::MAX_BIN_FILE_SIZE`] just to be mounted.
+pub mod decompress {
+	use std::io::Read;
+
+	use super::MountFormat;
+
+	/// Looks at the leading bytes of a mount source and guesses its
+	/// [`MountFormat`]. Falls back to `None` (meaning: not a format this
+	/// module recognizes; let the existing WAD/zip/directory/plain-file
+	/// resolution in [`super::MountKind`] take over) if nothing matches.
+	#[must_use]
+	pub fn sniff(header: &[u8]) -> Option<MountFormat> {
+		if header.starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+			return Some(MountFormat::Xz);
+		}
+
+		if header.starts_with(&[0x1F, 0x8B]) {
+			return Some(MountFormat::Gzip);
+		}
+
+		if header.starts_with(b"KenSilverman") {
+			return Some(MountFormat::Grp);
+		}
+
+		if header.starts_with(b"PACK") {
+			return Some(MountFormat::Pak);
+		}
+
+		if header.starts_with(b"RFF\x1A") {
+			return Some(MountFormat::Rff);
+		}
+
+		if header.starts_with(b"SSI\0") {
+			return Some(MountFormat::Ssi);
+		}
+
+		// Raw LZMA has no fixed magic; its header is a properties byte
+		// followed by a dictionary size. `5D 00` is the properties byte
+		// produced by every encoder in common use (`lc=3, lp=0, pb=2`)
+		// followed by the low byte of the smallest standard dictionary size,
+		// so treat it as LZMA's de facto signature.
+		if header.starts_with(&[0x5D, 0x00]) {
+			return Some(MountFormat::Lzma);
+		}
+
+		None
+	}
+
+	/// A mount source failed to decompress. See [`stream_decoder`].
+	#[derive(Debug)]
+	pub enum Error {
+		/// `sniff` identified a compressed format, but nothing in this build
+		/// knows how to decode it yet. Carries the format that was detected.
+		Unsupported(MountFormat),
+		Io(std::io::Error),
+	}
+
+	impl std::fmt::Display for Error {
+		fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+			match self {
+				Self::Unsupported(format) => {
+					write!(f, "unsupported mount compression format: {format:#?}")
+				}
+				Self::Io(err) => err.fmt(f),
+			}
+		}
+	}
+
+	impl std::error::Error for Error {}
+
+	/// A source of decompressed bytes, read lump-by-lump rather than all at
+	/// once. Wraps whichever decoder [`stream_decoder`] chose for a mount's
+	/// [`MountFormat`], so callers can read an archive's directory and then
+	/// its entries without the whole thing ever being resident in memory at
+	/// full size.
+	pub trait StreamDecoder: Read {}
+
+	impl<T: Read> StreamDecoder for T {}
+
+	/// Wraps `source` in a streaming decoder appropriate to `format`.
+	/// Returns [`Error::Unsupported`] for a format [`sniff`] can identify but
+	/// that doesn't have a decoder wired up in this build yet, rather than
+	/// silently returning the compressed bytes as-is.
+	pub fn stream_decoder<'r>(
+		format: MountFormat,
+		source: impl Read + 'r,
+	) -> Result<Box<dyn StreamDecoder + 'r>, Error> {
+		match format {
+			// `flate2` already handles gzip elsewhere in this workspace (see
+			// `crates/vfs`'s tar.gz support), so there's no reason to leave a
+			// sniffed Gzip source undecoded.
+			MountFormat::Gzip => Ok(Box::new(flate2::read::GzDecoder::new(source))),
+			// No LZMA/XZ decoder crate is pulled into this workspace yet.
+			MountFormat::Lzma | MountFormat::Xz => Err(Error::Unsupported(format)),
+			_ => Ok(Box::new(source)),
+		}
+	}
 }
 
 impl MountInfo {
@@ -345,6 +607,256 @@ impl MountInfo {
 	pub fn public_links(&self) -> &[String] {
 		&self.links
 	}
+
+	#[must_use]
+	pub fn dependencies(&self) -> &[PackageRef] {
+		&self.dependencies
+	}
+
+	#[must_use]
+	pub fn incompatibilities(&self) -> &[PackageRef] {
+		&self.incompatibilities
+	}
+
+	#[must_use]
+	pub fn load_after(&self) -> &[PackageRef] {
+		&self.load_after
+	}
+
+	#[must_use]
+	pub fn load_before(&self) -> &[PackageRef] {
+		&self.load_before
+	}
+}
+
+// Load ordering ///////////////////////////////////////////////////////////////
+
+/// A reference to another package by ID, as used in [`MountInfo::dependencies`]
+/// and its siblings. Parsed from a `meta.toml` entry of the form `"id"` or
+/// `"id op version"` (e.g. `">=1.2.0"`); see [`VersionConstraint::parse`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageRef {
+	pub id: String,
+	pub constraint: Option<VersionConstraint>,
+}
+
+/// A loose, non-semver version comparison against [`MountInfo::version`].
+/// Versions in this ecosystem are arbitrary strings (ZDoom/Eternity mods
+/// don't agree on a scheme), so anything that isn't a bare `>=`/`<=`/`>`/`<`/`=`
+/// prefix just falls back to exact string equality.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionConstraint {
+	Exact(String),
+	AtLeast(String),
+	AtMost(String),
+	GreaterThan(String),
+	LessThan(String),
+}
+
+impl VersionConstraint {
+	/// Parses a constraint like `">=1.2.0"`, `"=1.2.0"`, or bare `"1.2.0"`
+	/// (treated as [`Self::Exact`]).
+	#[must_use]
+	pub fn parse(raw: &str) -> Self {
+		let raw = raw.trim();
+
+		if let Some(rest) = raw.strip_prefix(">=") {
+			Self::AtLeast(rest.trim().to_string())
+		} else if let Some(rest) = raw.strip_prefix("<=") {
+			Self::AtMost(rest.trim().to_string())
+		} else if let Some(rest) = raw.strip_prefix('>') {
+			Self::GreaterThan(rest.trim().to_string())
+		} else if let Some(rest) = raw.strip_prefix('<') {
+			Self::LessThan(rest.trim().to_string())
+		} else if let Some(rest) = raw.strip_prefix('=') {
+			Self::Exact(rest.trim().to_string())
+		} else {
+			Self::Exact(raw.to_string())
+		}
+	}
+
+	/// Whether `version` satisfies this constraint, compared as plain
+	/// strings; callers that need real semver ordering should normalize
+	/// `version` (e.g. zero-pad it) before calling this.
+	#[must_use]
+	pub fn is_satisfied_by(&self, version: &str) -> bool {
+		match self {
+			Self::Exact(v) => v == version,
+			Self::AtLeast(v) => version >= v.as_str(),
+			Self::AtMost(v) => version <= v.as_str(),
+			Self::GreaterThan(v) => version > v.as_str(),
+			Self::LessThan(v) => version < v.as_str(),
+		}
+	}
+}
+
+/// Returned by [`Catalog::resolve_load_order`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadOrder {
+	/// Indices into the mount slice passed to [`Catalog::resolve_load_order`],
+	/// in the order mounting should proceed.
+	pub order: Vec<usize>,
+	/// Non-fatal problems found while resolving, e.g. an absent soft
+	/// dependency. Never populated on an `Err` result.
+	pub warnings: Vec<String>,
+}
+
+impl LoadOrder {
+	/// Maps [`Self::order`] back to package ids, given the same mount slice
+	/// passed to [`Catalog::resolve_load_order`]. Exposed so a frontend can
+	/// display the resolved order without keeping index bookkeeping itself.
+	#[must_use]
+	pub fn ordered_ids(&self, mounts: &[MountInfo]) -> Vec<String> {
+		self.order.iter().map(|&i| mounts[i].id.clone()).collect()
+	}
+}
+
+/// Why [`Catalog::resolve_load_order`] failed outright, as opposed to
+/// reporting a [`LoadOrder::warnings`] entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadOrderError {
+	/// Two requested mounts declared each other incompatible.
+	Incompatible(String, String),
+	/// `dependencies`/`load_after`/`load_before` edges formed a cycle;
+	/// carries the id of every package that could not be ordered: those on
+	/// the cycle and any that depend on them.
+	Cycle(Vec<String>),
+}
+
+impl std::fmt::Display for LoadOrderError {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			Self::Incompatible(a, b) => write!(f, "`{a}` and `{b}` are declared incompatible"),
+			Self::Cycle(ids) => write!(f, "load order cycle detected among: {}", ids.join(", ")),
+		}
+	}
+}
+
+impl std::error::Error for LoadOrderError {}
+
+impl Catalog {
+	/// Builds a dependency graph over `mounts` from their
+	/// [`MountInfo::dependencies`], [`MountInfo::load_after`], and
+	/// [`MountInfo::load_before`], then topologically sorts it with Kahn's
+	/// algorithm (repeatedly emitting nodes with no remaining unsatisfied
+	/// prerequisites), so a mount never loads before something it depends
+	/// on. Meant to be called by [`Catalog::load`] on a [`LoadRequest`]
+	/// before any mounting happens.
+	///
+	/// Fails fast if two requested mounts declare each other
+	/// ([`MountInfo::incompatibilities`]) incompatible, or if the graph
+	/// contains a cycle. An absent soft dependency (named in
+	/// [`MountInfo::dependencies`] but not present in `mounts`) is reported
+	/// as a [`LoadOrder::warnings`] entry rather than failing the whole
+	/// resolution.
+	pub fn resolve_load_order(mounts: &[MountInfo]) -> Result<LoadOrder, LoadOrderError> {
+		let index_of: HashMap<&str, usize> = mounts
+			.iter()
+			.enumerate()
+			.map(|(i, m)| (m.id.as_str(), i))
+			.collect();
+
+		for mount in mounts {
+			for incompat in &mount.incompatibilities {
+				let Some(&j) = index_of.get(incompat.id.as_str()) else {
+					continue;
+				};
+
+				let satisfied = match &incompat.constraint {
+					Some(c) => c.is_satisfied_by(mounts[j].version().unwrap_or("")),
+					None => true,
+				};
+
+				if satisfied {
+					return Err(LoadOrderError::Incompatible(
+						mount.id.clone(),
+						incompat.id.clone(),
+					));
+				}
+			}
+		}
+
+		let mut warnings = Vec::new();
+		// `prereqs[i]` holds every mount that must be emitted before mount `i`.
+		let mut prereqs: Vec<HashSet<usize>> = vec![HashSet::new(); mounts.len()];
+
+		for (i, mount) in mounts.iter().enumerate() {
+			for dep in &mount.dependencies {
+				match index_of.get(dep.id.as_str()) {
+					Some(&j) => {
+						let satisfied = match &dep.constraint {
+							Some(c) => c.is_satisfied_by(mounts[j].version().unwrap_or("")),
+							None => true,
+						};
+
+						if !satisfied {
+							warnings.push(format!(
+								"`{}` depends on `{}`, but the present version does not satisfy its version constraint",
+								mount.id, dep.id
+							));
+						}
+
+						prereqs[i].insert(j);
+					}
+					None => warnings.push(format!(
+						"`{}` depends on `{}`, which was not requested",
+						mount.id, dep.id
+					)),
+				}
+			}
+
+			for after in &mount.load_after {
+				if let Some(&j) = index_of.get(after.id.as_str()) {
+					prereqs[i].insert(j);
+				}
+			}
+
+			for before in &mount.load_before {
+				if let Some(&j) = index_of.get(before.id.as_str()) {
+					prereqs[j].insert(i);
+				}
+			}
+		}
+
+		let mut in_degree: Vec<usize> = prereqs.iter().map(HashSet::len).collect();
+		let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); mounts.len()];
+
+		for (i, p) in prereqs.iter().enumerate() {
+			for &j in p {
+				dependents[j].push(i);
+			}
+		}
+
+		let mut queue: std::collections::VecDeque<usize> = (0..mounts.len())
+			.filter(|&i| in_degree[i] == 0)
+			.collect();
+		let mut order = Vec::with_capacity(mounts.len());
+
+		while let Some(i) = queue.pop_front() {
+			order.push(i);
+
+			for &dependent in &dependents[i] {
+				in_degree[dependent] -= 1;
+
+				if in_degree[dependent] == 0 {
+					queue.push_back(dependent);
+				}
+			}
+		}
+
+		if order.len() != mounts.len() {
+			let emitted: HashSet<usize> = order.iter().copied().collect();
+
+			let cycle = (0..mounts.len())
+				.filter(|i| !emitted.contains(i))
+				.map(|i| mounts[i].id.clone())
+				.collect();
+
+			return Err(LoadOrderError::Cycle(cycle));
+		}
+
+		Ok(LoadOrder { order, warnings })
+	}
 }
 
 // Handle //////////////////////////////////////////////////////////////////////
@@ -438,6 +950,37 @@ impl<A: Asset> PartialEq for InHandle<A> {
 
 impl<A: Asset> Eq for InHandle<A> {}
 
+// Deduplication ///////////////////////////////////////////////////////////////
+
+impl Catalog {
+	/// Hashes every mounted file's body (see [`ContentId`]) and reports how
+	/// much of the total is duplicated, whether within one mount or across
+	/// several. Files that can't be read as bytes (directories, primarily)
+	/// are skipped rather than counted as zero-length.
+	#[must_use]
+	pub fn dedup_report(&self) -> DedupReport {
+		let mut seen = HashSet::new();
+		let mut report = DedupReport {
+			total_bytes: 0,
+			unique_bytes: 0,
+		};
+
+		for file in self.files.values() {
+			let Ok(bytes) = file.try_read_bytes() else {
+				continue;
+			};
+
+			report.total_bytes += bytes.len() as u64;
+
+			if seen.insert(ContentId::of(bytes)) {
+				report.unique_bytes += bytes.len() as u64;
+			}
+		}
+
+		report
+	}
+}
+
 // Configuration ///////////////////////////////////////////////////////////////
 
 /// Configuration methods are kept in a wrapper around a [`Catalog`] reference
@@ -533,6 +1076,10 @@ where
 	/// Only pass a `Some` if you need to, for instance, display a loading screen,
 	/// or otherwise report to the end user on the progress of a mount operation.
 	pub tracker: Option<Arc<LoadTracker>>,
+	/// If given, an unchanged mount (per [`MountCache::check`]) is restored
+	/// from the file here instead of being re-read and re-parsed, and the
+	/// cache is rewritten afterward to reflect whatever was (re)mounted.
+	pub cache_path: Option<Box<Path>>,
 }
 
 /// Wrap in an [`Arc`] and use to check how far along a load operation is.
@@ -544,6 +1091,20 @@ pub struct LoadTracker {
 	pub(super) mount_target: AtomicU64,
 	pub(super) pproc_progress: AtomicU32,
 	pub(super) pproc_target: AtomicU32,
+	/// Mounts restored from a [`MountCache`] instead of being re-read.
+	pub(super) cache_hits: AtomicU32,
+	/// Mounts that a [`MountCache`] couldn't vouch for (missing, stale, or
+	/// [`StampStatus::Ambiguous`]) and so were read and parsed as normal.
+	pub(super) cache_rebuilds: AtomicU32,
+	/// Checked at mount and post-processing boundaries; see [`Self::cancel`].
+	pub(super) cancelled: AtomicBool,
+	/// See [`LoadPhase`].
+	pub(super) phase: AtomicU32,
+	/// The id and virtual path of whatever's currently being mounted or
+	/// post-processed, e.g. for a loading screen to show
+	/// "Processing MAP01 TEXTMAP...". `None` before the first file and after
+	/// [`LoadPhase::Done`].
+	pub(super) current_file: RwLock<Option<(String, Box<VPath>)>>,
 }
 
 impl LoadTracker {
@@ -557,7 +1118,7 @@ impl LoadTracker {
 			return 0.0;
 		}
 
-		(prog / tgt) as f64
+		(prog as f64) / (tgt as f64)
 	}
 
 	/// 0.0 means just started; 1.0 means done.
@@ -570,7 +1131,7 @@ impl LoadTracker {
 			return 0.0;
 		}
 
-		(prog / tgt) as f64
+		(prog as f64) / (tgt as f64)
 	}
 
 	#[must_use]
@@ -589,4 +1150,490 @@ impl LoadTracker {
 		self.mount_progress
 			.fetch_add(bytes, atomic::Ordering::SeqCst);
 	}
+
+	/// How many mounts were restored from a [`MountCache`] this load, rather
+	/// than being re-read and re-parsed.
+	#[must_use]
+	pub fn cache_hits(&self) -> u32 {
+		self.cache_hits.load(atomic::Ordering::SeqCst)
+	}
+
+	/// How many mounts a [`MountCache`] couldn't vouch for this load (missing,
+	/// stale, or too ambiguous to trust) and so were rebuilt as normal.
+	#[must_use]
+	pub fn cache_rebuilds(&self) -> u32 {
+		self.cache_rebuilds.load(atomic::Ordering::SeqCst)
+	}
+
+	pub(super) fn add_cache_hit(&self) {
+		self.cache_hits.fetch_add(1, atomic::Ordering::SeqCst);
+	}
+
+	pub(super) fn add_cache_rebuild(&self) {
+		self.cache_rebuilds.fetch_add(1, atomic::Ordering::SeqCst);
+	}
+
+	/// Requests that the in-progress load stop at its next mount or
+	/// post-processing boundary. Cooperative: the load only actually unwinds
+	/// once whatever's running next checks [`Self::is_cancelled`], and the
+	/// catalog is left exactly as it was before the load started, as if it
+	/// had never been requested.
+	pub fn cancel(&self) {
+		self.cancelled.store(true, atomic::Ordering::SeqCst);
+	}
+
+	#[must_use]
+	pub fn is_cancelled(&self) -> bool {
+		self.cancelled.load(atomic::Ordering::SeqCst)
+	}
+
+	/// The stage the load is currently in; see [`LoadPhase`].
+	#[must_use]
+	pub fn phase(&self) -> LoadPhase {
+		LoadPhase::from_u32(self.phase.load(atomic::Ordering::SeqCst))
+	}
+
+	pub(super) fn set_phase(&self, phase: LoadPhase) {
+		self.phase.store(phase as u32, atomic::Ordering::SeqCst);
+	}
+
+	/// The id and virtual path of whatever's currently being mounted or
+	/// post-processed, e.g. `("mymount", "/mymount/maps/MAP01/TEXTMAP")`, so
+	/// a loading screen can show something like "Processing MAP01 TEXTMAP...".
+	/// `None` before the first file and once the load reaches
+	/// [`LoadPhase::Done`].
+	#[must_use]
+	pub fn current_file(&self) -> Option<(String, Box<VPath>)> {
+		self.current_file.read().clone()
+	}
+
+	pub(super) fn set_current_file(&self, mount_id: impl Into<String>, path: impl Into<Box<VPath>>) {
+		*self.current_file.write() = Some((mount_id.into(), path.into()));
+	}
+
+	pub(super) fn clear_current_file(&self) {
+		*self.current_file.write() = None;
+	}
+}
+
+/// The stage a load operation tracked by [`LoadTracker`] is currently in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LoadPhase {
+	/// Reading and parsing mount sources (or restoring them from a
+	/// [`MountCache`]) into the VFS.
+	Mounting = 0,
+	/// Resolving inter-asset relationships and running format-specific
+	/// passes (e.g. UDMF `TEXTMAP` parsing) over newly-mounted files.
+	PostProcessing = 1,
+	/// Writing the resolved VFS tree and asset metadata back out to a
+	/// [`MountCache`] file.
+	Caching = 2,
+	/// The load has finished (or been cancelled); nothing is in flight.
+	Done = 3,
+}
+
+impl LoadPhase {
+	fn from_u32(value: u32) -> Self {
+		match value {
+			0 => Self::Mounting,
+			1 => Self::PostProcessing,
+			2 => Self::Caching,
+			_ => Self::Done,
+		}
+	}
+}
+
+// MountCache //////////////////////////////////////////////////////////////////
+
+/// Fixed byte sequence every [`MountCache`] file begins with, so a cache left
+/// over from an incompatible build (or a plain corrupt file) is rejected
+/// before its format version is even checked.
+const MOUNTCACHE_MAGIC: [u8; 14] = *b"viletech-vfs1\n";
+
+/// Bumped whenever [`MountCache`]'s on-disk layout changes. A version
+/// mismatch is handled the same as a bad magic marker: the whole file is
+/// discarded and every mount gets rebuilt from its real files.
+const MOUNTCACHE_VERSION: u32 = 1;
+
+/// A cheap-to-check fingerprint of a mount's real file/directory, used by
+/// [`MountCache::check`] to decide whether that mount can be restored from
+/// cache instead of being re-read and re-parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct MountStamp {
+	pub(super) real_path: Box<Path>,
+	pub(super) len: u64,
+	/// Truncated to whole seconds and nanoseconds; see
+	/// [`StampStatus::Ambiguous`].
+	pub(super) mtime: (u64, u32),
+}
+
+impl MountStamp {
+	/// Fails only if `real_path` can't be `stat`-ed.
+	pub(super) fn of(real_path: &Path) -> std::io::Result<Self> {
+		let meta = real_path.metadata()?;
+
+		let since_epoch = meta
+			.modified()?
+			.duration_since(std::time::SystemTime::UNIX_EPOCH)
+			.unwrap_or_default();
+
+		Ok(Self {
+			real_path: real_path.into(),
+			len: meta.len(),
+			mtime: (since_epoch.as_secs(), since_epoch.subsec_nanos()),
+		})
+	}
+
+	fn write_to(&self, out: &mut Vec<u8>) {
+		let path_bytes = self.real_path.to_string_lossy().into_owned().into_bytes();
+
+		out.extend_from_slice(&(path_bytes.len() as u32).to_le_bytes());
+		out.extend_from_slice(&path_bytes);
+		out.extend_from_slice(&self.len.to_le_bytes());
+		out.extend_from_slice(&self.mtime.0.to_le_bytes());
+		out.extend_from_slice(&self.mtime.1.to_le_bytes());
+	}
+
+	fn read_from(inp: &mut impl Read) -> std::io::Result<Self> {
+		let path_len = read_u32(inp)? as usize;
+		let mut path_bytes = vec![0u8; path_len];
+		inp.read_exact(&mut path_bytes)?;
+
+		Ok(Self {
+			real_path: PathBuf::from(String::from_utf8_lossy(&path_bytes).into_owned()).into_boxed_path(),
+			len: read_u64(inp)?,
+			mtime: (read_u64(inp)?, read_u32(inp)?),
+		})
+	}
+}
+
+/// Whether a cached [`MountStamp`] still matches its on-disk source. See
+/// [`MountCache::check`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StampStatus {
+	/// Size and mtime both match; the mount can be restored from cache.
+	Fresh,
+	/// Size or mtime changed, or there was no cached entry at all; the mount
+	/// must be read and parsed from its real file.
+	Stale,
+	/// The stamp's mtime equals the cache file's own write time. A source
+	/// file rewritten in the same second the cache was saved would show an
+	/// unchanged mtime despite having different content, so this is always
+	/// treated as a forced rebuild rather than risking a false hit.
+	Ambiguous,
+}
+
+/// One mount's freshness stamp alongside whatever [`Catalog::load`]
+/// serialized to represent it: its slice of the VFS directory tree and its
+/// assets' metadata. `MountCache` itself doesn't interpret `payload`; it only
+/// decides whether that payload is still trustworthy.
+///
+/// [`Catalog::load`]: super::Catalog::load
+#[derive(Debug, Clone)]
+struct CachedMount {
+	stamp: MountStamp,
+	payload: Vec<u8>,
+}
+
+/// Serializes the mounted VFS directory tree and each mount's asset metadata
+/// to a file, so a later [`Catalog::load`] of the same, unchanged sources can
+/// skip re-reading and re-parsing them. Modeled on Mercurial's dirstate-v2:
+/// a fixed magic marker and format version gate the whole file, and every
+/// entry is re-validated against its live source (see [`Self::check`])
+/// before being trusted.
+///
+/// [`Catalog::load`]: super::Catalog::load
+#[derive(Debug, Default)]
+pub struct MountCache {
+	entries: Vec<CachedMount>,
+}
+
+impl MountCache {
+	#[must_use]
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Reads a cache file previously written by [`Self::write`]. Returns
+	/// `Ok(None)` if `path` doesn't exist, or if its magic marker or format
+	/// version don't match; either way the caller should treat every mount
+	/// as needing a full rebuild rather than trusting a partial read.
+	pub fn read(path: &Path) -> std::io::Result<Option<Self>> {
+		let bytes = match std::fs::read(path) {
+			Ok(b) => b,
+			Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+			Err(err) => return Err(err),
+		};
+
+		let mut cursor = bytes.as_slice();
+		let mut magic = [0u8; MOUNTCACHE_MAGIC.len()];
+
+		if cursor.read_exact(&mut magic).is_err() || magic != MOUNTCACHE_MAGIC {
+			return Ok(None);
+		}
+
+		let version = match read_u32(&mut cursor) {
+			Ok(v) => v,
+			Err(_) => return Ok(None),
+		};
+
+		if version != MOUNTCACHE_VERSION {
+			return Ok(None);
+		}
+
+		let count = read_u32(&mut cursor)? as usize;
+		let mut entries = Vec::with_capacity(count);
+
+		for _ in 0..count {
+			let stamp = MountStamp::read_from(&mut cursor)?;
+			let payload_len = read_u32(&mut cursor)? as usize;
+			let mut payload = vec![0u8; payload_len];
+			cursor.read_exact(&mut payload)?;
+			entries.push(CachedMount { stamp, payload });
+		}
+
+		Ok(Some(Self { entries }))
+	}
+
+	/// Writes this cache to `path`, overwriting whatever was there before.
+	pub fn write(&self, path: &Path) -> std::io::Result<()> {
+		let mut out = Vec::new();
+		out.extend_from_slice(&MOUNTCACHE_MAGIC);
+		out.extend_from_slice(&MOUNTCACHE_VERSION.to_le_bytes());
+		out.extend_from_slice(&(self.entries.len() as u32).to_le_bytes());
+
+		for entry in &self.entries {
+			entry.stamp.write_to(&mut out);
+			out.extend_from_slice(&(entry.payload.len() as u32).to_le_bytes());
+			out.extend_from_slice(&entry.payload);
+		}
+
+		std::fs::write(path, out)
+	}
+
+	/// Stages `payload` as the cached representation of the mount at
+	/// `real_path`, stamping it with that file's current size and mtime.
+	/// Replaces any existing entry for the same path. Fails only if
+	/// `real_path` can't be `stat`-ed.
+	pub fn insert(&mut self, real_path: &Path, payload: Vec<u8>) -> std::io::Result<()> {
+		let stamp = MountStamp::of(real_path)?;
+		self.entries.retain(|e| &*e.stamp.real_path != real_path);
+		self.entries.push(CachedMount { stamp, payload });
+		Ok(())
+	}
+
+	/// Checks whether the entry for `real_path`, if any, is still fresh.
+	/// `cache_mtime` is this cache file's own last-modified time, truncated
+	/// the same way as [`MountStamp::mtime`]; an entry whose stamp matches it
+	/// exactly is always reported [`StampStatus::Ambiguous`], since a source
+	/// file rewritten within the same second the cache was last saved can't
+	/// be told apart from one that was never touched.
+	#[must_use]
+	pub fn check(&self, real_path: &Path, cache_mtime: (u64, u32)) -> StampStatus {
+		let Some(entry) = self.entries.iter().find(|e| &*e.stamp.real_path == real_path) else {
+			return StampStatus::Stale;
+		};
+
+		if entry.stamp.mtime == cache_mtime {
+			return StampStatus::Ambiguous;
+		}
+
+		let Ok(live) = MountStamp::of(real_path) else {
+			return StampStatus::Stale;
+		};
+
+		if entry.stamp.len == live.len && entry.stamp.mtime == live.mtime {
+			StampStatus::Fresh
+		} else {
+			StampStatus::Stale
+		}
+	}
+
+	/// The payload staged for `real_path` by [`Self::insert`] or read back by
+	/// [`Self::read`]. Callers should only trust this after [`Self::check`]
+	/// reports [`StampStatus::Fresh`].
+	#[must_use]
+	pub fn payload(&self, real_path: &Path) -> Option<&[u8]> {
+		self.entries
+			.iter()
+			.find(|e| &*e.stamp.real_path == real_path)
+			.map(|e| e.payload.as_slice())
+	}
+}
+
+fn read_u32(inp: &mut impl Read) -> std::io::Result<u32> {
+	let mut buf = [0u8; 4];
+	inp.read_exact(&mut buf)?;
+	Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(inp: &mut impl Read) -> std::io::Result<u64> {
+	let mut buf = [0u8; 8];
+	inp.read_exact(&mut buf)?;
+	Ok(u64::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn mount(id: &str, dependencies: &[&str], load_after: &[&str], load_before: &[&str]) -> MountInfo {
+		fn refs(ids: &[&str]) -> Vec<PackageRef> {
+			ids.iter()
+				.map(|id| PackageRef {
+					id: id.to_string(),
+					constraint: None,
+				})
+				.collect()
+		}
+
+		MountInfo {
+			id: id.to_string(),
+			format: MountFormat::Directory,
+			kind: MountKind::VileTech,
+			version: None,
+			name: None,
+			description: None,
+			authors: Vec::new(),
+			copyright: None,
+			links: Vec::new(),
+			real_path: Path::new("/dev/null").into(),
+			virtual_path: VPath::new("/").into(),
+			script_root: None,
+			dependencies: refs(dependencies),
+			incompatibilities: Vec::new(),
+			load_after: refs(load_after),
+			load_before: refs(load_before),
+		}
+	}
+
+	/// `d` depends on both `b` and `c`, which both depend on `a`, so either of
+	/// the two valid topological orders must place `a` before `b`/`c` and
+	/// `b`/`c` before `d`.
+	#[test]
+	fn diamond_dependency() {
+		let mounts = [
+			mount("a", &[], &[], &[]),
+			mount("b", &["a"], &[], &[]),
+			mount("c", &["a"], &[], &[]),
+			mount("d", &["b", "c"], &[], &[]),
+		];
+
+		let order = Catalog::resolve_load_order(&mounts).expect("a diamond dependency graph is acyclic");
+		let ids = order.ordered_ids(&mounts);
+
+		let pos = |id: &str| ids.iter().position(|i| i == id).unwrap();
+
+		assert!(pos("a") < pos("b"));
+		assert!(pos("a") < pos("c"));
+		assert!(pos("b") < pos("d"));
+		assert!(pos("c") < pos("d"));
+	}
+
+	/// `a` must load after `b` (via `load_after`) and before it (via
+	/// `load_before`), so neither can come first.
+	#[test]
+	fn real_cycle() {
+		let mounts = [mount("a", &[], &["b"], &["b"]), mount("b", &[], &[], &[])];
+
+		let err = Catalog::resolve_load_order(&mounts).expect_err("a mutual load-order edge is a cycle");
+
+		match err {
+			LoadOrderError::Cycle(mut ids) => {
+				ids.sort();
+				assert_eq!(ids, vec!["a".to_string(), "b".to_string()]);
+			}
+			other => panic!("expected `LoadOrderError::Cycle`, got {other:?}"),
+		}
+	}
+
+	/// A missing soft dependency is a warning, not a hard failure.
+	#[test]
+	fn absent_soft_dependency_warns() {
+		let mounts = [mount("a", &["ghost"], &[], &[])];
+
+		let result = Catalog::resolve_load_order(&mounts).expect("an absent dependency is non-fatal");
+
+		assert_eq!(result.order, vec![0]);
+		assert_eq!(result.warnings.len(), 1);
+	}
+
+	/// An incompatibility naming a version range isn't triggered by a
+	/// present mount whose version falls outside that range.
+	#[test]
+	fn version_constrained_incompatibility_is_not_triggered() {
+		let mut a = mount("a", &[], &[], &[]);
+		a.incompatibilities.push(PackageRef {
+			id: "bar".to_string(),
+			constraint: Some(VersionConstraint::LessThan("1.0.0".to_string())),
+		});
+
+		let mut bar = mount("bar", &[], &[], &[]);
+		bar.version = Some("5.0.0".to_string());
+
+		let mounts = [a, bar];
+
+		let result = Catalog::resolve_load_order(&mounts);
+		assert!(result.is_ok(), "`bar` 5.0.0 does not satisfy `<1.0.0`");
+	}
+
+	/// The same incompatibility does fail the load when the present version
+	/// falls within the declared range.
+	#[test]
+	fn version_constrained_incompatibility_is_triggered() {
+		let mut a = mount("a", &[], &[], &[]);
+		a.incompatibilities.push(PackageRef {
+			id: "bar".to_string(),
+			constraint: Some(VersionConstraint::LessThan("1.0.0".to_string())),
+		});
+
+		let mut bar = mount("bar", &[], &[], &[]);
+		bar.version = Some("0.5.0".to_string());
+
+		let mounts = [a, bar];
+
+		let err = Catalog::resolve_load_order(&mounts).expect_err("`bar` 0.5.0 satisfies `<1.0.0`");
+		assert!(matches!(err, LoadOrderError::Incompatible(..)));
+	}
+
+	/// A dependency whose version constraint the present mount doesn't
+	/// satisfy still orders correctly but surfaces a warning instead of
+	/// silently treating any present version as sufficient.
+	#[test]
+	fn version_constrained_dependency_warns_when_unsatisfied() {
+		let mut a = mount("a", &[], &[], &[]);
+		a.dependencies.push(PackageRef {
+			id: "foo".to_string(),
+			constraint: Some(VersionConstraint::AtLeast("2.0.0".to_string())),
+		});
+
+		let mut foo = mount("foo", &[], &[], &[]);
+		foo.version = Some("1.0.0".to_string());
+
+		let mounts = [a, foo];
+
+		let result =
+			Catalog::resolve_load_order(&mounts).expect("an unsatisfied constraint is non-fatal");
+		assert_eq!(result.warnings.len(), 1);
+	}
+
+	#[test]
+	fn gzip_stream_decoder_round_trips() {
+		use std::io::Write;
+
+		use super::decompress::{sniff, stream_decoder};
+
+		let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+		encoder.write_all(b"hello, decompressed world").unwrap();
+		let gzipped = encoder.finish().unwrap();
+
+		assert_eq!(sniff(&gzipped), Some(MountFormat::Gzip));
+
+		let mut decoder = stream_decoder(MountFormat::Gzip, gzipped.as_slice()).unwrap();
+		let mut out = Vec::new();
+		decoder.read_to_end(&mut out).unwrap();
+
+		assert_eq!(out, b"hello, decompressed world");
+	}
 }
@@ -1,9 +1,17 @@
 //! Details of Lithica's [Cranelift](cranelift)-based backend.
 
-use std::hash::BuildHasherDefault;
+pub(crate) mod host;
+pub(crate) mod jitdbg;
 
-use cranelift::codegen::ir;
+use std::{
+	hash::BuildHasherDefault,
+	sync::{Arc, Condvar, Mutex},
+};
+
+use cranelift::codegen::{control::ControlPlane, ir, isa::TargetIsa, settings};
 use cranelift_module::{FuncId, Module};
+use cranelift_object::{ObjectBuilder, ObjectModule};
+use rayon::prelude::*;
 use rustc_hash::FxHashMap;
 use util::pushvec::PushVec;
 
@@ -29,12 +37,28 @@ pub struct Compilation {
 
 /// [`Compilation::clif`] will only be `Some` if `emit_clif` is `true`.
 /// [`Compilation::disasm`] will only be `Some` if `disasm` is `true`.
+///
+/// If `debuginfo` is `true`, every compiled function also gets a GDB/LLDB-visible
+/// entry registered via [`jitdbg`]; see that module for details.
+///
+/// `host_fns` is consulted to resolve Lith's calls to engine-registered
+/// native functions (see [`host`]) into direct `FuncId` references, rather
+/// than dispatching through a hardcoded builtin-index enum.
 #[must_use]
-pub fn finalize(mut compiler: Compiler, emit_clif: bool, disasm: bool) -> Compilation {
+pub fn finalize(
+	mut compiler: Compiler,
+	host_fns: &host::HostRegistry,
+	emit_clif: bool,
+	disasm: bool,
+	debuginfo: bool,
+) -> Compilation {
 	assert!(!compiler.failed);
 	assert_eq!(compiler.stage, compile::Stage::CodeGen);
 
 	let mut module = compiler.module.take().unwrap();
+	let host_fn_ids = host_fns
+		.declare_all(&mut module)
+		.expect("host function declaration failed");
 	let ir = std::mem::take(&mut compiler.ir);
 	let fn_count = ir.len();
 
@@ -56,23 +80,46 @@ pub fn finalize(mut compiler: Compiler, emit_clif: bool, disasm: bool) -> Compil
 		None
 	};
 
+	let mut srcloc_map = debuginfo.then(|| {
+		FxHashMap::<FuncId, jitdbg::FnSrcLocs>::with_capacity_and_hasher(
+			fn_count,
+			BuildHasherDefault::default(),
+		)
+	});
+
 	define_functions(
 		&compiler,
 		&mut module,
 		ir,
 		clif_map.as_mut(),
 		disasm_map.as_mut(),
+		srcloc_map.as_mut(),
 	);
 
 	module
 		.finalize_definitions()
 		.expect("JIT definition finalization failed");
 
+	let function_rti = if let Some(srclocs) = srcloc_map {
+		srclocs
+			.into_iter()
+			.filter_map(|(id, fn_locs)| {
+				let ptr = module.get_finalized_function(id);
+				// SAFETY: `ptr` was just retrieved from this same `FuncId`,
+				// and `fn_locs.code_len` was recorded from the same compilation.
+				jitdbg::register(ptr, &fn_locs).map(|entry| (id, entry))
+			})
+			.collect()
+	} else {
+		FxHashMap::default()
+	};
+
 	Compilation {
 		runtime: Runtime {
-			_function_rti: FxHashMap::default(),
+			_function_rti: function_rti,
 			_data_rti: FxHashMap::default(),
 			_type_rti: FxHashMap::default(),
+			host_fns: host_fn_ids,
 			module,
 		},
 		clif: clif_map,
@@ -80,37 +127,256 @@ pub fn finalize(mut compiler: Compiler, emit_clif: bool, disasm: bool) -> Compil
 	}
 }
 
-fn define_functions(
+/// The complete set of possible compilation artifacts which can be emitted by [`finalize_object`].
+#[derive(Debug)]
+pub struct ObjectCompilation {
+	/// A relocatable object file (ELF/Mach-O/COFF, depending on the target ISA
+	/// passed to [`finalize_object`]), ready to be linked into a shared object.
+	pub object: Vec<u8>,
+	/// Pretty-printed Cranelift Intermediate Format.
+	///
+	/// This is a middle stage between Lith ASTs and machine code; LithC interprets
+	/// this as it is generated to perform compile-time evaluation.
+	pub clif: Option<FxHashMap<FuncId, String>>,
+	/// Pretty-printed Cranelift VCode,
+	/// which resembles the final generated machine instructions.
+	pub disasm: Option<FxHashMap<FuncId, String>>,
+}
+
+/// The ahead-of-time counterpart to [`finalize`].
+///
+/// Rather than JIT-ing `compiler`'s IR into process memory, this emits a
+/// relocatable native object, so that a mod's compiled Lith can be cached to
+/// disk and `dlopen`'d on a subsequent run instead of being recompiled.
+///
+/// `host_fns` is consulted the same way as in [`finalize`]: every registered
+/// host function is declared on the `ObjectModule` as an imported symbol
+/// before any Lith function is compiled, so a call to one resolves to a
+/// `FuncId` the linker can later bind against the engine's exported native
+/// symbol instead of being left undeclared.
+///
+/// [`ObjectCompilation::clif`] will only be `Some` if `emit_clif` is `true`.
+/// [`ObjectCompilation::disasm`] will only be `Some` if `disasm` is `true`.
+#[must_use]
+pub fn finalize_object(
+	mut compiler: Compiler,
+	host_fns: &host::HostRegistry,
+	target_isa: Arc<dyn TargetIsa>,
+	flags: settings::Flags,
+	emit_clif: bool,
+	disasm: bool,
+) -> ObjectCompilation {
+	assert!(!compiler.failed);
+	assert_eq!(compiler.stage, compile::Stage::CodeGen);
+	debug_assert_eq!(target_isa.flags(), &flags);
+
+	let builder = ObjectBuilder::new(
+		target_isa,
+		"lith_module".to_string(),
+		cranelift_module::default_libcall_names(),
+	)
+	.expect("object module builder creation failed");
+
+	let mut module = ObjectModule::new(builder);
+	// The returned `FuncId`s aren't needed here the way `finalize` threads
+	// them into a `Runtime`: once the emitted object is loaded back, whatever
+	// loads it declares these same host functions again against its own
+	// fresh module. All that matters at this point is that they're declared
+	// *before* `define_functions` compiles IR that calls them.
+	host_fns
+		.declare_all(&mut module)
+		.expect("host function declaration failed");
+	let ir = std::mem::take(&mut compiler.ir);
+	let fn_count = ir.len();
+
+	let mut clif_map = if emit_clif {
+		Some(FxHashMap::with_capacity_and_hasher(
+			fn_count,
+			BuildHasherDefault::default(),
+		))
+	} else {
+		None
+	};
+
+	let mut disasm_map = if disasm {
+		Some(FxHashMap::with_capacity_and_hasher(
+			fn_count,
+			BuildHasherDefault::default(),
+		))
+	} else {
+		None
+	};
+
+	define_functions(
+		&compiler,
+		&mut module,
+		ir,
+		clif_map.as_mut(),
+		disasm_map.as_mut(),
+		None,
+	);
+
+	let product = module.finish();
+	let object = product.emit().expect("native object emission failed");
+
+	ObjectCompilation {
+		object,
+		clif: clif_map,
+		disasm: disasm_map,
+	}
+}
+
+/// A crude counting semaphore.
+///
+/// Each [`cranelift::codegen::Context`] used by a parallel compile worker in
+/// [`define_functions`] can retain a surprising amount of memory for the
+/// duration of a single function's codegen, so [`define_functions`] caps the
+/// number of in-flight compiles with this. It is given one permit per thread
+/// of the global thread pool (see
+/// [`crate::thread_pool_init`](../../viletech/fn.thread_pool_init.html)), so
+/// it admits as many compiles at once as that pool can already run.
+struct ConcurrencyLimiter {
+	available: Mutex<usize>,
+	unblocked: Condvar,
+}
+
+impl ConcurrencyLimiter {
+	fn new(permits: usize) -> Self {
+		Self {
+			available: Mutex::new(permits.max(1)),
+			unblocked: Condvar::new(),
+		}
+	}
+
+	fn acquire(&self) {
+		let mut avail = self.available.lock().unwrap();
+
+		while *avail == 0 {
+			avail = self.unblocked.wait(avail).unwrap();
+		}
+
+		*avail -= 1;
+	}
+
+	fn release(&self) {
+		*self.available.lock().unwrap() += 1;
+		self.unblocked.notify_one();
+	}
+}
+
+/// One function's output from the parallel half of [`define_functions`],
+/// ready to be handed to [`Module::define_function_bytes`] on the thread
+/// that owns `module`.
+struct CompiledFn {
+	id: FuncId,
+	func: ir::Function,
+	clif: Option<String>,
+	disasm: Option<String>,
+	alignment: u64,
+	bytes: Vec<u8>,
+	relocs: Vec<cranelift::codegen::FinalizedMachReloc>,
+	srclocs: Option<jitdbg::FnSrcLocs>,
+}
+
+fn define_functions<M: Module>(
 	_: &Compiler,
-	module: &mut JitModule,
+	module: &mut M,
 	ir: PushVec<(FuncId, ir::Function)>,
 	mut clif_map: Option<&mut FxHashMap<FuncId, String>>,
 	mut disasm_map: Option<&mut FxHashMap<FuncId, String>>,
+	mut srcloc_map: Option<&mut FxHashMap<FuncId, jitdbg::FnSrcLocs>>,
 ) {
-	let mut ctx = module.make_context();
+	let isa = module.isa();
+	let want_clif = clif_map.is_some();
 	let want_disasm = disasm_map.is_some();
+	let want_srclocs = srcloc_map.is_some();
+	// One permit per thread of `rayon`'s global pool (see `thread_pool_init`),
+	// so this admits as many compiles at once as the pool already runs.
+	let limiter = ConcurrencyLimiter::new(rayon::current_num_threads());
+
+	let compiled: Vec<CompiledFn> = ir
+		.into_iter()
+		.collect::<Vec<_>>()
+		.into_par_iter()
+		.map(|(id, func)| {
+			limiter.acquire();
 
-	for (id, clif) in ir.into_iter() {
-		ctx.set_disasm(want_disasm);
+			let mut ctx = cranelift::codegen::Context::for_function(func.clone());
+			ctx.set_disasm(want_disasm);
 
+			let clif = want_clif.then(|| {
+				let mut buf = String::new();
+				cranelift::codegen::write::write_function(&mut buf, &func).unwrap();
+				buf
+			});
+
+			ctx.compile(isa, &mut ControlPlane::default())
+				.expect("Lith function codegen failed");
+
+			let comp_code = ctx.compiled_code().unwrap();
+			let disasm = comp_code.vcode.clone();
+			let bytes = comp_code.buffer.data().to_vec();
+			let relocs = comp_code.buffer.relocs().to_vec();
+			let alignment = comp_code.buffer.alignment as u64;
+
+			// Every instruction in `func` that was lowered from a Lith AST node
+			// carries the span of that node as its `SourceLoc` (see the frontend's
+			// lowering pass); the GDB JIT interface wants these paired with the
+			// code offset they landed at so it can map PCs back to Lith source.
+			let srclocs = want_srclocs.then(|| jitdbg::FnSrcLocs {
+				code_len: bytes.len(),
+				entries: comp_code
+					.buffer
+					.get_srclocs_sorted()
+					.iter()
+					.map(|mlr| (mlr.start, mlr.loc))
+					.collect(),
+			});
+
+			limiter.release();
+
+			CompiledFn {
+				id,
+				func,
+				clif,
+				disasm,
+				alignment,
+				bytes,
+				relocs,
+				srclocs,
+			}
+		})
+		.collect();
+
+	// `module` is not `Sync`, so registration of the already-compiled code
+	// has to happen back on this thread.
+	for compiled_fn in compiled {
 		if let Some(m) = clif_map.as_mut() {
-			let mut buf = String::new();
-			cranelift::codegen::write::write_function(&mut buf, &clif).unwrap();
-			m.insert(id, buf);
+			if let Some(clif) = compiled_fn.clif {
+				m.insert(compiled_fn.id, clif);
+			}
 		}
 
-		ctx.func = clif;
-
 		module
-			.define_function(id, &mut ctx)
+			.define_function_bytes(
+				compiled_fn.id,
+				&compiled_fn.func,
+				compiled_fn.alignment,
+				&compiled_fn.bytes,
+				&compiled_fn.relocs,
+			)
 			.expect("JIT function definition failed");
 
 		if let Some(m) = disasm_map.as_mut() {
-			let comp_code = ctx.compiled_code().unwrap();
-			let vcode = comp_code.vcode.as_ref().unwrap();
-			m.insert(id, vcode.clone());
+			if let Some(disasm) = compiled_fn.disasm {
+				m.insert(compiled_fn.id, disasm);
+			}
 		}
 
-		module.clear_context(&mut ctx);
+		if let Some(m) = srcloc_map.as_mut() {
+			if let Some(srclocs) = compiled_fn.srclocs {
+				m.insert(compiled_fn.id, srclocs);
+			}
+		}
 	}
 }
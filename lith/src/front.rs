@@ -3,9 +3,13 @@
 //! [lexing]: crate::syn
 //! [parsing]: crate::parse
 
+pub(crate) mod const_eval;
 pub(crate) mod decl;
 
-use doomfront::rowan::{ast::AstNode, TextRange};
+use doomfront::{
+	ptr::SyntaxNodePtr,
+	rowan::{ast::AstNode, TextRange},
+};
 
 use crate::{
 	ast,
@@ -34,7 +38,11 @@ impl FrontendContext<'_> {
 		let location = Location {
 			lib_ix: self.lib_ix,
 			file_ix: self.file_ix,
-			span: node.text_range(),
+			// A bare `TextRange` goes stale the moment this file is edited
+			// and reparsed; a `SyntaxNodePtr` can be re-resolved against
+			// whatever tree is current when a diagnostic needs to re-anchor
+			// to it. See `resolve_node` below.
+			ptr: SyntaxNodePtr::new(node),
 		};
 
 		let name = self.names.intern(name);
@@ -59,6 +67,31 @@ impl FrontendContext<'_> {
 		Ok(sym_ptr)
 	}
 
+	/// Folds a `const`/`comptime` Lith function down to a concrete value via
+	/// [`const_eval::eval`], given its already-lowered IR and checked
+	/// argument values. Meant to be called by whichever pass resolves a
+	/// [`ast::SymConst`]'s initializer (that pass's expression-to-IR
+	/// lowering isn't in this checkout yet, so there is no such caller
+	/// here); this method is the seam it should call through, rather than
+	/// reaching into [`const_eval`] directly.
+	// TODO: remove this `allow` once the `SymConst`-initializer lowering
+	// pass lands and starts calling through here.
+	#[allow(dead_code)]
+	fn eval_const(
+		&self,
+		node: &SyntaxNode,
+		func: &cranelift::codegen::ir::Function,
+		args: &[cranelift_interpreter::interpreter::DataValue],
+	) -> Result<cranelift_interpreter::interpreter::DataValue, const_eval::EvalError> {
+		let location = Location {
+			lib_ix: self.lib_ix,
+			file_ix: self.file_ix,
+			ptr: SyntaxNodePtr::new(node),
+		};
+
+		const_eval::eval(func, args, const_eval::EvalContext { location })
+	}
+
 	#[must_use]
 	fn resolve_file(&self, sym: &Symbol) -> (&String, &ParseTree) {
 		let prev_lib = &self.sources[sym.location.lib_ix as usize];
@@ -71,6 +104,18 @@ impl FrontendContext<'_> {
 
 		(path, ptree)
 	}
+
+	/// Re-resolves `sym`'s location against its file's current parse tree,
+	/// surviving any incremental reparse that happened between when `sym`
+	/// was declared and when this is called.
+	///
+	/// # Panics
+	/// Panics under the same conditions as [`SyntaxNodePtr::to_node`].
+	#[must_use]
+	fn resolve_node(&self, sym: &Symbol) -> SyntaxNode {
+		let (_, ptree) = self.resolve_file(sym);
+		sym.location.ptr.to_node(&ptree.syntax())
+	}
 }
 
 impl std::ops::Deref for FrontendContext<'_> {
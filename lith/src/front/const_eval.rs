@@ -0,0 +1,143 @@
+//! Compile-time evaluation of `const`/`comptime` Lith functions.
+//!
+//! This is the "LithC" referred to by [`crate::back::Compilation::clif`]'s
+//! doc comment: rather than JIT-ing or AOT-compiling a `const` function,
+//! [`eval`] runs its already-lowered [`ir::Function`] through
+//! [`cranelift_interpreter`]'s [`Interpreter`], folding it down to a concrete
+//! [`DataValue`] at compile time. Interpreter [`Trap`]s are mapped back to
+//! Lith spans here rather than bubbled up as panics, so a bad `const`
+//! expression fails compilation with a pointed diagnostic instead of
+//! miscompiling or crashing the compiler.
+
+use cranelift::codegen::ir::{self, TrapCode};
+use cranelift_interpreter::{
+	environment::FunctionStore,
+	interpreter::{Interpreter, InterpreterError, InterpreterState},
+	step::ControlFlow,
+};
+use doomfront::rowan::TextRange;
+
+use crate::data::Location;
+
+/// Everything [`eval`] needs to turn an interpreter trap or mismatch back
+/// into a diagnostic pointed at the original Lith source.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EvalContext {
+	/// The location of the indexing/arithmetic/call expression currently
+	/// being folded; attached to whichever [`EvalError`] results.
+	pub(crate) location: Location,
+}
+
+/// A compile-time evaluation failure, reported in place of a miscompile or
+/// an interpreter panic.
+#[derive(Debug, Clone)]
+pub(crate) enum EvalError {
+	/// A `const` function was called with the wrong number of arguments,
+	/// or an argument's type did not match the corresponding parameter.
+	/// Checked before interpretation starts, so no CLIF is ever stepped.
+	ArityOrTypeMismatch {
+		location: Location,
+		expected: usize,
+		got: usize,
+	},
+	/// An array/slice index expression evaluated to something outside
+	/// `0..size` (interpreter trap code [`TrapCode::HeapOutOfBounds`]).
+	///
+	/// `index`/`size` are `None` rather than the real offending values: the
+	/// interpreter's [`Trap`] carries only a [`TrapCode`], no operand data,
+	/// so there is nothing here to recover them from. A future pass that
+	/// wants the concrete numbers would have to re-walk `func`'s heap
+	/// access instructions itself.
+	IndexOutOfRange {
+		location: Location,
+		index: Option<i64>,
+		size: Option<u64>,
+	},
+	/// A signed arithmetic operation overflowed
+	/// (interpreter trap code [`TrapCode::IntegerOverflow`]).
+	IntegerOverflow { location: Location },
+	/// A division or remainder operation's divisor was zero
+	/// (interpreter trap code [`TrapCode::IntegerDivisionByZero`]).
+	DivisionByZero { location: Location },
+	/// Any other trap the interpreter raised; Lith has no specific
+	/// diagnostic for it, so the raw trap code is preserved for reporting.
+	Other { location: Location, trap: TrapCode },
+}
+
+impl EvalError {
+	#[must_use]
+	pub(crate) fn span(&self) -> TextRange {
+		match self {
+			Self::ArityOrTypeMismatch { location, .. }
+			| Self::IndexOutOfRange { location, .. }
+			| Self::IntegerOverflow { location }
+			| Self::DivisionByZero { location }
+			| Self::Other { location, .. } => location.ptr.range(),
+		}
+	}
+}
+
+/// Interprets `func` to a single concrete result, given already-checked
+/// `args`.
+///
+/// `ctx.location` is used to locate any [`EvalError`] this produces; callers
+/// fold one expression at a time, so a single location for the whole call is
+/// sufficient (a finer-grained per-instruction span would require threading
+/// [`ir::SourceLoc`] through the interpreter, which it does not expose).
+pub(crate) fn eval(
+	func: &ir::Function,
+	args: &[cranelift_interpreter::interpreter::DataValue],
+	ctx: EvalContext,
+) -> Result<cranelift_interpreter::interpreter::DataValue, EvalError> {
+	let params = func.signature.params.len();
+
+	if args.len() != params {
+		return Err(EvalError::ArityOrTypeMismatch {
+			location: ctx.location,
+			expected: params,
+			got: args.len(),
+		});
+	}
+
+	for (arg, param) in args.iter().zip(&func.signature.params) {
+		if arg.ty() != param.value_type {
+			return Err(EvalError::ArityOrTypeMismatch {
+				location: ctx.location,
+				expected: params,
+				got: args.len(),
+			});
+		}
+	}
+
+	let mut env = FunctionStore::default();
+	env.add(func.name.to_string(), func);
+	let mut state = InterpreterState::default().with_function_store(env);
+	let interp = Interpreter::new(&mut state);
+
+	match interp.call_by_name(&func.name.to_string(), args) {
+		Ok(ControlFlow::Return(mut results)) => Ok(results.remove(0)),
+		Ok(_) => unreachable!("a const-evaluable function must terminate in a return"),
+		Err(InterpreterError::Trap(trap)) => Err(trap_to_eval_error(trap, ctx)),
+		Err(other) => panic!("unexpected interpreter error in const-eval: {other}"),
+	}
+}
+
+fn trap_to_eval_error(trap: TrapCode, ctx: EvalContext) -> EvalError {
+	match trap {
+		TrapCode::HeapOutOfBounds => EvalError::IndexOutOfRange {
+			location: ctx.location,
+			index: None,
+			size: None,
+		},
+		TrapCode::IntegerOverflow => EvalError::IntegerOverflow {
+			location: ctx.location,
+		},
+		TrapCode::IntegerDivisionByZero => EvalError::DivisionByZero {
+			location: ctx.location,
+		},
+		other => EvalError::Other {
+			location: ctx.location,
+			trap: other,
+		},
+	}
+}
@@ -0,0 +1,95 @@
+//! A registry of host-bound native functions, addressable by name.
+//!
+//! This generalizes ACS's `NamedExecute`/`NamedExecuteWithResult` pattern
+//! (see `engine::acs::funcs::Function`) into a first-class binding
+//! mechanism: rather than growing a frozen enum of builtin indices every
+//! time the engine wants to expose something new to scripts, engine code
+//! (or a mod's Rust plugin) registers a Rust callback under an interned
+//! name and a fixed [`HostSig`], and the Cranelift backend resolves Lith
+//! calls to that name into a direct [`FuncId`] at link time.
+
+use cranelift::codegen::ir;
+use cranelift_module::{FuncId, Linkage, Module, ModuleError};
+use rustc_hash::FxHashMap;
+
+/// A host function's signature, as far as Lith's linker needs to know it;
+/// the actual Rust-side `extern "C" fn` signature behind [`HostFn::ptr`] is
+/// the registering code's responsibility to get right.
+#[derive(Debug, Clone)]
+pub struct HostSig {
+	pub params: Vec<ir::Type>,
+	pub returns: Vec<ir::Type>,
+}
+
+/// One registered host function: its signature, and the address of the
+/// native code implementing it.
+#[derive(Debug, Clone, Copy)]
+pub struct HostFn {
+	pub sig_params: usize,
+	pub ptr: *const u8,
+}
+
+// SAFETY: `ptr` is only ever read, and always points to executable code
+// owned by the engine for the process' whole lifetime.
+unsafe impl Send for HostFn {}
+unsafe impl Sync for HostFn {}
+
+/// Host functions registered under interned names, not yet linked into any
+/// particular compilation's `JitModule`.
+///
+/// Engine startup populates one of these (e.g. binding `"engine.spawn_actor"`
+/// to a Rust closure wrapped in an `extern "C" fn`), and every `Compiler`
+/// consults it while resolving Lith's `extern` function declarations,
+/// instead of dispatching through a hardcoded builtin-index enum.
+#[derive(Debug, Default)]
+pub struct HostRegistry {
+	fns: FxHashMap<Box<str>, (HostSig, HostFn)>,
+}
+
+impl HostRegistry {
+	/// Binds `name` to `ptr`, a function matching `sig`.
+	///
+	/// # Panics
+	/// Panics if `name` is already bound; rebinding is not supported since
+	/// a mod's compiled Lith may already hold a direct reference to the
+	/// previous binding's [`FuncId`].
+	pub fn register(&mut self, name: impl Into<Box<str>>, sig: HostSig, ptr: *const u8) {
+		let name = name.into();
+		let host_fn = HostFn {
+			sig_params: sig.params.len(),
+			ptr,
+		};
+
+		let prev = self.fns.insert(name.clone(), (sig, host_fn));
+		assert!(prev.is_none(), "host function `{name}` registered twice");
+	}
+
+	#[must_use]
+	pub fn get(&self, name: &str) -> Option<&(HostSig, HostFn)> {
+		self.fns.get(name)
+	}
+
+	/// Declares every registered host function as an imported symbol on
+	/// `module`, returning their [`FuncId`]s keyed by name so the frontend
+	/// can resolve calls to them directly rather than through string dispatch.
+	pub fn declare_all<M: Module>(
+		&self,
+		module: &mut M,
+	) -> Result<FxHashMap<Box<str>, FuncId>, ModuleError> {
+		self.fns
+			.iter()
+			.map(|(name, (sig, _))| {
+				let mut signature = module.make_signature();
+				signature
+					.params
+					.extend(sig.params.iter().map(|ty| ir::AbiParam::new(*ty)));
+				signature
+					.returns
+					.extend(sig.returns.iter().map(|ty| ir::AbiParam::new(*ty)));
+
+				let id = module.declare_function(name, Linkage::Import, &signature)?;
+				Ok((name.clone(), id))
+			})
+			.collect()
+	}
+}
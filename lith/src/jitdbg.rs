@@ -0,0 +1,287 @@
+//! GDB/LLDB-visible debug info for JIT-compiled Lith functions.
+//!
+//! This implements the de-facto standard "JIT Compilation Interface for
+//! Debuggers" that GDB polls (and which LLDB understands via the same
+//! protocol): a process-wide linked list of [`JitCodeEntry`] nodes rooted at
+//! `__jit_debug_descriptor`, appended to and then announced by calling the
+//! `__jit_debug_register_code` breakpoint stub. rustc's Cranelift backend
+//! (`rustc_codegen_cranelift`) registers JIT frames the same way.
+
+use std::{
+	pin::Pin,
+	sync::atomic::{AtomicU32, Ordering},
+};
+
+use cranelift::codegen::ir;
+use gimli::write::{Address, EndianVec, LineProgram, LineString, Sections, Unit, UnitEntryId};
+
+/// One instruction's lowered source span, paired with the byte offset into
+/// the compiled function's machine code at which it begins.
+pub(crate) type SrcLocEntry = (u32, ir::SourceLoc);
+
+/// Everything needed to build a `.debug_line` program for one compiled
+/// function: the length of its machine code and the source locations
+/// recorded for it during lowering, in ascending code-offset order.
+#[derive(Debug, Clone)]
+pub(crate) struct FnSrcLocs {
+	pub(crate) code_len: usize,
+	pub(crate) entries: Vec<SrcLocEntry>,
+}
+
+/// A registered entry in the GDB JIT interface's linked list.
+///
+/// Unregisters itself (removing its node and re-announcing the list) when
+/// dropped, which is why [`crate::runtime::Runtime`] keeps these alive for as
+/// long as the JIT code they describe remains mapped.
+#[derive(Debug)]
+pub(crate) struct JitCodeEntry {
+	raw: *mut sys::jit_code_entry,
+	// Keeps the ELF bytes referenced by `raw.symfile_addr` alive.
+	_object: Pin<Box<[u8]>>,
+}
+
+// SAFETY: the entry behind `raw` is owned by this type alone, and its
+// `symfile_addr` only ever points into `_object`, which this type uniquely owns.
+unsafe impl Send for JitCodeEntry {}
+
+impl Drop for JitCodeEntry {
+	fn drop(&mut self) {
+		// SAFETY: `raw` was produced by `sys::push` and has not been unlinked yet.
+		unsafe {
+			sys::unregister(self.raw);
+		}
+	}
+}
+
+/// Builds a minimal in-memory ELF object carrying a `.debug_line` program
+/// mapping `ptr..ptr + locs.code_len` back to Lith source spans, and
+/// registers it with the GDB JIT interface.
+///
+/// Returns `None` if `locs` has no source locations to encode (e.g. the
+/// function lowered to a single call with no attributable span).
+#[must_use]
+pub(crate) fn register(ptr: *const u8, locs: &FnSrcLocs) -> Option<JitCodeEntry> {
+	if locs.entries.is_empty() {
+		return None;
+	}
+
+	let object = build_debug_object(ptr, locs);
+	let mut object = Pin::new(object.into_boxed_slice());
+
+	// SAFETY: `object` outlives `raw` for the lifetime of the returned
+	// `JitCodeEntry`, and is never moved out of the `Pin`.
+	let raw = unsafe { sys::push(object.as_mut_ptr().cast(), object.len()) };
+
+	Some(JitCodeEntry {
+		raw,
+		_object: object,
+	})
+}
+
+/// Lith does not (yet) track source file names per-function in a form
+/// reachable from this module, so every entry is attributed to this
+/// placeholder compilation unit name; the line/column encoded in each
+/// [`ir::SourceLoc`] is still faithful.
+const COMP_UNIT_NAME: &str = "<lith>";
+
+fn build_debug_object(ptr: *const u8, locs: &FnSrcLocs) -> Vec<u8> {
+	let encoding = gimli::Encoding {
+		format: gimli::Format::Dwarf32,
+		version: 4,
+		address_size: 8,
+	};
+
+	let mut dwarf = gimli::write::DwarfUnit::new(encoding);
+	let line_strings = &mut dwarf.line_strings;
+
+	let comp_name = LineString::new(COMP_UNIT_NAME.as_bytes(), encoding, line_strings);
+	let mut program = LineProgram::new(
+		encoding,
+		gimli::LineEncoding::default(),
+		gimli::write::LineString::new("".as_bytes(), encoding, line_strings),
+		comp_name,
+		None,
+	);
+
+	let file = program.default_file_index();
+	program.begin_sequence(Some(Address::Constant(ptr as u64)));
+
+	for &(offset, loc) in &locs.entries {
+		// `ir::SourceLoc` is an opaque cookie assigned during lowering; Lith's
+		// frontend packs `(line << 12) | column` into it so it survives
+		// codegen without a side table.
+		let bits = loc.bits();
+		let line = (bits >> 12) as u64;
+		let column = (bits & 0xfff) as u64;
+		program.row().address_offset = offset as u64;
+		program.row().line = line;
+		program.row().column = column;
+		program.row().file = file;
+		program.generate_row();
+	}
+
+	program.end_sequence(locs.code_len as u64);
+	dwarf.unit.line_program = program;
+
+	let root = dwarf.unit.root();
+	add_subprogram_die(&mut dwarf.unit, root, ptr, locs.code_len);
+
+	let mut sections = Sections::new(EndianVec::new(gimli::RunTimeEndian::default()));
+	dwarf.write(&mut sections).expect("DWARF section write failed");
+
+	emit_elf(ptr, locs.code_len, &sections)
+}
+
+fn add_subprogram_die(
+	unit: &mut Unit,
+	parent: UnitEntryId,
+	ptr: *const u8,
+	code_len: usize,
+) -> UnitEntryId {
+	let die_id = unit.add(parent, gimli::DW_TAG_subprogram);
+	let die = unit.get_mut(die_id);
+	die.set(
+		gimli::DW_AT_low_pc,
+		gimli::write::AttributeValue::Address(Address::Constant(ptr as u64)),
+	);
+	die.set(
+		gimli::DW_AT_high_pc,
+		gimli::write::AttributeValue::Udata(code_len as u64),
+	);
+	die_id
+}
+
+/// Wraps `sections` (and a synthetic symbol for `ptr`) in a
+/// relocatable-ish ELF the same way rustc's JIT debuginfo emitter does, so
+/// GDB's JIT reader (which only understands ELF/Mach-O/COFF object files,
+/// not bare DWARF) can parse it. The object is always x86-64 little-endian,
+/// whatever the host.
+fn emit_elf(
+	ptr: *const u8,
+	code_len: usize,
+	sections: &Sections<EndianVec<gimli::RunTimeEndian>>,
+) -> Vec<u8> {
+	use object::write::{Object, StandardSection, Symbol, SymbolSection};
+	use object::{Architecture, BinaryFormat, Endianness, SymbolFlags, SymbolKind, SymbolScope};
+
+	let mut obj = Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+
+	let debug_line = obj.add_section(vec![], b".debug_line".to_vec(), StandardSection::Data);
+	obj.set_section_data(debug_line, sections.debug_line.slice().to_vec(), 1);
+
+	obj.add_symbol(Symbol {
+		name: b"lith_jit_fn".to_vec(),
+		value: ptr as u64,
+		size: code_len as u64,
+		kind: SymbolKind::Text,
+		scope: SymbolScope::Linkage,
+		weak: false,
+		section: SymbolSection::Absolute,
+		flags: SymbolFlags::None,
+	});
+
+	obj.write().expect("in-memory ELF emission failed")
+}
+
+static NEXT_GENERATION: AtomicU32 = AtomicU32::new(1);
+
+/// Raw FFI surface mirroring the layout GDB's `jit-reader.h` / the LLVM JIT
+/// event listener expect. Kept separate from the safe wrapper above so the
+/// `unsafe` surface touching the global linked list is easy to audit.
+mod sys {
+	use super::*;
+
+	#[repr(C)]
+	pub(super) struct jit_code_entry {
+		pub next_entry: *mut jit_code_entry,
+		pub prev_entry: *mut jit_code_entry,
+		pub symfile_addr: *const u8,
+		pub symfile_size: u64,
+	}
+
+	#[repr(u32)]
+	enum JitActions {
+		NoAction = 0,
+		RegisterFn = 1,
+		UnregisterFn = 2,
+	}
+
+	#[repr(C)]
+	struct jit_descriptor {
+		version: u32,
+		action_flag: u32,
+		relevant_entry: *mut jit_code_entry,
+		first_entry: *mut jit_code_entry,
+	}
+
+	#[no_mangle]
+	static mut __jit_debug_descriptor: jit_descriptor = jit_descriptor {
+		version: 1,
+		action_flag: JitActions::NoAction as u32,
+		relevant_entry: std::ptr::null_mut(),
+		first_entry: std::ptr::null_mut(),
+	};
+
+	/// Guards every read/write of `__jit_debug_descriptor` and its linked
+	/// list. Concurrent compilation of multiple Lith modules means
+	/// [`push`]/[`unregister`] can race on this process-global mutable
+	/// state; `rustc_codegen_cranelift` guards the same GDB JIT interface
+	/// structure with a mutex for the same reason.
+	static JIT_DEBUG_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+	#[no_mangle]
+	#[inline(never)]
+	extern "C" fn __jit_debug_register_code() {
+		// Left empty: its only purpose is to be a stable breakpoint location
+		// for the debugger to trap on, per the GDB JIT interface contract.
+		std::hint::black_box(());
+	}
+
+	/// Allocates an entry, links it into the global list, and announces it.
+	///
+	/// # Safety
+	/// `symfile` must remain valid for as long as the returned entry is
+	/// registered (until [`unregister`] is called on it).
+	pub(super) unsafe fn push(symfile: *const u8, symfile_size: usize) -> *mut jit_code_entry {
+		let _guard = JIT_DEBUG_LOCK.lock().unwrap_or_else(|poison| poison.into_inner());
+
+		let entry = Box::into_raw(Box::new(jit_code_entry {
+			next_entry: __jit_debug_descriptor.first_entry,
+			prev_entry: std::ptr::null_mut(),
+			symfile_addr: symfile,
+			symfile_size: symfile_size as u64,
+		}));
+
+		if !__jit_debug_descriptor.first_entry.is_null() {
+			(*__jit_debug_descriptor.first_entry).prev_entry = entry;
+		}
+
+		__jit_debug_descriptor.first_entry = entry;
+		__jit_debug_descriptor.relevant_entry = entry;
+		__jit_debug_descriptor.action_flag = JitActions::RegisterFn as u32;
+		NEXT_GENERATION.fetch_add(1, Ordering::SeqCst);
+		__jit_debug_register_code();
+		entry
+	}
+
+	/// # Safety
+	/// `entry` must have been returned by [`push`] and not yet unregistered.
+	pub(super) unsafe fn unregister(entry: *mut jit_code_entry) {
+		let _guard = JIT_DEBUG_LOCK.lock().unwrap_or_else(|poison| poison.into_inner());
+
+		if !(*entry).prev_entry.is_null() {
+			(*(*entry).prev_entry).next_entry = (*entry).next_entry;
+		} else {
+			__jit_debug_descriptor.first_entry = (*entry).next_entry;
+		}
+
+		if !(*entry).next_entry.is_null() {
+			(*(*entry).next_entry).prev_entry = (*entry).prev_entry;
+		}
+
+		__jit_debug_descriptor.relevant_entry = entry;
+		__jit_debug_descriptor.action_flag = JitActions::UnregisterFn as u32;
+		__jit_debug_register_code();
+		drop(Box::from_raw(entry));
+	}
+}
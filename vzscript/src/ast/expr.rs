@@ -1,7 +1,7 @@
 //! AST nodes for representing expressions.
 
 use doomfront::{
-	rowan::{ast::AstNode, Language},
+	rowan::{ast::AstNode, GreenNode, Language, NodeOrToken, WalkEvent},
 	simple_astnode, AstError, AstResult,
 };
 
@@ -34,6 +34,89 @@ pub enum Expr {
 	While(WhileExpr),
 }
 
+/// The precedence reported by [`PrefixOp::precedence`] and by every atomic
+/// expression (an [`IdentExpr`], [`Literal`], [`CallExpr`], [`IndexExpr`], or
+/// [`FieldExpr`]); see [`Expr::needs_parens_in`].
+const MAX_PRECEDENCE: u8 = 10;
+
+impl Expr {
+	/// This expression's own top-level precedence: a [`BinExpr`]'s
+	/// [`BinOp::precedence`], a [`PrefixExpr`]'s [`PrefixOp::precedence`], or
+	/// [`MAX_PRECEDENCE`] for anything else (including a [`GroupExpr`],
+	/// which is already as tightly-bound as an expression can be).
+	#[must_use]
+	fn precedence(&self) -> u8 {
+		match self {
+			Self::Binary(e) => e.operator().1.precedence(),
+			Self::Prefix(e) => e.operator().1.precedence(),
+			_ => MAX_PRECEDENCE,
+		}
+	}
+
+	/// Returns `true` iff `self` sits as a direct operand of `parent` and
+	/// eliding a [`GroupExpr`] around `self` would silently reassociate the
+	/// expression: `self`'s top-level operator binds looser than `parent`'s,
+	/// or the two bind with equal precedence on the side that `parent`'s
+	/// [`BinOp::associativity`] forbids (a right operand under a
+	/// left-associative operator, or a left operand under a
+	/// right-associative one). Anything other than a [`BinExpr`] `parent`
+	/// never forces parentheses on its children through this check.
+	#[must_use]
+	pub fn needs_parens_in(&self, parent: &Expr) -> bool {
+		let Self::Binary(parent_bin) = parent else {
+			return false;
+		};
+
+		let (_, parent_op) = parent_bin.operator();
+		let parent_prec = parent_op.precedence();
+		let self_prec = self.precedence();
+
+		match self_prec.cmp(&parent_prec) {
+			std::cmp::Ordering::Less => true,
+			std::cmp::Ordering::Greater => false,
+			std::cmp::Ordering::Equal => {
+				let is_right_operand = parent_bin
+					.right()
+					.is_ok_and(|rhs| rhs.syntax() == self.syntax());
+
+				match parent_op.associativity() {
+					Assoc::Left => is_right_operand,
+					Assoc::Right => !is_right_operand,
+					Assoc::None => true,
+				}
+			}
+		}
+	}
+
+	/// Yields every node in `self`'s subtree (including `self`) that casts
+	/// to an `Expr`, in preorder.
+	pub fn descendant_exprs(&self) -> impl Iterator<Item = Expr> {
+		self.syntax().descendants().filter_map(Expr::cast)
+	}
+
+	/// Walks `self`'s subtree in preorder, invoking `visit` with an
+	/// [`Enter`](WalkEvent::Enter)/[`Leave`](WalkEvent::Leave) event for
+	/// every node (including `self`) that casts to an `Expr`. Saves
+	/// downstream passes (e.g. collecting every [`IdentExpr`] reference, or
+	/// every [`CallExpr`] callee) from re-implementing child-kind matching.
+	pub fn walk(&self, visit: &mut dyn FnMut(WalkEvent<Expr>)) {
+		for event in self.syntax().preorder() {
+			match event {
+				WalkEvent::Enter(node) => {
+					if let Some(expr) = Expr::cast(node) {
+						visit(WalkEvent::Enter(expr));
+					}
+				}
+				WalkEvent::Leave(node) => {
+					if let Some(expr) = Expr::cast(node) {
+						visit(WalkEvent::Leave(expr));
+					}
+				}
+			}
+		}
+	}
+}
+
 impl AstNode for Expr {
 	type Language = Syn;
 
@@ -123,6 +206,13 @@ pub struct ArrayExpr(SyntaxNode);
 
 simple_astnode!(Syn, ArrayExpr, Syn::ArrayExpr);
 
+impl ArrayExpr {
+	/// Yields each element in this array literal.
+	pub fn elements(&self) -> impl Iterator<Item = Expr> {
+		self.0.children().filter_map(Expr::cast)
+	}
+}
+
 // Binary //////////////////////////////////////////////////////////////////////
 
 /// Wraps a node tagged [`Syn::BinExpr`].
@@ -140,6 +230,173 @@ impl BinExpr {
 	pub fn right(&self) -> AstResult<Expr> {
 		Expr::cast(self.0.children().nth(1).ok_or(AstError::Missing)?).ok_or(AstError::Incorrect)
 	}
+
+	/// The returned token is whichever direct token child of this node maps
+	/// to a [`BinOp`] (see [`bin_op_from_syn`]) — i.e. not whitespace, a
+	/// comment, or any other trivia sitting between the operands and the
+	/// operator itself. Don't take the first token child unconditionally:
+	/// this crate's trees carry trivia as ordinary sibling tokens, so for
+	/// e.g. `a + b` the first token child is the whitespace before `+`, not
+	/// the operator.
+	#[must_use]
+	pub fn operator(&self) -> (SyntaxToken, BinOp) {
+		self.0
+			.children_with_tokens()
+			.filter_map(NodeOrToken::into_token)
+			.find_map(|token| bin_op_from_syn(token.kind()).map(|op| (token, op)))
+			.expect("a well-formed `BinExpr` always has an operator token")
+	}
+}
+
+/// Maps a [`SyntaxToken`]'s kind to the [`BinOp`] it denotes, or `None` if
+/// `kind` is not a binary operator (e.g. trivia, or an operand's own token).
+/// See [`BinExpr::operator`].
+#[must_use]
+fn bin_op_from_syn(kind: Syn) -> Option<BinOp> {
+	Some(match kind {
+		Syn::Plus => BinOp::Add,
+		Syn::Minus => BinOp::Sub,
+		Syn::Asterisk => BinOp::Mul,
+		Syn::Slash => BinOp::Div,
+		Syn::Percent => BinOp::Rem,
+		Syn::Eq2 => BinOp::Eq,
+		Syn::BangEq => BinOp::NotEq,
+		Syn::AngleL => BinOp::Lt,
+		Syn::AngleR => BinOp::Gt,
+		Syn::AngleLEq => BinOp::LtEq,
+		Syn::AngleREq => BinOp::GtEq,
+		Syn::Ampersand2 => BinOp::LogicAnd,
+		Syn::Pipe2 => BinOp::LogicOr,
+		Syn::Ampersand => BinOp::BitAnd,
+		Syn::Pipe => BinOp::BitOr,
+		Syn::Caret => BinOp::BitXor,
+		Syn::AngleL2 => BinOp::Shl,
+		Syn::AngleR2 => BinOp::Shr,
+		Syn::Eq => BinOp::Assign,
+		Syn::PlusEq => BinOp::AddAssign,
+		Syn::MinusEq => BinOp::SubAssign,
+		Syn::AsteriskEq => BinOp::MulAssign,
+		Syn::SlashEq => BinOp::DivAssign,
+		Syn::PercentEq => BinOp::RemAssign,
+		Syn::AmpersandEq => BinOp::AndAssign,
+		Syn::PipeEq => BinOp::OrAssign,
+		Syn::CaretEq => BinOp::XorAssign,
+		Syn::AngleL2Eq => BinOp::ShlAssign,
+		Syn::AngleR2Eq => BinOp::ShrAssign,
+		_ => return None,
+	})
+}
+
+/// See [`BinExpr::operator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+	// Arithmetic //////////////////////////////////////////////////////////
+	Add,
+	Sub,
+	Mul,
+	Div,
+	Rem,
+	// Comparison //////////////////////////////////////////////////////////
+	Eq,
+	NotEq,
+	Lt,
+	Gt,
+	LtEq,
+	GtEq,
+	// Logical /////////////////////////////////////////////////////////////
+	LogicAnd,
+	LogicOr,
+	// Bitwise /////////////////////////////////////////////////////////////
+	BitAnd,
+	BitOr,
+	BitXor,
+	Shl,
+	Shr,
+	// Assignment //////////////////////////////////////////////////////////
+	Assign,
+	AddAssign,
+	SubAssign,
+	MulAssign,
+	DivAssign,
+	RemAssign,
+	AndAssign,
+	OrAssign,
+	XorAssign,
+	ShlAssign,
+	ShrAssign,
+}
+
+impl BinOp {
+	/// Is this a plain or compound assignment operator?
+	#[must_use]
+	pub fn is_assignment(self) -> bool {
+		matches!(
+			self,
+			Self::Assign
+				| Self::AddAssign | Self::SubAssign
+				| Self::MulAssign | Self::DivAssign
+				| Self::RemAssign | Self::AndAssign
+				| Self::OrAssign | Self::XorAssign
+				| Self::ShlAssign | Self::ShrAssign
+		)
+	}
+
+	/// Is this one of `==`, `!=`, `<`, `>`, `<=`, `>=`?
+	#[must_use]
+	pub fn is_comparison(self) -> bool {
+		matches!(
+			self,
+			Self::Eq | Self::NotEq | Self::Lt | Self::Gt | Self::LtEq | Self::GtEq
+		)
+	}
+
+	/// Higher binds tighter; see [`Expr::needs_parens_in`]. Assignment is
+	/// lowest, then (in ascending order) logical OR, logical AND, comparison,
+	/// bitwise OR, bitwise XOR, bitwise AND, shift, additive, multiplicative.
+	#[must_use]
+	pub fn precedence(self) -> u8 {
+		match self {
+			Self::Assign
+			| Self::AddAssign
+			| Self::SubAssign
+			| Self::MulAssign
+			| Self::DivAssign
+			| Self::RemAssign
+			| Self::AndAssign
+			| Self::OrAssign
+			| Self::XorAssign
+			| Self::ShlAssign
+			| Self::ShrAssign => 0,
+			Self::LogicOr => 1,
+			Self::LogicAnd => 2,
+			Self::Eq | Self::NotEq | Self::Lt | Self::Gt | Self::LtEq | Self::GtEq => 3,
+			Self::BitOr => 4,
+			Self::BitXor => 5,
+			Self::BitAnd => 6,
+			Self::Shl | Self::Shr => 7,
+			Self::Add | Self::Sub => 8,
+			Self::Mul | Self::Div | Self::Rem => 9,
+		}
+	}
+
+	/// The side on which two operators of equal [`Self::precedence`] group
+	/// together without parentheses.
+	#[must_use]
+	pub fn associativity(self) -> Assoc {
+		if self.is_assignment() {
+			Assoc::Right
+		} else {
+			Assoc::Left
+		}
+	}
+}
+
+/// See [`BinOp::associativity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+	Left,
+	Right,
+	None,
 }
 
 // Block ///////////////////////////////////////////////////////////////////////
@@ -150,6 +407,52 @@ pub struct BlockExpr(SyntaxNode);
 
 simple_astnode!(Syn, BlockExpr, Syn::BlockExpr);
 
+impl BlockExpr {
+	/// The returned token is always tagged [`Syn::BraceL`].
+	pub fn l_curly(&self) -> AstResult<SyntaxToken> {
+		self.0
+			.first_token()
+			.filter(|token| token.kind() == Syn::BraceL)
+			.ok_or(AstError::Missing)
+	}
+
+	/// The returned token is always tagged [`Syn::BraceR`].
+	pub fn r_curly(&self) -> AstResult<SyntaxToken> {
+		self.0
+			.last_token()
+			.filter(|token| token.kind() == Syn::BraceR)
+			.ok_or(AstError::Missing)
+	}
+
+	/// Every expression in this block other than a possible trailing
+	/// [`Self::tail_expr`].
+	pub fn statements(&self) -> impl Iterator<Item = Expr> {
+		let tail = self.tail_expr();
+
+		self.0.children().filter_map(Expr::cast).filter(move |expr| {
+			tail.as_ref()
+				.map_or(true, |t| t.syntax() != expr.syntax())
+		})
+	}
+
+	/// This block's final expression, if present and not followed by a
+	/// [`Syn::Semicolon`].
+	#[must_use]
+	pub fn tail_expr(&self) -> Option<Expr> {
+		let mut ret = None;
+
+		for child in self.0.children_with_tokens() {
+			match child {
+				NodeOrToken::Node(node) => ret = Expr::cast(node),
+				NodeOrToken::Token(token) if token.kind() == Syn::Semicolon => ret = None,
+				NodeOrToken::Token(_) => {}
+			}
+		}
+
+		ret
+	}
+}
+
 // Call ////////////////////////////////////////////////////////////////////////
 
 /// Wraps a node tagged [`Syn::CallExpr`].
@@ -158,7 +461,17 @@ pub struct CallExpr(SyntaxNode);
 
 simple_astnode!(Syn, CallExpr, Syn::CallExpr);
 
-impl CallExpr {}
+impl CallExpr {
+	/// The expression being called.
+	pub fn callee(&self) -> AstResult<Expr> {
+		Expr::cast(self.0.first_child().ok_or(AstError::Missing)?).ok_or(AstError::Incorrect)
+	}
+
+	/// Yields each argument passed to [`Self::callee`].
+	pub fn args(&self) -> impl Iterator<Item = Expr> {
+		self.0.children().skip(1).filter_map(Expr::cast)
+	}
+}
 
 // Class ///////////////////////////////////////////////////////////////////////
 
@@ -212,6 +525,17 @@ pub struct ForExpr(SyntaxNode);
 
 simple_astnode!(Syn, ForExpr, Syn::ForExpr);
 
+impl ForExpr {
+	/// The expression being iterated over.
+	pub fn condition(&self) -> AstResult<Expr> {
+		Expr::cast(self.0.first_child().ok_or(AstError::Missing)?).ok_or(AstError::Incorrect)
+	}
+
+	pub fn body(&self) -> AstResult<Expr> {
+		Expr::cast(self.0.last_child().ok_or(AstError::Missing)?).ok_or(AstError::Incorrect)
+	}
+}
+
 // Function ////////////////////////////////////////////////////////////////////
 
 /// Wraps a node tagged [`Syn::FunctionExpr`].
@@ -333,6 +657,22 @@ pub enum PrefixOp {
 	Tilde,
 }
 
+impl PrefixOp {
+	/// Always [`MAX_PRECEDENCE`]; every prefix operator binds tighter than
+	/// any [`BinOp`].
+	#[must_use]
+	pub fn precedence(self) -> u8 {
+		MAX_PRECEDENCE
+	}
+
+	/// Always [`Assoc::None`]; a prefix operator has only one operand, so
+	/// associativity does not apply.
+	#[must_use]
+	pub fn associativity(self) -> Assoc {
+		Assoc::None
+	}
+}
+
 // Struct //////////////////////////////////////////////////////////////////////
 
 /// Wraps a node tagged [`Syn::StructExpr`].
@@ -341,6 +681,13 @@ pub struct StructExpr(SyntaxNode);
 
 simple_astnode!(Syn, StructExpr, Syn::StructExpr);
 
+impl StructExpr {
+	/// Yields each field initializer in this struct expression.
+	pub fn members(&self) -> impl Iterator<Item = Expr> {
+		self.0.children().filter_map(Expr::cast)
+	}
+}
+
 // Switch //////////////////////////////////////////////////////////////////////
 
 /// Wraps a node tagged [`Syn::SwitchExpr`].
@@ -349,6 +696,13 @@ pub struct SwitchExpr(SyntaxNode);
 
 simple_astnode!(Syn, SwitchExpr, Syn::SwitchExpr);
 
+impl SwitchExpr {
+	/// Yields each arm in this switch expression.
+	pub fn members(&self) -> impl Iterator<Item = Expr> {
+		self.0.children().filter_map(Expr::cast)
+	}
+}
+
 // Type ////////////////////////////////////////////////////////////////////////
 
 /// Each variant wraps a node tagged [`Syn::TypeExpr`].
@@ -527,3 +881,588 @@ simple_astnode!(Syn, VariantExpr, Syn::VariantExpr);
 pub struct WhileExpr(SyntaxNode);
 
 simple_astnode!(Syn, WhileExpr, Syn::WhileExpr);
+
+impl WhileExpr {
+	pub fn condition(&self) -> AstResult<Expr> {
+		Expr::cast(self.0.first_child().ok_or(AstError::Missing)?).ok_or(AstError::Incorrect)
+	}
+
+	pub fn body(&self) -> AstResult<Expr> {
+		Expr::cast(self.0.last_child().ok_or(AstError::Missing)?).ok_or(AstError::Incorrect)
+	}
+}
+
+// Make ////////////////////////////////////////////////////////////////////////
+
+/// Constructors for synthesizing expression AST nodes.
+///
+/// Mirrors the approach of `doomfront`'s ZScript `ast::make`: each function
+/// assembles a well-formed [`GreenNode`] directly out of its arguments'
+/// already-parsed syntax and hands back the corresponding typed wrapper,
+/// so tooling can build or rewrite expressions without resorting to string
+/// concatenation and a re-parse.
+pub mod make {
+	use doomfront::{
+		rowan::{ast::AstNode, GreenNode, GreenToken, NodeOrToken},
+		GreenElement,
+	};
+
+	use crate::Syn;
+
+	use super::{
+		ArrayExpr, BinExpr, BinOp, CallExpr, Expr, GroupExpr, IdentExpr, Literal, PrefixExpr,
+		PrefixOp, SyntaxNode,
+	};
+
+	/// Wraps `green` in a standalone red tree and casts it to `N`.
+	///
+	/// # Panics
+	/// Panics if `green`'s root kind does not match `N`; every function in
+	/// this module builds its green tree to satisfy this by construction.
+	#[must_use]
+	pub(super) fn cast<N: AstNode<Language = Syn>>(green: GreenNode) -> N {
+		N::cast(SyntaxNode::new_root(green)).expect("malformed synthesized node")
+	}
+
+	#[must_use]
+	fn token(kind: Syn, text: &str) -> GreenElement {
+		NodeOrToken::Token(GreenToken::new(kind.into(), text))
+	}
+
+	#[must_use]
+	fn node(expr: &Expr) -> GreenElement {
+		NodeOrToken::Node(expr.syntax().green().into_owned())
+	}
+
+	#[must_use]
+	fn bin_op_token(op: BinOp) -> (Syn, &'static str) {
+		match op {
+			BinOp::Add => (Syn::Plus, "+"),
+			BinOp::Sub => (Syn::Minus, "-"),
+			BinOp::Mul => (Syn::Asterisk, "*"),
+			BinOp::Div => (Syn::Slash, "/"),
+			BinOp::Rem => (Syn::Percent, "%"),
+			BinOp::Eq => (Syn::Eq2, "=="),
+			BinOp::NotEq => (Syn::BangEq, "!="),
+			BinOp::Lt => (Syn::AngleL, "<"),
+			BinOp::Gt => (Syn::AngleR, ">"),
+			BinOp::LtEq => (Syn::AngleLEq, "<="),
+			BinOp::GtEq => (Syn::AngleREq, ">="),
+			BinOp::LogicAnd => (Syn::Ampersand2, "&&"),
+			BinOp::LogicOr => (Syn::Pipe2, "||"),
+			BinOp::BitAnd => (Syn::Ampersand, "&"),
+			BinOp::BitOr => (Syn::Pipe, "|"),
+			BinOp::BitXor => (Syn::Caret, "^"),
+			BinOp::Shl => (Syn::AngleL2, "<<"),
+			BinOp::Shr => (Syn::AngleR2, ">>"),
+			BinOp::Assign => (Syn::Eq, "="),
+			BinOp::AddAssign => (Syn::PlusEq, "+="),
+			BinOp::SubAssign => (Syn::MinusEq, "-="),
+			BinOp::MulAssign => (Syn::AsteriskEq, "*="),
+			BinOp::DivAssign => (Syn::SlashEq, "/="),
+			BinOp::RemAssign => (Syn::PercentEq, "%="),
+			BinOp::AndAssign => (Syn::AmpersandEq, "&="),
+			BinOp::OrAssign => (Syn::PipeEq, "|="),
+			BinOp::XorAssign => (Syn::CaretEq, "^="),
+			BinOp::ShlAssign => (Syn::AngleL2Eq, "<<="),
+			BinOp::ShrAssign => (Syn::AngleR2Eq, ">>="),
+		}
+	}
+
+	/// Builds a [`Syn::BinExpr`] node joining `lhs` and `rhs` with `op`.
+	#[must_use]
+	pub fn bin_expr(lhs: &Expr, op: BinOp, rhs: &Expr) -> BinExpr {
+		let (op_kind, op_text) = bin_op_token(op);
+
+		let gnode = GreenNode::new(
+			Syn::BinExpr.into(),
+			[node(lhs), token(op_kind, op_text), node(rhs)],
+		);
+
+		cast(gnode)
+	}
+
+	/// Builds a [`Syn::CallExpr`] node invoking `callee` with `args`, each
+	/// separated by a comma and the whole list wrapped in parentheses, so
+	/// the result round-trips to valid source text (see [`CallExpr::args`],
+	/// which skips exactly this punctuation when reading one back).
+	#[must_use]
+	pub fn call_expr(callee: &Expr, args: impl IntoIterator<Item = Expr>) -> CallExpr {
+		let mut children = vec![node(callee), token(Syn::ParenL, "(")];
+
+		for (i, arg) in args.into_iter().enumerate() {
+			if i > 0 {
+				children.push(token(Syn::Comma, ","));
+			}
+
+			children.push(node(&arg));
+		}
+
+		children.push(token(Syn::ParenR, ")"));
+
+		let gnode = GreenNode::new(Syn::CallExpr.into(), children);
+		cast(gnode)
+	}
+
+	/// Builds a [`Syn::ArrayExpr`] node out of `elements`, each separated by
+	/// a comma and the whole list wrapped in brackets, so the result
+	/// round-trips to valid source text (see [`ArrayExpr::elements`], which
+	/// skips exactly this punctuation when reading one back).
+	#[must_use]
+	pub fn array_expr(elements: impl IntoIterator<Item = Expr>) -> ArrayExpr {
+		let mut children = vec![token(Syn::BracketL, "[")];
+
+		for (i, elem) in elements.into_iter().enumerate() {
+			if i > 0 {
+				children.push(token(Syn::Comma, ","));
+			}
+
+			children.push(node(&elem));
+		}
+
+		children.push(token(Syn::BracketR, "]"));
+
+		let gnode = GreenNode::new(Syn::ArrayExpr.into(), children);
+		cast(gnode)
+	}
+
+	/// Builds a [`Syn::IdentExpr`] node out of a bare (non-dotted) identifier.
+	#[must_use]
+	pub fn ident_expr(name: &str) -> IdentExpr {
+		let gnode = GreenNode::new(Syn::IdentExpr.into(), [token(Syn::Ident, name)]);
+		cast(gnode)
+	}
+
+	/// Builds a [`Syn::Literal`] node out of a single pre-rendered token, e.g.
+	/// `(Syn::IntLit, "1")` or `(Syn::KwTrue, "true")`.
+	#[must_use]
+	pub fn literal(kind: Syn, text: &str) -> Literal {
+		let gnode = GreenNode::new(Syn::Literal.into(), [token(kind, text)]);
+		cast(gnode)
+	}
+
+	/// Builds a [`Syn::GroupExpr`] node, i.e. a parenthesized `inner`.
+	#[must_use]
+	pub fn group_expr(inner: &Expr) -> GroupExpr {
+		let gnode = GreenNode::new(
+			Syn::GroupExpr.into(),
+			[
+				token(Syn::ParenL, "("),
+				node(inner),
+				token(Syn::ParenR, ")"),
+			],
+		);
+
+		cast(gnode)
+	}
+
+	/// Builds a [`Syn::PrefixExpr`] node applying `op` to `operand`.
+	#[must_use]
+	pub fn prefix_expr(op: PrefixOp, operand: &Expr) -> PrefixExpr {
+		let (op_kind, op_text) = match op {
+			PrefixOp::Bang => (Syn::Bang, "!"),
+			PrefixOp::Minus => (Syn::Minus, "-"),
+			PrefixOp::Tilde => (Syn::Tilde, "~"),
+		};
+
+		let gnode = GreenNode::new(Syn::PrefixExpr.into(), [token(op_kind, op_text), node(operand)]);
+		cast(gnode)
+	}
+}
+
+// Edit ////////////////////////////////////////////////////////////////////////
+//
+// Non-destructive editing via green-tree splicing, analogous to `make` on
+// the other end: each method here leaves `self` unchanged, rebuilds just the
+// green children of the node being edited and hands back a new typed node
+// rooted at the result,
+// reusing every unaffected subtree by green-tree identity. This lets an
+// assist compose a new child with `make`, then splice it in here, rather
+// than falling back to a text edit and a re-parse.
+
+/// Identifies which side of a [`BinExpr`] to act on; see
+/// [`BinExpr::replace_operand`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+	Left,
+	Right,
+}
+
+impl BinExpr {
+	/// Replaces the operand on `side`, leaving the operator and the other
+	/// operand untouched.
+	#[must_use]
+	pub fn replace_operand(&self, side: Side, new: Expr) -> Self {
+		let children: Vec<_> = self.0.children_with_tokens().collect();
+		let mut green_children = to_green_children(&children);
+
+		let mut seen = 0;
+
+		for (i, elem) in children.iter().enumerate() {
+			if elem.as_node().is_none() {
+				continue;
+			}
+
+			let is_target = match side {
+				Side::Left => seen == 0,
+				Side::Right => seen == 1,
+			};
+
+			if is_target {
+				green_children[i] = NodeOrToken::Node(new.syntax().green().into_owned());
+				break;
+			}
+
+			seen += 1;
+		}
+
+		let gnode = GreenNode::new(Syn::BinExpr.into(), green_children);
+		make::cast(gnode)
+	}
+}
+
+impl CallExpr {
+	/// Inserts `arg` so that it becomes the `index`th argument (the callee
+	/// itself is not counted), adding a separating comma so the result stays
+	/// well-formed.
+	///
+	/// # Panics
+	/// Panics if `index` is greater than the number of arguments already
+	/// present.
+	#[must_use]
+	pub fn insert_arg(&self, index: usize, arg: Expr) -> Self {
+		let children: Vec<_> = self.0.children_with_tokens().collect();
+		let mut green_children = to_green_children(&children);
+
+		let existing_arg_count = children.iter().filter(|elem| elem.as_node().is_some()).count() - 1;
+		assert!(
+			index <= existing_arg_count,
+			"`index` is out of bounds for this call's arguments"
+		);
+
+		let mut seen = 0;
+		let mut insert_before = None;
+
+		for (i, elem) in children.iter().enumerate() {
+			if elem.as_node().is_none() {
+				continue;
+			}
+
+			if seen > 0 && (seen - 1) == index {
+				insert_before = Some(i);
+				break;
+			}
+
+			seen += 1;
+		}
+
+		let new_arg = NodeOrToken::Node(arg.syntax().green().into_owned());
+
+		if let Some(i) = insert_before {
+			// Inserting before an existing argument: follow the new one with
+			// a comma so it doesn't run into that argument's text.
+			green_children.splice(i..i, [new_arg, comma_token()]);
+		} else {
+			// Appending past the last existing argument: the insert point
+			// has to be the closing `)` token, not `green_children.len()`
+			// (the end of the *whole* children list) — that would land the
+			// new argument after the closing paren instead of inside it.
+			let paren_r = children
+				.iter()
+				.rposition(|elem| matches!(elem, NodeOrToken::Token(t) if t.kind() == Syn::ParenR))
+				.expect("a well-formed `CallExpr` always has a closing `)`");
+
+			if existing_arg_count > 0 {
+				green_children.splice(paren_r..paren_r, [comma_token(), new_arg]);
+			} else {
+				green_children.insert(paren_r, new_arg);
+			}
+		}
+
+		let gnode = GreenNode::new(Syn::CallExpr.into(), green_children);
+		make::cast(gnode)
+	}
+
+	/// Removes the `index`th argument (the callee itself is not counted),
+	/// also removing whichever separating comma sits next to it so the
+	/// result stays well-formed.
+	///
+	/// # Panics
+	/// Panics if `index` is out of bounds for this call's arguments.
+	#[must_use]
+	pub fn remove_arg(&self, index: usize) -> Self {
+		let children: Vec<_> = self.0.children_with_tokens().collect();
+		let mut green_children = to_green_children(&children);
+
+		let mut seen = 0;
+		let mut remove_at = None;
+
+		for (i, elem) in children.iter().enumerate() {
+			if elem.as_node().is_none() {
+				continue;
+			}
+
+			if seen > 0 && (seen - 1) == index {
+				remove_at = Some(i);
+				break;
+			}
+
+			seen += 1;
+		}
+
+		let remove_at = remove_at.expect("`index` is out of bounds for this call's arguments");
+
+		// Prefer taking the comma that follows (so removing the first or a
+		// middle argument leaves no leading comma); fall back to the comma
+		// that precedes (removing the last argument) when there isn't one.
+		let is_comma =
+			|elem: &doomfront::rowan::SyntaxElement<Syn>| matches!(elem, NodeOrToken::Token(t) if t.kind() == Syn::Comma);
+
+		if children.get(remove_at + 1).is_some_and(is_comma) {
+			green_children.remove(remove_at + 1);
+			green_children.remove(remove_at);
+		} else if remove_at > 0 && children.get(remove_at - 1).is_some_and(is_comma) {
+			green_children.remove(remove_at);
+			green_children.remove(remove_at - 1);
+		} else {
+			green_children.remove(remove_at);
+		}
+
+		let gnode = GreenNode::new(Syn::CallExpr.into(), green_children);
+		make::cast(gnode)
+	}
+}
+
+impl GroupExpr {
+	/// Discards the enclosing parentheses, returning the inner expression on
+	/// its own; pair this with a parent's own replace/splice method (e.g.
+	/// [`BinExpr::replace_operand`]) to eliminate redundant grouping.
+	#[must_use]
+	pub fn unwrap(&self) -> Expr {
+		self.inner()
+			.expect("a well-formed `GroupExpr` always has an inner expression")
+	}
+}
+
+impl PrefixExpr {
+	/// Replaces this prefix expression's operand, leaving the operator
+	/// untouched.
+	#[must_use]
+	pub fn with_operand(&self, new: Expr) -> Self {
+		let children: Vec<_> = self.0.children_with_tokens().collect();
+		let mut green_children = to_green_children(&children);
+
+		let ix = children
+			.iter()
+			.rposition(|elem| elem.as_node().is_some())
+			.expect("a `PrefixExpr` always has an operand node");
+
+		green_children[ix] = NodeOrToken::Node(new.syntax().green().into_owned());
+
+		let gnode = GreenNode::new(Syn::PrefixExpr.into(), green_children);
+		make::cast(gnode)
+	}
+}
+
+/// A bare `,` green token, for splicing a separator in between two spliced-in
+/// argument/element nodes (see [`CallExpr::insert_arg`]).
+#[must_use]
+fn comma_token() -> doomfront::GreenElement {
+	NodeOrToken::Token(doomfront::rowan::GreenToken::new(Syn::Comma.into(), ","))
+}
+
+fn to_green_children(
+	children: &[doomfront::rowan::SyntaxElement<Syn>],
+) -> Vec<doomfront::GreenElement> {
+	children
+		.iter()
+		.map(|elem| match elem {
+			NodeOrToken::Node(n) => NodeOrToken::Node(n.green().into_owned()),
+			NodeOrToken::Token(t) => NodeOrToken::Token(t.green().to_owned()),
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use doomfront::rowan::GreenToken;
+
+	use super::*;
+
+	fn ws() -> doomfront::GreenElement {
+		NodeOrToken::Token(GreenToken::new(Syn::Whitespace.into(), " "))
+	}
+
+	/// Regression test for the bug fixed alongside this test: `operator()`
+	/// used to grab the first *token* child unconditionally, which is the
+	/// whitespace before the operator for any ordinary parse of e.g. `a + b`,
+	/// not the operator itself.
+	#[test]
+	fn bin_expr_operator_skips_surrounding_trivia() {
+		let lhs = Expr::Ident(make::ident_expr("a"));
+		let rhs = Expr::Ident(make::ident_expr("b"));
+
+		let gnode = GreenNode::new(
+			Syn::BinExpr.into(),
+			[
+				NodeOrToken::Node(lhs.syntax().green().into_owned()),
+				ws(),
+				NodeOrToken::Token(GreenToken::new(Syn::Plus.into(), "+")),
+				ws(),
+				NodeOrToken::Node(rhs.syntax().green().into_owned()),
+			],
+		);
+
+		let bin: BinExpr = make::cast(gnode);
+		let (token, op) = bin.operator();
+
+		assert_eq!(token.kind(), Syn::Plus);
+		assert_eq!(op, BinOp::Add);
+	}
+
+	#[test]
+	fn make_call_expr_round_trips() {
+		let callee = Expr::Ident(make::ident_expr("foo"));
+		let args = vec![
+			Expr::Ident(make::ident_expr("a")),
+			Expr::Ident(make::ident_expr("b")),
+		];
+
+		let call = make::call_expr(&callee, args);
+
+		assert_eq!(call.syntax().text().to_string(), "foo(a,b)");
+		let args: Vec<_> = call
+			.args()
+			.map(|e| e.syntax().text().to_string())
+			.collect();
+		assert_eq!(args, ["a", "b"]);
+	}
+
+	#[test]
+	fn make_array_expr_round_trips() {
+		let elements = vec![
+			Expr::Ident(make::ident_expr("a")),
+			Expr::Ident(make::ident_expr("b")),
+		];
+
+		let array = make::array_expr(elements);
+
+		assert_eq!(array.syntax().text().to_string(), "[a,b]");
+		let elements: Vec<_> = array
+			.elements()
+			.map(|e| e.syntax().text().to_string())
+			.collect();
+		assert_eq!(elements, ["a", "b"]);
+	}
+
+	#[test]
+	fn call_expr_insert_arg_into_empty() {
+		let callee = Expr::Ident(make::ident_expr("foo"));
+		let call = make::call_expr(&callee, []);
+
+		let appended = call.insert_arg(0, Expr::Ident(make::ident_expr("a")));
+
+		assert_eq!(appended.syntax().text().to_string(), "foo(a)");
+	}
+
+	#[test]
+	fn call_expr_insert_arg_appends_before_closing_paren() {
+		let callee = Expr::Ident(make::ident_expr("foo"));
+		let call = make::call_expr(&callee, [Expr::Ident(make::ident_expr("a"))]);
+
+		let appended = call.insert_arg(1, Expr::Ident(make::ident_expr("b")));
+
+		assert_eq!(appended.syntax().text().to_string(), "foo(a,b)");
+		let args: Vec<_> = appended
+			.args()
+			.map(|e| e.syntax().text().to_string())
+			.collect();
+		assert_eq!(args, ["a", "b"]);
+	}
+
+	#[test]
+	fn call_expr_insert_arg_in_middle() {
+		let callee = Expr::Ident(make::ident_expr("foo"));
+		let call = make::call_expr(
+			&callee,
+			[
+				Expr::Ident(make::ident_expr("a")),
+				Expr::Ident(make::ident_expr("c")),
+			],
+		);
+
+		let inserted = call.insert_arg(1, Expr::Ident(make::ident_expr("b")));
+
+		let args: Vec<_> = inserted
+			.args()
+			.map(|e| e.syntax().text().to_string())
+			.collect();
+		assert_eq!(args, ["a", "b", "c"]);
+	}
+
+	#[test]
+	fn call_expr_remove_arg_first() {
+		let callee = Expr::Ident(make::ident_expr("foo"));
+		let call = make::call_expr(
+			&callee,
+			[
+				Expr::Ident(make::ident_expr("a")),
+				Expr::Ident(make::ident_expr("b")),
+				Expr::Ident(make::ident_expr("c")),
+			],
+		);
+
+		let removed = call.remove_arg(0);
+
+		assert_eq!(removed.syntax().text().to_string(), "foo(b,c)");
+		let args: Vec<_> = removed
+			.args()
+			.map(|e| e.syntax().text().to_string())
+			.collect();
+		assert_eq!(args, ["b", "c"]);
+	}
+
+	#[test]
+	fn call_expr_remove_arg_middle() {
+		let callee = Expr::Ident(make::ident_expr("foo"));
+		let call = make::call_expr(
+			&callee,
+			[
+				Expr::Ident(make::ident_expr("a")),
+				Expr::Ident(make::ident_expr("b")),
+				Expr::Ident(make::ident_expr("c")),
+			],
+		);
+
+		let removed = call.remove_arg(1);
+
+		assert_eq!(removed.syntax().text().to_string(), "foo(a,c)");
+		let args: Vec<_> = removed
+			.args()
+			.map(|e| e.syntax().text().to_string())
+			.collect();
+		assert_eq!(args, ["a", "c"]);
+	}
+
+	#[test]
+	fn call_expr_remove_arg_last() {
+		let callee = Expr::Ident(make::ident_expr("foo"));
+		let call = make::call_expr(
+			&callee,
+			[
+				Expr::Ident(make::ident_expr("a")),
+				Expr::Ident(make::ident_expr("b")),
+				Expr::Ident(make::ident_expr("c")),
+			],
+		);
+
+		let removed = call.remove_arg(2);
+
+		assert_eq!(removed.syntax().text().to_string(), "foo(a,b)");
+		let args: Vec<_> = removed
+			.args()
+			.map(|e| e.syntax().text().to_string())
+			.collect();
+		assert_eq!(args, ["a", "b"]);
+	}
+}
@@ -0,0 +1,253 @@
+//! WASM codegen: walking a [`Module`] of already-resolved functions to
+//! build WAT (WebAssembly text format) text via [`emit_wat`], then
+//! assembling that text through the `wat`/`wasmparser` crates via
+//! [`assemble`] (wrapped end-to-end by [`emit_wasm`]).
+//!
+//! [`Module`] is deliberately small — just enough of a resolved-function
+//! representation (locals, straight-line arithmetic, returns) for
+//! [`emit_wat`] to walk. VZScript has no semantic IR yet (no
+//! type-resolution/`sema` pass is declared anywhere in this crate), so
+//! nothing upstream builds a [`Module`] today; whatever frontend pass
+//! eventually resolves a parsed VZScript file should target this type
+//! rather than hand-assembling WAT text itself.
+
+use crate::{Error, Version};
+
+const VERSION_SECTION_NAME: &str = "vzscript-version";
+
+/// A WASM value type, covering everything [`Instr`] can produce or consume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValType {
+	I32,
+	I64,
+	F32,
+	F64,
+}
+
+impl ValType {
+	#[must_use]
+	fn wat(self) -> &'static str {
+		match self {
+			Self::I32 => "i32",
+			Self::I64 => "i64",
+			Self::F32 => "f32",
+			Self::F64 => "f64",
+		}
+	}
+}
+
+/// One instruction in a [`Function`] body. Intentionally limited to what a
+/// straight-line arithmetic expression needs; control flow and calls into
+/// engine-registered host functions are left for when a real semantic pass
+/// exists to emit them.
+#[derive(Debug, Clone, Copy)]
+pub enum Instr {
+	I32Const(i32),
+	I64Const(i64),
+	F32Const(f32),
+	F64Const(f64),
+	LocalGet(u32),
+	LocalSet(u32),
+	Add(ValType),
+	Sub(ValType),
+	Mul(ValType),
+	/// For [`ValType::I32`]/[`ValType::I64`], always emitted as the signed
+	/// variant (`div_s`), since VZScript's resolved IR has no unsigned
+	/// integer type to distinguish it from; floats use plain `div`.
+	Div(ValType),
+}
+
+/// A single resolved VZScript function, ready to be walked by [`emit_wat`].
+#[derive(Debug, Clone)]
+pub struct Function {
+	pub name: String,
+	pub params: Vec<ValType>,
+	pub results: Vec<ValType>,
+	/// Locals declared beyond `params`, in declaration order.
+	pub locals: Vec<ValType>,
+	pub body: Vec<Instr>,
+	/// Whether this function is exported under its own name; VZScript
+	/// entry points (`comptime`/`const` functions callable from outside the
+	/// module) set this.
+	pub exported: bool,
+}
+
+/// A resolved VZScript module: just its function list, for now. See the
+/// module docs for why nothing upstream builds one of these yet.
+#[derive(Debug, Clone, Default)]
+pub struct Module {
+	pub functions: Vec<Function>,
+}
+
+/// Walks `module`, building WAT text for every function (and its export, if
+/// [`Function::exported`]).
+#[must_use]
+pub fn emit_wat(module: &Module) -> String {
+	let mut out = String::from("(module");
+
+	for func in &module.functions {
+		out.push_str("\n  (func $");
+		out.push_str(&func.name);
+
+		for param in &func.params {
+			out.push_str(" (param ");
+			out.push_str(param.wat());
+			out.push(')');
+		}
+
+		for result in &func.results {
+			out.push_str(" (result ");
+			out.push_str(result.wat());
+			out.push(')');
+		}
+
+		for local in &func.locals {
+			out.push_str(" (local ");
+			out.push_str(local.wat());
+			out.push(')');
+		}
+
+		for instr in &func.body {
+			out.push_str("\n    ");
+			emit_instr(&mut out, *instr);
+		}
+
+		out.push(')');
+
+		if func.exported {
+			out.push_str(&format!(
+				"\n  (export \"{}\" (func ${}))",
+				func.name, func.name
+			));
+		}
+	}
+
+	out.push_str("\n)");
+	out
+}
+
+fn emit_instr(out: &mut String, instr: Instr) {
+	match instr {
+		Instr::I32Const(v) => out.push_str(&format!("i32.const {v}")),
+		Instr::I64Const(v) => out.push_str(&format!("i64.const {v}")),
+		Instr::F32Const(v) => out.push_str(&format!("f32.const {v}")),
+		Instr::F64Const(v) => out.push_str(&format!("f64.const {v}")),
+		Instr::LocalGet(ix) => out.push_str(&format!("local.get {ix}")),
+		Instr::LocalSet(ix) => out.push_str(&format!("local.set {ix}")),
+		Instr::Add(ty) => out.push_str(&format!("{}.add", ty.wat())),
+		Instr::Sub(ty) => out.push_str(&format!("{}.sub", ty.wat())),
+		Instr::Mul(ty) => out.push_str(&format!("{}.mul", ty.wat())),
+		Instr::Div(ty) => {
+			let suffix = match ty {
+				ValType::I32 | ValType::I64 => "div_s",
+				ValType::F32 | ValType::F64 => "div",
+			};
+
+			out.push_str(&format!("{}.{suffix}", ty.wat()));
+		}
+	}
+}
+
+/// Walks `module` via [`emit_wat`] and assembles the result through
+/// [`assemble`], stamping it with `version`.
+pub fn emit_wasm(module: &Module, version: Version) -> Result<Vec<u8>, Error> {
+	assemble(&emit_wat(module), version)
+}
+
+/// Assembles `wat` into a `.wasm` binary using the `wat` crate, validates it
+/// with `wasmparser`, and stamps it with a custom section named
+/// `"vzscript-version"` recording `version`, so a loader can check spec
+/// compatibility before running the module.
+pub fn assemble(wat: &str, version: Version) -> Result<Vec<u8>, Error> {
+	let mut bytes = wat::parse_str(wat).map_err(Error::Wat)?;
+	bytes.extend_from_slice(&version_section(version));
+
+	wasmparser::validate(&bytes).map_err(Error::WasmValidate)?;
+
+	Ok(bytes)
+}
+
+/// Builds a custom WASM section (id `0`) carrying `version` under the name
+/// [`VERSION_SECTION_NAME`].
+fn version_section(version: Version) -> Vec<u8> {
+	let mut payload = Vec::new();
+	write_uleb128(&mut payload, VERSION_SECTION_NAME.len() as u64);
+	payload.extend_from_slice(VERSION_SECTION_NAME.as_bytes());
+	payload.extend_from_slice(&version.major.to_le_bytes());
+	payload.extend_from_slice(&version.minor.to_le_bytes());
+	payload.extend_from_slice(&version.rev.to_le_bytes());
+
+	let mut section = vec![0u8];
+	write_uleb128(&mut section, payload.len() as u64);
+	section.extend_from_slice(&payload);
+	section
+}
+
+fn write_uleb128(out: &mut Vec<u8>, mut value: u64) {
+	loop {
+		let byte = (value & 0x7f) as u8;
+		value >>= 7;
+
+		if value == 0 {
+			out.push(byte);
+			break;
+		}
+
+		out.push(byte | 0x80);
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn emit_wat_smoke() {
+		let module = Module {
+			functions: vec![Function {
+				name: "add_one".to_string(),
+				params: vec![ValType::I32],
+				results: vec![ValType::I32],
+				locals: vec![],
+				body: vec![
+					Instr::LocalGet(0),
+					Instr::I32Const(1),
+					Instr::Add(ValType::I32),
+				],
+				exported: true,
+			}],
+		};
+
+		let wat = emit_wat(&module);
+
+		assert!(wat.contains("(func $add_one"));
+		assert!(wat.contains("local.get 0"));
+		assert!(wat.contains("i32.add"));
+		assert!(wat.contains("(export \"add_one\" (func $add_one))"));
+
+		wat::parse_str(&wat).expect("emit_wat produced invalid WAT");
+	}
+
+	#[test]
+	fn emit_wasm_round_trip() {
+		let module = Module {
+			functions: vec![Function {
+				name: "answer".to_string(),
+				params: vec![],
+				results: vec![ValType::I32],
+				locals: vec![],
+				body: vec![Instr::I32Const(42)],
+				exported: true,
+			}],
+		};
+
+		let version = Version {
+			major: 0,
+			minor: 0,
+			rev: 0,
+		};
+
+		let bytes = emit_wasm(&module, version).expect("a well-formed module should assemble");
+		wasmparser::validate(&bytes).expect("emit_wasm produced invalid WASM");
+	}
+}
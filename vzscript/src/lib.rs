@@ -6,15 +6,32 @@
 //! as ZScript did.
 //!
 //! [ZScript]: https://zdoom.org/wiki/ZScript
-
+//!
+//! ## Blocked on the semantic IR
+//!
+//! There's no real semantic IR here yet (no type-resolution/`sema` pass is
+//! declared anywhere in this crate), so nothing upstream of codegen ever
+//! produces a [`codegen::Module`] today. [`codegen`] itself is complete: it
+//! declares a small resolved-function IR ([`codegen::Module`],
+//! [`codegen::Function`], [`codegen::Instr`]) and [`codegen::emit_wasm`]
+//! walks it to WAT text and assembles that to a validated `.wasm` binary
+//! stamped with the targeted [`Version`]. Whatever pass eventually resolves
+//! a parsed VZScript file should target [`codegen::Module`] rather than
+//! hand-assembling WAT text itself.
+
+mod codegen;
+mod semver;
 mod syn;
 
-pub use self::syn::Syn;
+pub use self::{
+	semver::{resolve, VersionReq},
+	syn::Syn,
+};
 
 /// Each library is declared as belonging to a version of the VZScript specification.
 ///
 /// The specification is versioned as per [Semantic Versioning](https://semver.org/).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Version {
 	pub major: u16,
 	pub minor: u16,
@@ -51,26 +68,33 @@ impl Version {
 	/// Check if this version is equal to an existing VZScript spec version.
 	#[must_use]
 	pub fn is_valid(&self) -> bool {
-		use std::collections::HashSet;
+		known_versions().contains(self)
+	}
+}
 
-		use once_cell::sync::Lazy;
+/// The table of VZScript specification versions recognized by this
+/// toolchain, shared by [`Version::is_valid`] and [`semver::resolve`].
+fn known_versions() -> &'static [Version] {
+	use once_cell::sync::Lazy;
 
-		static VERSIONS: Lazy<HashSet<Version>> = Lazy::new(|| {
-			HashSet::from([Version {
-				major: 0,
-				minor: 0,
-				rev: 0,
-			}])
-		});
+	static VERSIONS: Lazy<Vec<Version>> = Lazy::new(|| {
+		vec![Version {
+			major: 0,
+			minor: 0,
+			rev: 0,
+		}]
+	});
 
-		VERSIONS.contains(self)
-	}
+	&VERSIONS
 }
 
 #[derive(Debug)]
 pub enum Error {
 	/// Tried to parse a SemVer string without any numbers or periods in it.
 	EmptyVersion,
+	/// Tried to parse a [`VersionReq`](crate::VersionReq) with no comparators
+	/// in it (e.g. an empty string, or one made entirely of commas).
+	EmptyVersionReq,
 	SemVerParse(std::num::ParseIntError),
 	/// Tried to retrieve a function from a module and found it, but failed to
 	/// pass the generic arguments matching its signature.
@@ -78,6 +102,13 @@ pub enum Error {
 	/// Tried to retrieve a symbol from a module using an identifier that didn't
 	/// resolve to anything.
 	UnknownIdent,
+	/// No known VZScript spec [`Version`] satisfies every requirement passed
+	/// to [`semver::resolve`](crate::resolve).
+	UnsatisfiableVersionReq(Vec<VersionReq>),
+	/// Failed to assemble WAT (WebAssembly text format) source to a binary.
+	Wat(wat::Error),
+	/// Assembled WASM bytes failed `wasmparser` validation.
+	WasmValidate(wasmparser::BinaryReaderError),
 }
 
 impl std::error::Error for Error {}
@@ -86,6 +117,7 @@ impl std::fmt::Display for Error {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Self::EmptyVersion => write!(f, "Tried to parse an empty version string."),
+			Self::EmptyVersionReq => write!(f, "Tried to parse an empty version requirement."),
 			Self::SemVerParse(err) => err.fmt(f),
 			Self::SignatureMismatch => {
 				write!(
@@ -94,6 +126,16 @@ impl std::fmt::Display for Error {
 				)
 			}
 			Self::UnknownIdent => write!(f, "An identifier was not found in the symbol table."),
+			Self::UnsatisfiableVersionReq(reqs) => write!(
+				f,
+				"no known VZScript spec version satisfies all of: {}",
+				reqs.iter()
+					.map(|r| format!("{r:?}"))
+					.collect::<Vec<_>>()
+					.join(", ")
+			),
+			Self::Wat(err) => write!(f, "failed to assemble WAT source: {err}"),
+			Self::WasmValidate(err) => write!(f, "assembled WASM module failed validation: {err}"),
 		}
 	}
 }
@@ -0,0 +1,170 @@
+//! Semantic-version range matching and dependency resolution, so a
+//! library manifest can declare something like "this library requires
+//! spec 0.1 or any compatible later release" instead of pinning an exact
+//! [`Version`].
+
+use std::str::FromStr;
+
+use crate::{Error, Version};
+
+/// A parsed semantic-version range, e.g. `^0.2`, `>=0.1, <0.3`, or `0.2.*`.
+///
+/// Comma-separated comparators are ANDed together, the same way Cargo's own
+/// dependency version requirements work.
+#[derive(Debug, Clone)]
+pub struct VersionReq {
+	comparators: Vec<Comparator>,
+}
+
+impl VersionReq {
+	/// Returns `true` if every comparator in this range admits `version`.
+	#[must_use]
+	pub fn matches(&self, version: Version) -> bool {
+		self.comparators.iter().all(|c| c.matches(version))
+	}
+}
+
+impl FromStr for VersionReq {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.split(',').all(|part| part.trim().is_empty()) {
+			return Err(Error::EmptyVersionReq);
+		}
+
+		let comparators = s
+			.split(',')
+			.map(|part| Comparator::parse(part.trim()))
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(Self { comparators })
+	}
+}
+
+/// Resolves `reqs` (e.g. one per declared library dependency) against the
+/// table of known VZScript spec versions, returning the highest version
+/// that satisfies all of them.
+pub fn resolve(reqs: &[VersionReq]) -> Result<Version, Error> {
+	crate::known_versions()
+		.iter()
+		.copied()
+		.filter(|v| reqs.iter().all(|r| r.matches(*v)))
+		.max()
+		.ok_or_else(|| Error::UnsatisfiableVersionReq(reqs.to_vec()))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Op {
+	Exact,
+	Wildcard,
+	Gte,
+	Gt,
+	Lte,
+	Lt,
+	Caret,
+	Tilde,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Comparator {
+	op: Op,
+	major: u16,
+	minor: Option<u16>,
+	rev: Option<u16>,
+}
+
+impl Comparator {
+	fn parse(s: &str) -> Result<Self, Error> {
+		let (op, rest) = if let Some(r) = s.strip_prefix(">=") {
+			(Op::Gte, r)
+		} else if let Some(r) = s.strip_prefix("<=") {
+			(Op::Lte, r)
+		} else if let Some(r) = s.strip_prefix('>') {
+			(Op::Gt, r)
+		} else if let Some(r) = s.strip_prefix('<') {
+			(Op::Lt, r)
+		} else if let Some(r) = s.strip_prefix('^') {
+			(Op::Caret, r)
+		} else if let Some(r) = s.strip_prefix('~') {
+			(Op::Tilde, r)
+		} else if let Some(r) = s.strip_prefix('=') {
+			(Op::Exact, r)
+		} else {
+			(Op::Caret, s)
+		};
+
+		let rest = rest.trim();
+		let has_wildcard = rest.contains('*');
+		let mut parts = rest.split('.');
+
+		let major = parts
+			.next()
+			.filter(|p| !p.is_empty())
+			.ok_or(Error::EmptyVersion)?
+			.parse::<u16>()
+			.map_err(Error::SemVerParse)?;
+
+		let minor = match parts.next() {
+			None | Some("*") => None,
+			Some(m) => Some(m.parse::<u16>().map_err(Error::SemVerParse)?),
+		};
+
+		let rev = match parts.next() {
+			None | Some("*") => None,
+			Some(r) => Some(r.parse::<u16>().map_err(Error::SemVerParse)?),
+		};
+
+		// A bare version with a `*` component (e.g. `0.2.*`) is a wildcard
+		// match, not Cargo's implicit-caret default.
+		let op = if matches!(op, Op::Caret) && has_wildcard {
+			Op::Wildcard
+		} else {
+			op
+		};
+
+		Ok(Self {
+			op,
+			major,
+			minor,
+			rev,
+		})
+	}
+
+	/// The lowest [`Version`] admitted by this comparator, with any omitted
+	/// component treated as `0`.
+	fn floor(&self) -> Version {
+		Version {
+			major: self.major,
+			minor: self.minor.unwrap_or(0),
+			rev: self.rev.unwrap_or(0),
+		}
+	}
+
+	fn matches(&self, v: Version) -> bool {
+		match self.op {
+			Op::Exact | Op::Wildcard => {
+				v.major == self.major
+					&& self.minor.map_or(true, |m| v.minor == m)
+					&& self.rev.map_or(true, |r| v.rev == r)
+			}
+			Op::Gte => v >= self.floor(),
+			Op::Gt => v > self.floor(),
+			Op::Lte => v <= self.floor(),
+			Op::Lt => v < self.floor(),
+			// `^0.y.z` only allows patch bumps within the matching minor,
+			// per SemVer's carve-out for pre-1.0 releases; `^x.y.z` (x >= 1)
+			// allows any later minor/patch within the same major.
+			Op::Caret => {
+				v >= self.floor()
+					&& if self.major == 0 {
+						v.minor == self.minor.unwrap_or(0)
+					} else {
+						v.major == self.major
+					}
+			}
+			// `~x.y.z` allows only patch bumps within the matching minor,
+			// regardless of major.
+			Op::Tilde => v >= self.floor() && v.major == self.major && v.minor == self.minor.unwrap_or(0),
+		}
+	}
+}